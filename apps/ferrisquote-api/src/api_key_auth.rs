@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use ferrisquote_auth::domain::{
+    entities::{
+        claims::{Claims, DEFAULT_ORGANIZATION_CLAIM, Subject},
+        identity::Identity,
+    },
+    error::AuthError,
+    ports::AuthRepository,
+};
+use ferrisquote_domain::{ApiKey, DomainError, domain::api_key::ports::ApiKeyService};
+
+/// Issuer recorded in the claims of API key callers.
+pub const API_KEY_ISSUER: &str = "ferrisquote:api-key";
+
+/// Authenticates the secret of an API key sent in `X-Api-Key`.
+///
+/// A valid key yields an `Identity::Client` named after the key, acting for the key's
+/// organization with the key's scopes as permissions.
+pub struct ApiKeyAuthRepository<AK> {
+    api_keys: Arc<AK>,
+}
+
+impl<AK> ApiKeyAuthRepository<AK> {
+    pub fn new(api_keys: Arc<AK>) -> Self {
+        Self { api_keys }
+    }
+}
+
+fn claims_for(api_key: ApiKey) -> Claims {
+    let mut extra = serde_json::Map::new();
+    extra.insert(
+        DEFAULT_ORGANIZATION_CLAIM.to_string(),
+        api_key.organization_id.into_string().into(),
+    );
+
+    Claims {
+        sub: Subject(api_key.id.to_string()),
+        iss: API_KEY_ISSUER.to_string(),
+        aud: None,
+        exp: api_key.expires_at.map(|at| at.timestamp()),
+        nbf: None,
+        email: None,
+        email_verified: false,
+        name: Some(api_key.name.clone()),
+        preferred_username: api_key.name.clone(),
+        given_name: None,
+        family_name: None,
+        scope: api_key.scopes.join(" "),
+        client_id: Some(api_key.name),
+        extra,
+    }
+}
+
+impl<AK: ApiKeyService> AuthRepository for ApiKeyAuthRepository<AK> {
+    async fn validate_token(&self, token: &str) -> Result<Claims, AuthError> {
+        let api_key = self
+            .api_keys
+            .authenticate(token)
+            .await
+            .map_err(|e| match e {
+                DomainError::Unauthorized { message } => AuthError::InvalidToken { message },
+                other => AuthError::Internal {
+                    message: other.to_string(),
+                },
+            })?;
+
+        Ok(claims_for(api_key))
+    }
+
+    async fn identity(&self, token: &str) -> Result<Identity, AuthError> {
+        Ok(self.validate_token(token).await?.into())
+    }
+}
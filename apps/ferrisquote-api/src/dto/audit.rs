@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    /// Only entries about this kind of entity (`flow`, `step`, `field`, `estimator`,
+    /// `estimator_variable`, `quote` or `share_link`)
+    pub entity: Option<String>,
+    /// Only entries about the entity with this id
+    pub entity_id: Option<String>,
+    /// 1-based page number (defaults to 1)
+    pub page: Option<u32>,
+    /// Entries per page, at most 100 (defaults to 20)
+    pub per_page: Option<u32>,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActorResponse {
+    /// `user`, `client` or `system`
+    pub kind: String,
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// A leaf of the entity that changed, keyed by dotted path.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditChangeResponse {
+    pub path: String,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub id: Uuid,
+    pub actor: ActorResponse,
+    pub entity: String,
+    pub entity_id: String,
+    /// `create`, `update` or `delete`
+    pub action: String,
+    /// The entity before the change (absent for a creation)
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    /// The entity after the change (absent for a deletion)
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    pub changes: Vec<AuditChangeResponse>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditPageResponse {
+    /// Newest first
+    pub entries: Vec<AuditEntryResponse>,
+    pub page: u32,
+    pub per_page: u32,
+    /// Number of matching entries across all pages
+    pub total: u64,
+}
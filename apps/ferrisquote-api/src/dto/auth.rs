@@ -0,0 +1,22 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityKindDto {
+    User,
+    Client,
+    Anonymous,
+}
+
+/// The authenticated caller.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IdentityResponse {
+    pub kind: IdentityKindDto,
+    pub id: Option<String>,
+    pub username: Option<String>,
+    pub roles: Vec<String>,
+    pub scopes: Vec<String>,
+    /// Organization the caller's editor resources are scoped to.
+    pub organization: Option<String>,
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AddressDto {
+    #[validate(length(min = 1, max = 255))]
+    pub line1: String,
+    #[validate(length(max = 255))]
+    pub line2: Option<String>,
+    #[validate(length(min = 1, max = 32))]
+    pub postal_code: String,
+    #[validate(length(min = 1, max = 128))]
+    pub city: String,
+    #[validate(length(max = 128))]
+    pub region: Option<String>,
+    /// ISO 3166-1 alpha-2 country code
+    #[validate(length(equal = 2))]
+    pub country: String,
+}
+
+/// Used both to create a customer and to replace an existing one.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CustomerRequest {
+    #[validate(length(max = 255))]
+    pub company_name: Option<String>,
+    #[validate(length(min = 1, max = 255))]
+    pub contact_name: String,
+    #[validate(email, length(max = 255))]
+    pub email: String,
+    #[validate(length(max = 64))]
+    pub phone: Option<String>,
+    #[validate(nested)]
+    pub billing_address: Option<AddressDto>,
+    #[validate(length(max = 64))]
+    pub vat_number: Option<String>,
+    /// Negotiated discount as a fraction (0.1 = 10 %), `@customer_discount_rate` in expressions
+    #[validate(range(min = 0.0, max = 1.0))]
+    #[serde(default)]
+    pub discount_rate: f64,
+    /// Numeric attributes exposed as `@customer_<key>` in expressions
+    #[serde(default)]
+    pub attributes: HashMap<String, f64>,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerResponse {
+    pub id: Uuid,
+    pub company_name: Option<String>,
+    pub contact_name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub billing_address: Option<AddressDto>,
+    pub vat_number: Option<String>,
+    pub discount_rate: f64,
+    pub attributes: HashMap<String, f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerListResponse {
+    pub customers: Vec<CustomerResponse>,
+}
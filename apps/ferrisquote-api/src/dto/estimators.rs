@@ -41,6 +41,12 @@ pub struct UpdateVariableRequest {
     pub description: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReorderVariableRequest {
+    pub after_id: Option<Uuid>,
+    pub before_id: Option<Uuid>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct EvaluateRequest {
     pub field_values: HashMap<String, f64>,
@@ -51,6 +57,8 @@ pub struct EvaluateSubmissionRequest {
     pub field_values: HashMap<String, f64>,
     pub iteration_values: HashMap<String, Vec<f64>>,
     pub iteration_counts: HashMap<String, usize>,
+    /// Customer whose attributes are exposed as `@customer_*` variables
+    pub customer_id: Option<Uuid>,
 }
 
 // ============================================================================
@@ -63,6 +71,8 @@ pub struct EstimatorResponse {
     pub flow_id: Uuid,
     pub name: String,
     pub variables: Vec<VariableResponse>,
+    /// Also sent as `ETag`; changes to the estimator must send it back in `If-Match`.
+    pub version: u32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -71,11 +81,15 @@ pub struct VariableResponse {
     pub name: String,
     pub expression: String,
     pub description: String,
+    pub rank: String,
+    pub version: u32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct EstimatorListResponse {
     pub estimators: Vec<EstimatorResponse>,
+    /// Cursor of the next page, `null` on the last one.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
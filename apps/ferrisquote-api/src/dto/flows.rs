@@ -1,5 +1,5 @@
 use serde::{Deserialize, Deserializer, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -19,6 +19,22 @@ where
 // Request DTOs
 // ============================================================================
 
+/// Query of the flow and estimator listings.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListParams {
+    /// Only items whose name contains this, ignoring case
+    pub search: Option<String>,
+    /// `created` (default) or `name`
+    pub sort: Option<String>,
+    /// `asc` or `desc` (defaults to newest first by creation, A to Z by name)
+    pub order: Option<String>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Items per page, at most 100 (defaults to 20)
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateFlowRequest {
     #[validate(length(min = 1, max = 255))]
@@ -97,6 +113,20 @@ pub struct MoveFieldRequest {
     pub before_id: Option<Uuid>,
 }
 
+/// The full order of a flow: every step, each with every one of its fields.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReorderFlowRequest {
+    pub steps: Vec<StepOrderRequest>,
+}
+
+/// A step of a `ReorderFlowRequest`. Fields listed here that currently belong
+/// to another step are moved into this one.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StepOrderRequest {
+    pub step_id: Uuid,
+    pub field_ids: Vec<Uuid>,
+}
+
 // ============================================================================
 // Field Config DTOs
 // ============================================================================
@@ -122,6 +152,8 @@ pub struct FlowResponse {
     pub name: String,
     pub description: String,
     pub steps: Vec<StepResponse>,
+    /// Also sent as `ETag`; changes to the flow must send it back in `If-Match`.
+    pub version: u32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -135,6 +167,7 @@ pub struct StepResponse {
     pub min_repeats: u32,
     pub max_repeats: Option<u32>,
     pub fields: Vec<FieldResponse>,
+    pub version: u32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -145,11 +178,14 @@ pub struct FieldResponse {
     pub description: String,
     pub rank: String,
     pub config: FieldConfigDto,
+    pub version: u32,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct FlowListResponse {
     pub flows: Vec<FlowSummaryResponse>,
+    /// Cursor of the next page, `null` on the last one.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -158,6 +194,7 @@ pub struct FlowSummaryResponse {
     pub name: String,
     pub description: String,
     pub step_count: usize,
+    pub version: u32,
 }
 
 // ============================================================================
@@ -1,15 +1,40 @@
+pub mod api_keys;
+pub mod audit;
+pub mod auth;
+pub mod customers;
 pub mod estimators;
 pub mod flows;
+pub mod quotes;
+pub mod runner;
 
 // Re-export commonly used DTOs
+pub use api_keys::{
+    ApiKeyListResponse, ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse,
+};
+pub use audit::{
+    ActorResponse, AuditChangeResponse, AuditEntryResponse, AuditPageResponse, AuditQuery,
+};
+pub use auth::{IdentityKindDto, IdentityResponse};
+pub use customers::{AddressDto, CustomerListResponse, CustomerRequest, CustomerResponse};
 pub use estimators::{
     CreateEstimatorRequest, CreateVariableRequest, EstimatorListResponse, EstimatorResponse,
-    EvaluateRequest, EvaluateResponse, EvaluateSubmissionRequest, UpdateEstimatorRequest,
-    UpdateVariableRequest, VariableResponse,
+    EvaluateRequest, EvaluateResponse, EvaluateSubmissionRequest, ReorderVariableRequest,
+    UpdateEstimatorRequest, UpdateVariableRequest, VariableResponse,
 };
 pub use flows::{
     ApiResponse, CreateFieldRequest, CreateFlowRequest, CreateStepRequest, FieldConfigDto,
-    FieldResponse, FlowListResponse, FlowResponse, FlowSummaryResponse, MessageResponse,
-    MoveFieldRequest, ReorderStepRequest, StepResponse, UpdateFieldConfigRequest,
-    UpdateFlowMetadataRequest, UpdateStepMetadataRequest,
+    FieldResponse, FlowListResponse, FlowResponse, FlowSummaryResponse, ListParams,
+    MessageResponse, MoveFieldRequest, ReorderFlowRequest, ReorderStepRequest, StepOrderRequest,
+    StepResponse, UpdateFieldConfigRequest, UpdateFlowMetadataRequest, UpdateStepMetadataRequest,
+};
+pub use quotes::{
+    AnswerChangeResponse, CreateQuoteRequest, IterationAnswerChangeResponse,
+    LineItemChangeResponse, QuoteDiffResponse, QuoteHistoryResponse, QuoteListResponse,
+    QuoteResponse, QuoteStatusChangeResponse, QuoteStatusDto, QuoteTransitionDto,
+    UpdateQuoteRequest, UpdateQuoteStatusRequest,
+};
+pub use runner::{
+    CreateShareLinkRequest, EstimateResponse, PublicFieldResponse, PublicFlowResponse,
+    PublicStepResponse, RunnerSessionResponse, ShareLinkListResponse, ShareLinkResponse,
+    SubmitStepRequest,
 };
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateQuoteRequest {
+    pub field_values: HashMap<String, f64>,
+    #[serde(default)]
+    pub iteration_values: HashMap<String, Vec<f64>>,
+    #[serde(default)]
+    pub iteration_counts: HashMap<String, usize>,
+    /// Customer the quote is prepared for
+    pub customer_id: Option<Uuid>,
+    /// Days the quote stays valid once sent (defaults to 30)
+    #[validate(range(min = 1, max = 365))]
+    pub validity_days: Option<u32>,
+}
+
+/// Replace the answers of a draft quote; the quote is re-evaluated.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateQuoteRequest {
+    pub field_values: HashMap<String, f64>,
+    #[serde(default)]
+    pub iteration_values: HashMap<String, Vec<f64>>,
+    #[serde(default)]
+    pub iteration_counts: HashMap<String, usize>,
+    #[validate(range(min = 1, max = 365))]
+    pub validity_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateQuoteStatusRequest {
+    pub status: QuoteTransitionDto,
+}
+
+/// Statuses a client can move a quote to. Quotes are expired by the expiry
+/// sweep and superseded by sending a newer revision.
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteTransitionDto {
+    Sent,
+    Accepted,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatusDto {
+    Draft,
+    Sent,
+    Accepted,
+    Rejected,
+    Expired,
+    Superseded,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteResponse {
+    pub id: Uuid,
+    /// Id of the first revision of this quote
+    pub root_id: Uuid,
+    pub revision: u32,
+    pub flow_id: Uuid,
+    pub estimator_id: Uuid,
+    pub customer_id: Option<Uuid>,
+    pub status: QuoteStatusDto,
+    pub field_values: HashMap<String, f64>,
+    pub iteration_values: HashMap<String, Vec<f64>>,
+    pub iteration_counts: HashMap<String, usize>,
+    pub results: HashMap<String, f64>,
+    pub total: f64,
+    pub validity_days: u32,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteListResponse {
+    pub quotes: Vec<QuoteResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteStatusChangeResponse {
+    pub from: QuoteStatusDto,
+    pub to: QuoteStatusDto,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteHistoryResponse {
+    pub history: Vec<QuoteStatusChangeResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnswerChangeResponse {
+    pub key: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IterationAnswerChangeResponse {
+    pub key: String,
+    pub before: Option<Vec<f64>>,
+    pub after: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LineItemChangeResponse {
+    /// Estimator variable name
+    pub name: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuoteDiffResponse {
+    pub from_id: Uuid,
+    pub to_id: Uuid,
+    pub from_revision: u32,
+    pub to_revision: u32,
+    pub changed_answers: Vec<AnswerChangeResponse>,
+    pub changed_iteration_answers: Vec<IterationAnswerChangeResponse>,
+    pub changed_line_items: Vec<LineItemChangeResponse>,
+    pub total_before: f64,
+    pub total_after: f64,
+    pub total_delta: f64,
+}
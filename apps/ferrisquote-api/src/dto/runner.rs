@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::FieldConfigDto;
+
+// ============================================================================
+// Request DTOs
+// ============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateShareLinkRequest {
+    /// Estimator computing the public estimate; must belong to the flow
+    pub estimator_id: Uuid,
+    /// The link stops working after this instant (never expires if omitted)
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Answers for one step: one entry per iteration (exactly one for a regular step),
+/// mapping field keys to numeric answers.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SubmitStepRequest {
+    #[validate(length(max = 100))]
+    pub iterations: Vec<HashMap<String, f64>>,
+}
+
+// ============================================================================
+// Response DTOs
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLinkResponse {
+    pub id: Uuid,
+    pub flow_id: Uuid,
+    pub estimator_id: Uuid,
+    pub token: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareLinkListResponse {
+    pub share_links: Vec<ShareLinkResponse>,
+}
+
+/// Flow schema as seen by anonymous visitors.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicFlowResponse {
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<PublicStepResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicStepResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub is_repeatable: bool,
+    pub repeat_label: Option<String>,
+    pub min_repeats: u32,
+    pub max_repeats: Option<u32>,
+    pub fields: Vec<PublicFieldResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicFieldResponse {
+    pub key: String,
+    pub label: String,
+    pub description: String,
+    pub config: FieldConfigDto,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RunnerSessionResponse {
+    pub session_id: Uuid,
+    pub completed_steps: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EstimateResponse {
+    pub total: f64,
+}
@@ -18,6 +18,8 @@ pub enum ApiError {
     Internal(String),
     /// Bad request errors
     BadRequest(String),
+    /// A change sent without the `If-Match` header it requires
+    PreconditionRequired(String),
 }
 
 impl fmt::Display for ApiError {
@@ -27,6 +29,7 @@ impl fmt::Display for ApiError {
             ApiError::Validation(msg) => write!(f, "Validation error: {}", msg),
             ApiError::Internal(msg) => write!(f, "Internal error: {}", msg),
             ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            ApiError::PreconditionRequired(msg) => write!(f, "Precondition required: {}", msg),
         }
     }
 }
@@ -72,6 +75,11 @@ impl IntoResponse for ApiError {
                 DomainError::Conflict { message } => {
                     (StatusCode::CONFLICT, "conflict", message.clone())
                 }
+                DomainError::InvalidTransition { entity, from, to } => (
+                    StatusCode::CONFLICT,
+                    "invalid_transition",
+                    format!("{} cannot go from {} to {}", entity, from, to),
+                ),
                 DomainError::Unauthorized { message } => {
                     (StatusCode::UNAUTHORIZED, "unauthorized", message.clone())
                 }
@@ -95,6 +103,11 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(ref msg) => {
                 (StatusCode::BAD_REQUEST, "bad_request", msg.clone())
             }
+            ApiError::PreconditionRequired(ref msg) => (
+                StatusCode::PRECONDITION_REQUIRED,
+                "precondition_required",
+                msg.clone(),
+            ),
             ApiError::Internal(ref msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "internal_error",
@@ -0,0 +1,73 @@
+//! Optimistic concurrency over HTTP.
+//!
+//! Flows, steps, fields, estimators and variables carry a version that every
+//! change increments. Responses returning one of them send its version as
+//! `ETag`, and requests changing one must send it back in `If-Match`: a change
+//! made against an older version is rejected with 409 Conflict.
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{
+        HeaderValue,
+        header::{ETAG, IF_MATCH},
+        request::Parts,
+    },
+    response::{IntoResponseParts, ResponseParts},
+};
+
+use crate::error::ApiError;
+
+/// The version a change is made against, from the `If-Match` header.
+///
+/// `If-Match: *` matches any version and gives `None`. A weak validator
+/// (`W/"3"`), as proxies that compress responses send back, stands for the
+/// same version as its strong form. Rejects with 428 when the header is
+/// missing and 400 when it does not hold a version.
+#[derive(Debug, Clone, Copy)]
+pub struct IfMatch(pub Option<u32>);
+
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = parts.headers.get(IF_MATCH).ok_or_else(|| {
+            ApiError::PreconditionRequired(
+                "If-Match header with the ETag of the resource is required".to_string(),
+            )
+        })?;
+        let invalid = || ApiError::BadRequest(format!("Invalid If-Match header: {header:?}"));
+
+        let value = header.to_str().map_err(|_| invalid())?.trim();
+        if value == "*" {
+            return Ok(IfMatch(None));
+        }
+        let value = value.strip_prefix("W/").unwrap_or(value);
+        let version = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        version
+            .parse()
+            .map(|version| IfMatch(Some(version)))
+            .map_err(|_| invalid())
+    }
+}
+
+/// `ETag` header holding the version of the returned resource.
+#[derive(Debug, Clone, Copy)]
+pub struct ETag(pub u32);
+
+impl IntoResponseParts for ETag {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        res.headers_mut().insert(
+            ETAG,
+            HeaderValue::from_str(&format!("\"{}\"", self.0))
+                .expect("a quoted number is a valid header value"),
+        );
+        Ok(res)
+    }
+}
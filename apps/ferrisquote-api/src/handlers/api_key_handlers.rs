@@ -0,0 +1,125 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use ferrisquote_domain::domain::api_key::ports::ApiKeyService;
+use ferrisquote_domain::{ApiKey, ApiKeyId, Permission};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        ApiKeyListResponse, ApiKeyResponse, ApiResponse, CreateApiKeyRequest, CreatedApiKeyResponse,
+    },
+    error::ApiResult,
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
+};
+
+fn map_api_key(api_key: ApiKey) -> ApiKeyResponse {
+    ApiKeyResponse {
+        active: api_key.is_active(Utc::now()),
+        id: api_key.id.into_uuid(),
+        name: api_key.name,
+        prefix: api_key.prefix,
+        scopes: api_key.scopes,
+        created_at: api_key.created_at,
+        expires_at: api_key.expires_at,
+        last_used_at: api_key.last_used_at,
+        revoked_at: api_key.revoked_at,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created; the secret is shown only once", body = CreatedApiKeyResponse),
+        (status = 400, description = "Validation error or unknown scope"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "api_keys"
+)]
+pub async fn create_api_key<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<CreatedApiKeyResponse>>)> {
+    identity.authorize(&state.policy, Permission::ApiKeyManage)?;
+    let organization = identity.organization()?;
+
+    request.validate()?;
+
+    let issued = state
+        .api_key_service
+        .create_api_key(
+            &organization,
+            request.name,
+            request.scopes,
+            request.expires_at,
+        )
+        .await?;
+
+    let response = CreatedApiKeyResponse {
+        api_key: map_api_key(issued.api_key),
+        secret: issued.secret,
+    };
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/api-keys",
+    responses(
+        (status = 200, description = "API keys of the organization", body = ApiKeyListResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "api_keys"
+)]
+pub async fn list_api_keys<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+) -> ApiResult<Json<ApiResponse<ApiKeyListResponse>>> {
+    identity.authorize(&state.policy, Permission::ApiKeyManage)?;
+    let organization = identity.organization()?;
+
+    let api_keys = state.api_key_service.list_api_keys(&organization).await?;
+
+    let response = ApiKeyListResponse {
+        api_keys: api_keys.into_iter().map(map_api_key).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/api-keys/{api_key_id}",
+    params(("api_key_id" = String, Path, description = "API key UUID")),
+    responses(
+        (status = 200, description = "API key revoked", body = ApiKeyResponse),
+        (status = 404, description = "API key not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "api_keys"
+)]
+pub async fn revoke_api_key<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(api_key_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<ApiKeyResponse>>> {
+    identity.authorize(&state.policy, Permission::ApiKeyManage)?;
+    let organization = identity.organization()?;
+
+    let id = ApiKeyId::from_uuid(uuid::Uuid::parse_str(&api_key_id)?);
+
+    let api_key = state
+        .api_key_service
+        .revoke_api_key(&organization, id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_api_key(api_key))))
+}
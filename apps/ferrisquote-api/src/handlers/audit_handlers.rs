@@ -0,0 +1,92 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use ferrisquote_domain::domain::audit::ports::AuditService;
+use ferrisquote_domain::{AuditEntity, AuditEntry, AuditFilter, Permission};
+
+use crate::{
+    dto::{
+        ActorResponse, ApiResponse, AuditChangeResponse, AuditEntryResponse, AuditPageResponse,
+        AuditQuery,
+    },
+    error::ApiResult,
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
+};
+
+const DEFAULT_PER_PAGE: u32 = 20;
+
+fn map_audit_entry(entry: AuditEntry) -> AuditEntryResponse {
+    AuditEntryResponse {
+        id: entry.id.into_uuid(),
+        actor: ActorResponse {
+            kind: entry.actor.kind.to_string(),
+            id: entry.actor.id,
+            name: entry.actor.name,
+        },
+        entity: entry.entity.to_string(),
+        entity_id: entry.entity_id,
+        action: entry.action.to_string(),
+        before: entry.before,
+        after: entry.after,
+        changes: entry
+            .changes
+            .into_iter()
+            .map(|change| AuditChangeResponse {
+                path: change.key,
+                before: change.before,
+                after: change.after,
+            })
+            .collect(),
+        at: entry.at,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit log of the organization, newest first", body = AuditPageResponse),
+        (status = 400, description = "Unknown entity or invalid page"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "audit"
+)]
+pub async fn list_audit_entries<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Query(query): Query<AuditQuery>,
+) -> ApiResult<Json<ApiResponse<AuditPageResponse>>> {
+    identity.authorize(&state.policy, Permission::AuditRead)?;
+    let organization = identity.organization()?;
+
+    let filter = AuditFilter {
+        entity: query
+            .entity
+            .as_deref()
+            .map(str::parse::<AuditEntity>)
+            .transpose()?,
+        entity_id: query.entity_id,
+    };
+
+    let page = state
+        .audit_service
+        .list_entries(
+            &organization,
+            filter,
+            query.page.unwrap_or(1),
+            query.per_page.unwrap_or(DEFAULT_PER_PAGE),
+        )
+        .await?;
+
+    let response = AuditPageResponse {
+        entries: page.entries.into_iter().map(map_audit_entry).collect(),
+        page: page.page,
+        per_page: page.per_page,
+        total: page.total,
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
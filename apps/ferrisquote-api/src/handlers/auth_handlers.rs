@@ -0,0 +1,35 @@
+use axum::Json;
+use ferrisquote_auth::domain::entities::identity::Identity;
+
+use crate::{
+    dto::{ApiResponse, IdentityKindDto, IdentityResponse},
+    middleware::CurrentIdentity,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    responses(
+        (status = 200, description = "Authenticated caller", body = IdentityResponse),
+        (status = 401, description = "Missing or invalid bearer token"),
+    ),
+    tag = "auth"
+)]
+pub async fn get_current_identity(
+    CurrentIdentity(identity): CurrentIdentity,
+) -> Json<ApiResponse<IdentityResponse>> {
+    let kind = match identity {
+        Identity::User(_) => IdentityKindDto::User,
+        Identity::Client(_) => IdentityKindDto::Client,
+        Identity::Anonymous => IdentityKindDto::Anonymous,
+    };
+
+    Json(ApiResponse::success(IdentityResponse {
+        kind,
+        id: identity.id().map(str::to_string),
+        username: identity.username().map(str::to_string),
+        roles: identity.roles(),
+        scopes: identity.scopes(),
+        organization: identity.organization().map(str::to_string),
+    }))
+}
@@ -0,0 +1,212 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use ferrisquote_domain::domain::customer::ports::CustomerService;
+use ferrisquote_domain::{Address, Customer, CustomerDetails, CustomerId, Permission};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        AddressDto, ApiResponse, CustomerListResponse, CustomerRequest, CustomerResponse,
+        MessageResponse,
+    },
+    error::ApiResult,
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
+};
+
+fn map_customer(c: Customer) -> CustomerResponse {
+    CustomerResponse {
+        id: c.id.into_uuid(),
+        company_name: c.company_name,
+        contact_name: c.contact_name,
+        email: c.email,
+        phone: c.phone,
+        billing_address: c.billing_address.map(|a| AddressDto {
+            line1: a.line1,
+            line2: a.line2,
+            postal_code: a.postal_code,
+            city: a.city,
+            region: a.region,
+            country: a.country,
+        }),
+        vat_number: c.vat_number,
+        discount_rate: c.discount_rate,
+        attributes: c.attributes,
+        created_at: c.created_at,
+        updated_at: c.updated_at,
+    }
+}
+
+fn map_details(request: CustomerRequest) -> CustomerDetails {
+    CustomerDetails {
+        company_name: request.company_name,
+        contact_name: request.contact_name,
+        email: request.email,
+        phone: request.phone,
+        billing_address: request.billing_address.map(|a| Address {
+            line1: a.line1,
+            line2: a.line2,
+            postal_code: a.postal_code,
+            city: a.city,
+            region: a.region,
+            country: a.country.to_uppercase(),
+        }),
+        vat_number: request.vat_number,
+        discount_rate: request.discount_rate,
+        attributes: request.attributes,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/customers",
+    request_body = CustomerRequest,
+    responses(
+        (status = 201, description = "Customer created", body = CustomerResponse),
+        (status = 400, description = "Validation error"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "customers"
+)]
+pub async fn create_customer<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Json(request): Json<CustomerRequest>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<CustomerResponse>>)> {
+    identity.authorize(&state.policy, Permission::CustomerWrite)?;
+
+    request.validate()?;
+
+    let organization = identity.organization()?;
+    let customer = state
+        .customer_service
+        .create_customer(&organization, map_details(request))
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(map_customer(customer))),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/customers",
+    responses(
+        (status = 200, description = "List of customers", body = CustomerListResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "customers"
+)]
+pub async fn list_customers<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+) -> ApiResult<Json<ApiResponse<CustomerListResponse>>> {
+    identity.authorize(&state.policy, Permission::CustomerRead)?;
+
+    let organization = identity.organization()?;
+    let customers = state.customer_service.list_customers(&organization).await?;
+
+    let response = CustomerListResponse {
+        customers: customers.into_iter().map(map_customer).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/customers/{customer_id}",
+    params(("customer_id" = String, Path, description = "Customer UUID")),
+    responses(
+        (status = 200, description = "Customer found", body = CustomerResponse),
+        (status = 404, description = "Customer not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "customers"
+)]
+pub async fn get_customer<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(customer_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<CustomerResponse>>> {
+    identity.authorize(&state.policy, Permission::CustomerRead)?;
+
+    let organization = identity.organization()?;
+    let id = CustomerId::from_uuid(uuid::Uuid::parse_str(&customer_id)?);
+    let customer = state
+        .customer_service
+        .get_customer(&organization, id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_customer(customer))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/customers/{customer_id}",
+    params(("customer_id" = String, Path, description = "Customer UUID")),
+    request_body = CustomerRequest,
+    responses(
+        (status = 200, description = "Customer replaced", body = CustomerResponse),
+        (status = 400, description = "Validation error"),
+        (status = 404, description = "Customer not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "customers"
+)]
+pub async fn update_customer<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(customer_id): Path<String>,
+    Json(request): Json<CustomerRequest>,
+) -> ApiResult<Json<ApiResponse<CustomerResponse>>> {
+    identity.authorize(&state.policy, Permission::CustomerWrite)?;
+
+    request.validate()?;
+
+    let organization = identity.organization()?;
+    let id = CustomerId::from_uuid(uuid::Uuid::parse_str(&customer_id)?);
+    let customer = state
+        .customer_service
+        .update_customer(&organization, id, map_details(request))
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_customer(customer))))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/customers/{customer_id}",
+    params(("customer_id" = String, Path, description = "Customer UUID")),
+    responses(
+        (status = 200, description = "Customer deleted", body = MessageResponse),
+        (status = 404, description = "Customer not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "customers"
+)]
+pub async fn delete_customer<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(customer_id): Path<String>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::CustomerWrite)?;
+
+    let organization = identity.organization()?;
+    let id = CustomerId::from_uuid(uuid::Uuid::parse_str(&customer_id)?);
+    state
+        .customer_service
+        .delete_customer(&organization, id)
+        .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(ApiResponse::success(MessageResponse::new(
+            "Customer deleted successfully",
+        ))),
+    ))
+}
@@ -1,34 +1,40 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
-use ferrisquote_domain::domain::{
-    estimator::{
-        entities::{ids::{EstimatorId, EstimatorVariableId}, submission::SubmissionData},
-        ports::EstimatorService,
+use ferrisquote_domain::domain::estimator::{
+    entities::{
+        ids::{EstimatorId, EstimatorVariableId},
+        submission::SubmissionData,
     },
-    flows::ports::{FieldService, FlowService, StepService},
+    ports::EstimatorService,
 };
-use ferrisquote_domain::FlowId;
+use ferrisquote_domain::{CustomerId, FlowId, Permission};
 use validator::Validate;
 
 use crate::{
     dto::{
         ApiResponse, CreateEstimatorRequest, CreateVariableRequest, EstimatorListResponse,
         EstimatorResponse, EvaluateRequest, EvaluateResponse, EvaluateSubmissionRequest,
-        MessageResponse, UpdateEstimatorRequest, UpdateVariableRequest, VariableResponse,
+        ListParams, MessageResponse, ReorderVariableRequest, UpdateEstimatorRequest,
+        UpdateVariableRequest, VariableResponse,
     },
     error::ApiResult,
-    state::AppState,
+    etag::{ETag, IfMatch},
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
 };
 
+use super::mappers::map_list_params;
+
 fn map_estimator(e: ferrisquote_domain::Estimator) -> EstimatorResponse {
     EstimatorResponse {
         id: e.id.into_uuid(),
         flow_id: e.flow_id.into_uuid(),
         name: e.name,
         variables: e.variables.into_iter().map(map_variable).collect(),
+        version: e.version,
     }
 }
 
@@ -38,6 +44,8 @@ fn map_variable(v: ferrisquote_domain::EstimatorVariable) -> VariableResponse {
         name: v.name,
         expression: v.expression,
         description: v.description,
+        rank: v.rank,
+        version: v.version,
     }
 }
 
@@ -51,26 +59,33 @@ fn map_variable(v: ferrisquote_domain::EstimatorVariable) -> VariableResponse {
     params(("flow_id" = String, Path, description = "Flow UUID")),
     request_body = CreateEstimatorRequest,
     responses(
-        (status = 201, description = "Estimator created", body = EstimatorResponse),
+        (status = 201, description = "Estimator created", body = EstimatorResponse, headers(("ETag" = String, description = "Version of the estimator"))),
         (status = 400, description = "Validation error"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimators"
 )]
-pub async fn create_estimator<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn create_estimator<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
     Json(request): Json<CreateEstimatorRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<EstimatorResponse>>)> {
+) -> ApiResult<(StatusCode, ETag, Json<ApiResponse<EstimatorResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
     let estimator = state
         .estimator_service
-        .create_estimator(flow_id, request.name)
+        .create_estimator(&organization, &actor, flow_id, request.name)
         .await?;
 
     Ok((
         StatusCode::CREATED,
+        ETag(estimator.version),
         Json(ApiResponse::success(map_estimator(estimator))),
     ))
 }
@@ -78,24 +93,33 @@ pub async fn create_estimator<FS: FlowService + StepService + FieldService, ES:
 #[utoipa::path(
     get,
     path = "/api/v1/flows/{flow_id}/estimators",
-    params(("flow_id" = String, Path, description = "Flow UUID")),
+    params(("flow_id" = String, Path, description = "Flow UUID"), ListParams),
     responses(
-        (status = 200, description = "List of estimators", body = EstimatorListResponse),
+        (status = 200, description = "One page of estimators", body = EstimatorListResponse),
+        (status = 400, description = "Unknown sort, order or cursor, or invalid page size"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimators"
 )]
-pub async fn list_estimators<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn list_estimators<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
+    Query(params): Query<ListParams>,
 ) -> ApiResult<Json<ApiResponse<EstimatorListResponse>>> {
+    identity.authorize(&state.policy, Permission::EstimatorRead)?;
+    let organization = identity.organization()?;
+
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
-    let estimators = state
+    let query = map_list_params::<EstimatorId>(params)?;
+    let page = state
         .estimator_service
-        .list_estimators_for_flow(flow_id)
+        .list_estimators_for_flow(&organization, flow_id, query)
         .await?;
 
     let response = EstimatorListResponse {
-        estimators: estimators.into_iter().map(map_estimator).collect(),
+        estimators: page.items.into_iter().map(map_estimator).collect(),
+        next_cursor: page.next.map(|cursor| cursor.to_string()),
     };
 
     Ok(Json(ApiResponse::success(response)))
@@ -106,65 +130,106 @@ pub async fn list_estimators<FS: FlowService + StepService + FieldService, ES: E
     path = "/api/v1/estimators/{estimator_id}",
     params(("estimator_id" = String, Path, description = "Estimator UUID")),
     responses(
-        (status = 200, description = "Estimator found", body = EstimatorResponse),
+        (status = 200, description = "Estimator found", body = EstimatorResponse, headers(("ETag" = String, description = "Version of the estimator"))),
         (status = 404, description = "Estimator not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimators"
 )]
-pub async fn get_estimator<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn get_estimator<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
-) -> ApiResult<Json<ApiResponse<EstimatorResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<EstimatorResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorRead)?;
+    let organization = identity.organization()?;
+
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
-    let estimator = state.estimator_service.get_estimator(id).await?;
+    let estimator = state
+        .estimator_service
+        .get_estimator(&organization, id)
+        .await?;
 
-    Ok(Json(ApiResponse::success(map_estimator(estimator))))
+    Ok((
+        ETag(estimator.version),
+        Json(ApiResponse::success(map_estimator(estimator))),
+    ))
 }
 
 #[utoipa::path(
     put,
     path = "/api/v1/estimators/{estimator_id}",
-    params(("estimator_id" = String, Path, description = "Estimator UUID")),
+    params(
+        ("estimator_id" = String, Path, description = "Estimator UUID"),
+        ("If-Match" = String, Header, description = "ETag of the estimator"),
+    ),
     request_body = UpdateEstimatorRequest,
     responses(
-        (status = 200, description = "Estimator updated", body = EstimatorResponse),
+        (status = 200, description = "Estimator updated", body = EstimatorResponse, headers(("ETag" = String, description = "Version of the estimator"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Estimator not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Estimator changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "estimators"
 )]
-pub async fn update_estimator<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn update_estimator<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<UpdateEstimatorRequest>,
-) -> ApiResult<Json<ApiResponse<EstimatorResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<EstimatorResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
     let estimator = state
         .estimator_service
-        .update_estimator(id, request.name)
+        .update_estimator(&organization, &actor, id, request.name, version)
         .await?;
 
-    Ok(Json(ApiResponse::success(map_estimator(estimator))))
+    Ok((
+        ETag(estimator.version),
+        Json(ApiResponse::success(map_estimator(estimator))),
+    ))
 }
 
 #[utoipa::path(
     delete,
     path = "/api/v1/estimators/{estimator_id}",
-    params(("estimator_id" = String, Path, description = "Estimator UUID")),
+    params(
+        ("estimator_id" = String, Path, description = "Estimator UUID"),
+        ("If-Match" = String, Header, description = "ETag of the estimator"),
+    ),
     responses(
         (status = 200, description = "Estimator deleted", body = MessageResponse),
         (status = 404, description = "Estimator not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Estimator changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "estimators"
 )]
-pub async fn delete_estimator<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn delete_estimator<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
+    IfMatch(version): IfMatch,
 ) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
-    state.estimator_service.delete_estimator(id).await?;
+    state
+        .estimator_service
+        .delete_estimator(&organization, &actor, id, version)
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -184,23 +249,31 @@ pub async fn delete_estimator<FS: FlowService + StepService + FieldService, ES:
     params(("estimator_id" = String, Path, description = "Estimator UUID")),
     request_body = CreateVariableRequest,
     responses(
-        (status = 201, description = "Variable created", body = VariableResponse),
+        (status = 201, description = "Variable created", body = VariableResponse, headers(("ETag" = String, description = "Version of the variable"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Estimator not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimator_variables"
 )]
-pub async fn add_variable<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn add_variable<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
     Json(request): Json<CreateVariableRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<VariableResponse>>)> {
+) -> ApiResult<(StatusCode, ETag, Json<ApiResponse<VariableResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
     let variable = state
         .estimator_service
         .add_variable(
+            &organization,
+            &actor,
             id,
             request.name,
             request.expression,
@@ -210,6 +283,7 @@ pub async fn add_variable<FS: FlowService + StepService + FieldService, ES: Esti
 
     Ok((
         StatusCode::CREATED,
+        ETag(variable.version),
         Json(ApiResponse::success(map_variable(variable))),
     ))
 }
@@ -217,47 +291,85 @@ pub async fn add_variable<FS: FlowService + StepService + FieldService, ES: Esti
 #[utoipa::path(
     put,
     path = "/api/v1/variables/{variable_id}",
-    params(("variable_id" = String, Path, description = "Variable UUID")),
+    params(
+        ("variable_id" = String, Path, description = "Variable UUID"),
+        ("If-Match" = String, Header, description = "ETag of the variable"),
+    ),
     request_body = UpdateVariableRequest,
     responses(
-        (status = 200, description = "Variable updated", body = VariableResponse),
+        (status = 200, description = "Variable updated", body = VariableResponse, headers(("ETag" = String, description = "Version of the variable"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Variable not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Variable changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "estimator_variables"
 )]
-pub async fn update_variable<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn update_variable<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(variable_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<UpdateVariableRequest>,
-) -> ApiResult<Json<ApiResponse<VariableResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<VariableResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let id = EstimatorVariableId::from_uuid(uuid::Uuid::parse_str(&variable_id)?);
     let variable = state
         .estimator_service
-        .update_variable(id, request.name, request.expression, request.description)
+        .update_variable(
+            &organization,
+            &actor,
+            id,
+            request.name,
+            request.expression,
+            request.description,
+            version,
+        )
         .await?;
 
-    Ok(Json(ApiResponse::success(map_variable(variable))))
+    Ok((
+        ETag(variable.version),
+        Json(ApiResponse::success(map_variable(variable))),
+    ))
 }
 
 #[utoipa::path(
     delete,
     path = "/api/v1/variables/{variable_id}",
-    params(("variable_id" = String, Path, description = "Variable UUID")),
+    params(
+        ("variable_id" = String, Path, description = "Variable UUID"),
+        ("If-Match" = String, Header, description = "ETag of the variable"),
+    ),
     responses(
         (status = 200, description = "Variable deleted", body = MessageResponse),
         (status = 404, description = "Variable not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Variable changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "estimator_variables"
 )]
-pub async fn remove_variable<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn remove_variable<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(variable_id): Path<String>,
+    IfMatch(version): IfMatch,
 ) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     let id = EstimatorVariableId::from_uuid(uuid::Uuid::parse_str(&variable_id)?);
-    state.estimator_service.remove_variable(id).await?;
+    state
+        .estimator_service
+        .remove_variable(&organization, &actor, id, version)
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -267,6 +379,48 @@ pub async fn remove_variable<FS: FlowService + StepService + FieldService, ES: E
     ))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/variables/{variable_id}/reorder",
+    params(
+        ("variable_id" = String, Path, description = "Variable UUID"),
+        ("If-Match" = String, Header, description = "ETag of the variable"),
+    ),
+    request_body = ReorderVariableRequest,
+    responses(
+        (status = 200, description = "Variable reordered, returns updated estimator", body = EstimatorResponse),
+        (status = 404, description = "Variable not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Variable changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+    ),
+    tag = "estimator_variables"
+)]
+pub async fn reorder_variable<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(variable_id): Path<String>,
+    IfMatch(version): IfMatch,
+    Json(request): Json<ReorderVariableRequest>,
+) -> ApiResult<Json<ApiResponse<EstimatorResponse>>> {
+    identity.authorize(&state.policy, Permission::EstimatorWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    request.validate()?;
+
+    let id = EstimatorVariableId::from_uuid(uuid::Uuid::parse_str(&variable_id)?);
+    let after_id = request.after_id.map(EstimatorVariableId::from_uuid);
+    let before_id = request.before_id.map(EstimatorVariableId::from_uuid);
+
+    let estimator = state
+        .estimator_service
+        .reorder_variable(&organization, &actor, id, after_id, before_id, version)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_estimator(estimator))))
+}
+
 // ============================================================================
 // Evaluation
 // ============================================================================
@@ -280,18 +434,23 @@ pub async fn remove_variable<FS: FlowService + StepService + FieldService, ES: E
         (status = 200, description = "Evaluation result", body = EvaluateResponse),
         (status = 400, description = "Evaluation error"),
         (status = 404, description = "Estimator not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimators"
 )]
-pub async fn evaluate<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn evaluate<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
     Json(request): Json<EvaluateRequest>,
 ) -> ApiResult<Json<ApiResponse<EvaluateResponse>>> {
+    identity.authorize(&state.policy, Permission::EstimatorEvaluate)?;
+    let organization = identity.organization()?;
+
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
     let results = state
         .estimator_service
-        .evaluate(id, request.field_values)
+        .evaluate(&organization, id, request.field_values)
         .await?;
 
     Ok(Json(ApiResponse::success(EvaluateResponse { results })))
@@ -305,24 +464,30 @@ pub async fn evaluate<FS: FlowService + StepService + FieldService, ES: Estimato
     responses(
         (status = 200, description = "Evaluation result", body = EvaluateResponse),
         (status = 400, description = "Evaluation error"),
-        (status = 404, description = "Estimator not found"),
+        (status = 404, description = "Estimator or customer not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "estimators"
 )]
-pub async fn evaluate_submission<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn evaluate_submission<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(estimator_id): Path<String>,
     Json(request): Json<EvaluateSubmissionRequest>,
 ) -> ApiResult<Json<ApiResponse<EvaluateResponse>>> {
+    identity.authorize(&state.policy, Permission::EstimatorEvaluate)?;
+    let organization = identity.organization()?;
+
     let id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
     let data = SubmissionData {
         field_values: request.field_values,
         iteration_values: request.iteration_values,
         iteration_counts: request.iteration_counts,
     };
+    let customer_id = request.customer_id.map(CustomerId::from_uuid);
     let results = state
         .estimator_service
-        .evaluate_submission(id, data)
+        .evaluate_submission(&organization, id, data, customer_id)
         .await?;
 
     Ok(Json(ApiResponse::success(EvaluateResponse { results })))
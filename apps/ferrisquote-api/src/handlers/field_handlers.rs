@@ -3,7 +3,7 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
 };
-use ferrisquote_domain::{FieldId, StepId, domain::{estimator::ports::EstimatorService, flows::ports::{FieldService, FlowService, StepService}}};
+use ferrisquote_domain::{FieldId, Permission, StepId, domain::flows::ports::FieldService};
 use validator::Validate;
 
 use crate::{
@@ -12,7 +12,9 @@ use crate::{
         MoveFieldRequest, UpdateFieldConfigRequest,
     },
     error::ApiResult,
-    state::AppState,
+    etag::{ETag, IfMatch},
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
 };
 
 use super::mappers::{map_field_config_from_dto, map_field_to_response, map_flow_to_response};
@@ -24,17 +26,23 @@ use super::mappers::{map_field_config_from_dto, map_field_to_response, map_flow_
     params(("step_id" = String, Path, description = "Step UUID")),
     request_body = CreateFieldRequest,
     responses(
-        (status = 201, description = "Field created", body = FieldResponse),
+        (status = 201, description = "Field created", body = FieldResponse, headers(("ETag" = String, description = "Version of the field"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Step not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "fields"
 )]
-pub async fn add_field<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn add_field<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(step_id): Path<String>,
     Json(request): Json<CreateFieldRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<FieldResponse>>)> {
+) -> ApiResult<(StatusCode, ETag, Json<ApiResponse<FieldResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let step_id = StepId::from_uuid(uuid::Uuid::parse_str(&step_id)?);
@@ -42,32 +50,56 @@ pub async fn add_field<FS: FlowService + StepService + FieldService, ES: Estimat
 
     let field = state
         .flow_service
-        .add_field(step_id, request.label, request.key, config)
+        .add_field(
+            &organization,
+            &actor,
+            step_id,
+            request.label,
+            request.key,
+            config,
+        )
         .await?;
 
+    let etag = ETag(field.version);
     let response = map_field_to_response(field);
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+    Ok((
+        StatusCode::CREATED,
+        etag,
+        Json(ApiResponse::success(response)),
+    ))
 }
 
 /// Update field configuration
 #[utoipa::path(
     put,
     path = "/api/v1/flows/fields/{field_id}",
-    params(("field_id" = String, Path, description = "Field UUID")),
+    params(
+        ("field_id" = String, Path, description = "Field UUID"),
+        ("If-Match" = String, Header, description = "ETag of the field"),
+    ),
     request_body = UpdateFieldConfigRequest,
     responses(
-        (status = 200, description = "Field updated", body = FieldResponse),
+        (status = 200, description = "Field updated", body = FieldResponse, headers(("ETag" = String, description = "Version of the field"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Field not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Field changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "fields"
 )]
-pub async fn update_field_config<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn update_field_config<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(field_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<UpdateFieldConfigRequest>,
-) -> ApiResult<Json<ApiResponse<FieldResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<FieldResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let field_id = FieldId::from_uuid(uuid::Uuid::parse_str(&field_id)?);
@@ -75,31 +107,54 @@ pub async fn update_field_config<FS: FlowService + StepService + FieldService, E
 
     let field = state
         .flow_service
-        .update_field_config(field_id, Some(request.label), Some(config))
+        .update_field_config(
+            &organization,
+            &actor,
+            field_id,
+            Some(request.label),
+            Some(config),
+            version,
+        )
         .await?;
 
+    let etag = ETag(field.version);
     let response = map_field_to_response(field);
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((etag, Json(ApiResponse::success(response))))
 }
 
 /// Remove a field from a step
 #[utoipa::path(
     delete,
     path = "/api/v1/flows/fields/{field_id}",
-    params(("field_id" = String, Path, description = "Field UUID")),
+    params(
+        ("field_id" = String, Path, description = "Field UUID"),
+        ("If-Match" = String, Header, description = "ETag of the field"),
+    ),
     responses(
         (status = 200, description = "Field removed", body = MessageResponse),
         (status = 404, description = "Field not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Field changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "fields"
 )]
-pub async fn remove_field<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn remove_field<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(field_id): Path<String>,
+    IfMatch(version): IfMatch,
 ) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     let field_id = FieldId::from_uuid(uuid::Uuid::parse_str(&field_id)?);
-    state.flow_service.remove_field(field_id).await?;
+    state
+        .flow_service
+        .remove_field(&organization, &actor, field_id, version)
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -113,29 +168,50 @@ pub async fn remove_field<FS: FlowService + StepService + FieldService, ES: Esti
 #[utoipa::path(
     put,
     path = "/api/v1/flows/fields/{field_id}/move",
-    params(("field_id" = String, Path, description = "Field UUID")),
+    params(
+        ("field_id" = String, Path, description = "Field UUID"),
+        ("If-Match" = String, Header, description = "ETag of the field"),
+    ),
     request_body = MoveFieldRequest,
     responses(
         (status = 200, description = "Field moved, returns updated flow", body = FlowResponse),
+        (status = 400, description = "Target step or neighbours are not siblings of the field, or are out of order"),
         (status = 404, description = "Field not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Field changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "fields"
 )]
-pub async fn move_field<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn move_field<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(field_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<MoveFieldRequest>,
 ) -> ApiResult<Json<ApiResponse<crate::dto::FlowResponse>>> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let field_id = FieldId::from_uuid(uuid::Uuid::parse_str(&field_id)?);
-    let target_step_id = request.target_step_id.map(|id| StepId::from_uuid(id));
-    let after_id = request.after_id.map(|id| FieldId::from_uuid(id));
-    let before_id = request.before_id.map(|id| FieldId::from_uuid(id));
+    let target_step_id = request.target_step_id.map(StepId::from_uuid);
+    let after_id = request.after_id.map(FieldId::from_uuid);
+    let before_id = request.before_id.map(FieldId::from_uuid);
 
     let flow = state
         .flow_service
-        .move_field(field_id, target_step_id, after_id, before_id)
+        .move_field(
+            &organization,
+            &actor,
+            field_id,
+            target_step_id,
+            after_id,
+            before_id,
+            version,
+        )
         .await?;
 
     let response = map_flow_to_response(flow);
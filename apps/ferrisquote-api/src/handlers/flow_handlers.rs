@@ -1,21 +1,25 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
-use ferrisquote_domain::{FlowId, domain::{estimator::ports::EstimatorService, flows::ports::{FieldService, FlowService, StepService}}};
+use ferrisquote_domain::{
+    FieldId, FlowId, FlowLayout, Permission, StepId, StepLayout, domain::flows::ports::FlowService,
+};
 use validator::Validate;
 
 use crate::{
     dto::{
         ApiResponse, CreateFlowRequest, FlowListResponse, FlowResponse, FlowSummaryResponse,
-        MessageResponse, UpdateFlowMetadataRequest,
+        ListParams, MessageResponse, ReorderFlowRequest, UpdateFlowMetadataRequest,
     },
     error::ApiResult,
-    state::AppState,
+    etag::{ETag, IfMatch},
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
 };
 
-use super::mappers::map_flow_to_response;
+use super::mappers::{map_flow_to_response, map_list_params};
 
 /// Create a new flow
 #[utoipa::path(
@@ -23,21 +27,35 @@ use super::mappers::map_flow_to_response;
     path = "/api/v1/flows",
     request_body = CreateFlowRequest,
     responses(
-        (status = 201, description = "Flow created", body = FlowResponse),
+        (status = 201, description = "Flow created", body = FlowResponse, headers(("ETag" = String, description = "Version of the flow"))),
         (status = 400, description = "Validation error"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "flows"
 )]
-pub async fn create_flow<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn create_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Json(request): Json<CreateFlowRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<FlowResponse>>)> {
+) -> ApiResult<(StatusCode, ETag, Json<ApiResponse<FlowResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
-    let flow = state.flow_service.create_flow(request.name).await?;
+    let flow = state
+        .flow_service
+        .create_flow(&organization, &actor, request.name)
+        .await?;
+    let etag = ETag(flow.version);
     let response = map_flow_to_response(flow);
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+    Ok((
+        StatusCode::CREATED,
+        etag,
+        Json(ApiResponse::success(response)),
+    ))
 }
 
 /// Get a flow by ID
@@ -46,46 +64,67 @@ pub async fn create_flow<FS: FlowService + StepService + FieldService, ES: Estim
     path = "/api/v1/flows/{flow_id}",
     params(("flow_id" = String, Path, description = "Flow UUID")),
     responses(
-        (status = 200, description = "Flow found", body = FlowResponse),
+        (status = 200, description = "Flow found", body = FlowResponse, headers(("ETag" = String, description = "Version of the flow"))),
         (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "flows"
 )]
-pub async fn get_flow<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn get_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
-) -> ApiResult<Json<ApiResponse<FlowResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<FlowResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowRead)?;
+    let organization = identity.organization()?;
+
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
-    let flow = state.flow_service.get_flow(flow_id).await?;
+    let flow = state.flow_service.get_flow(&organization, flow_id).await?;
+    let etag = ETag(flow.version);
     let response = map_flow_to_response(flow);
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((etag, Json(ApiResponse::success(response))))
 }
 
-/// List all flows
+/// List flows, one page at a time
+///
+/// Returns summaries of the flows, without their steps and fields. Pass the
+/// `next_cursor` of a page as `cursor` to get the following one.
 #[utoipa::path(
     get,
     path = "/api/v1/flows",
+    params(ListParams),
     responses(
-        (status = 200, description = "List of flows", body = FlowListResponse),
+        (status = 200, description = "One page of flows", body = FlowListResponse),
+        (status = 400, description = "Unknown sort, order or cursor, or invalid page size"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "flows"
 )]
-pub async fn list_flows<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn list_flows<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Query(params): Query<ListParams>,
 ) -> ApiResult<Json<ApiResponse<FlowListResponse>>> {
-    let flows = state.flow_service.list_flows().await?;
+    identity.authorize(&state.policy, Permission::FlowRead)?;
+    let organization = identity.organization()?;
+
+    let query = map_list_params::<FlowId>(params)?;
+    let page = state.flow_service.list_flows(&organization, query).await?;
 
     let response = FlowListResponse {
-        flows: flows
+        flows: page
+            .items
             .into_iter()
             .map(|flow| FlowSummaryResponse {
                 id: flow.id.into_uuid(),
                 name: flow.name,
                 description: flow.description,
-                step_count: flow.steps.len(),
+                step_count: flow.step_count,
+                version: flow.version,
             })
             .collect(),
+        next_cursor: page.next.map(|cursor| cursor.to_string()),
     };
 
     Ok(Json(ApiResponse::success(response)))
@@ -95,50 +134,85 @@ pub async fn list_flows<FS: FlowService + StepService + FieldService, ES: Estima
 #[utoipa::path(
     put,
     path = "/api/v1/flows/{flow_id}",
-    params(("flow_id" = String, Path, description = "Flow UUID")),
+    params(
+        ("flow_id" = String, Path, description = "Flow UUID"),
+        ("If-Match" = String, Header, description = "ETag of the flow"),
+    ),
     request_body = UpdateFlowMetadataRequest,
     responses(
-        (status = 200, description = "Flow updated", body = FlowResponse),
+        (status = 200, description = "Flow updated", body = FlowResponse, headers(("ETag" = String, description = "Version of the flow"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Flow changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "flows"
 )]
-pub async fn update_flow_metadata<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn update_flow_metadata<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<UpdateFlowMetadataRequest>,
-) -> ApiResult<Json<ApiResponse<FlowResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<FlowResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
     let flow = state
         .flow_service
-        .update_flow_metadata(flow_id, Some(request.name), request.description)
+        .update_flow_metadata(
+            &organization,
+            &actor,
+            flow_id,
+            Some(request.name),
+            request.description,
+            version,
+        )
         .await?;
 
+    let etag = ETag(flow.version);
     let response = map_flow_to_response(flow);
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((etag, Json(ApiResponse::success(response))))
 }
 
 /// Delete a flow
 #[utoipa::path(
     delete,
     path = "/api/v1/flows/{flow_id}",
-    params(("flow_id" = String, Path, description = "Flow UUID")),
+    params(
+        ("flow_id" = String, Path, description = "Flow UUID"),
+        ("If-Match" = String, Header, description = "ETag of the flow"),
+    ),
     responses(
         (status = 200, description = "Flow deleted", body = MessageResponse),
         (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Flow changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "flows"
 )]
-pub async fn delete_flow<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn delete_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
+    IfMatch(version): IfMatch,
 ) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
-    state.flow_service.delete_flow(flow_id).await?;
+    state
+        .flow_service
+        .delete_flow(&organization, &actor, flow_id, version)
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -147,3 +221,101 @@ pub async fn delete_flow<FS: FlowService + StepService + FieldService, ES: Estim
         ))),
     ))
 }
+
+/// Reorder all steps and fields of a flow at once
+///
+/// Lists every step of the flow in order, each with all of its fields in
+/// order. Fields listed under another step are moved there. The whole layout
+/// is applied in one transaction.
+#[utoipa::path(
+    put,
+    path = "/api/v1/flows/{flow_id}/layout",
+    params(
+        ("flow_id" = String, Path, description = "Flow UUID"),
+        ("If-Match" = String, Header, description = "ETag of the flow"),
+    ),
+    request_body = ReorderFlowRequest,
+    responses(
+        (status = 200, description = "Flow reordered, returns updated flow", body = FlowResponse, headers(("ETag" = String, description = "Version of the flow"))),
+        (status = 400, description = "Layout does not list every step and field of the flow exactly once"),
+        (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Flow changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+    ),
+    tag = "flows"
+)]
+pub async fn reorder_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(flow_id): Path<String>,
+    IfMatch(version): IfMatch,
+    Json(request): Json<ReorderFlowRequest>,
+) -> ApiResult<(ETag, Json<ApiResponse<FlowResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    request.validate()?;
+
+    let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
+    let layout = FlowLayout {
+        steps: request
+            .steps
+            .into_iter()
+            .map(|step| StepLayout {
+                step_id: StepId::from_uuid(step.step_id),
+                field_ids: step.field_ids.into_iter().map(FieldId::from_uuid).collect(),
+            })
+            .collect(),
+    };
+    let flow = state
+        .flow_service
+        .reorder_flow(&organization, &actor, flow_id, layout, version)
+        .await?;
+    let etag = ETag(flow.version);
+    let response = map_flow_to_response(flow);
+
+    Ok((etag, Json(ApiResponse::success(response))))
+}
+
+/// Rebalance the ranks of a flow's steps and fields
+///
+/// Ranks are rebalanced automatically when a step or field can no longer be
+/// placed between its neighbours; this triggers it for the whole flow.
+#[utoipa::path(
+    post,
+    path = "/api/v1/flows/{flow_id}/rebalance",
+    params(
+        ("flow_id" = String, Path, description = "Flow UUID"),
+        ("If-Match" = String, Header, description = "ETag of the flow"),
+    ),
+    responses(
+        (status = 200, description = "Ranks rebalanced, returns updated flow", body = FlowResponse, headers(("ETag" = String, description = "Version of the flow"))),
+        (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Flow changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
+    ),
+    tag = "flows"
+)]
+pub async fn rebalance_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(flow_id): Path<String>,
+    IfMatch(version): IfMatch,
+) -> ApiResult<(ETag, Json<ApiResponse<FlowResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
+    let flow = state
+        .flow_service
+        .rebalance_flow(&organization, &actor, flow_id, version)
+        .await?;
+    let etag = ETag(flow.version);
+    let response = map_flow_to_response(flow);
+
+    Ok((etag, Json(ApiResponse::success(response))))
+}
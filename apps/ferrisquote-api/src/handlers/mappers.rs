@@ -1,10 +1,41 @@
-use ferrisquote_domain::{Field, FieldConfig, Flow, Step};
+use std::str::FromStr;
+
+use ferrisquote_domain::{Cursor, Field, FieldConfig, Flow, ListQuery, ListSort, SortOrder, Step};
 
 use crate::{
-    dto::{FieldConfigDto, FieldResponse, FlowResponse, StepResponse},
+    dto::{FieldConfigDto, FieldResponse, FlowResponse, ListParams, StepResponse},
     error::ApiError,
 };
 
+/// Convert listing query parameters to a domain ListQuery.
+pub fn map_list_params<C: FromStr>(params: ListParams) -> Result<ListQuery<C>, ApiError> {
+    let sort = params
+        .sort
+        .as_deref()
+        .map(str::parse::<ListSort>)
+        .transpose()?
+        .unwrap_or_default();
+    let order = params
+        .order
+        .as_deref()
+        .map(str::parse::<SortOrder>)
+        .transpose()?
+        .unwrap_or_else(|| sort.default_order());
+    let defaults = ListQuery::<C>::default();
+
+    Ok(ListQuery {
+        search: params.search.filter(|search| !search.is_empty()),
+        sort,
+        order,
+        after: params
+            .cursor
+            .as_deref()
+            .map(str::parse::<Cursor<C>>)
+            .transpose()?,
+        limit: params.limit.unwrap_or(defaults.limit),
+    })
+}
+
 /// Convert domain Flow to FlowResponse DTO
 pub fn map_flow_to_response(flow: Flow) -> FlowResponse {
     FlowResponse {
@@ -12,6 +43,7 @@ pub fn map_flow_to_response(flow: Flow) -> FlowResponse {
         name: flow.name,
         description: flow.description,
         steps: flow.steps.into_iter().map(map_step_to_response).collect(),
+        version: flow.version,
     }
 }
 
@@ -27,6 +59,7 @@ pub fn map_step_to_response(step: Step) -> StepResponse {
         min_repeats: step.min_repeats,
         max_repeats: step.max_repeats,
         fields: step.fields.into_iter().map(map_field_to_response).collect(),
+        version: step.version,
     }
 }
 
@@ -39,6 +72,7 @@ pub fn map_field_to_response(field: Field) -> FieldResponse {
         description: field.description,
         rank: field.rank,
         config: map_field_config_to_dto(field.config),
+        version: field.version,
     }
 }
 
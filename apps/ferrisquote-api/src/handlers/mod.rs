@@ -1,9 +1,23 @@
+pub mod api_key_handlers;
+pub mod audit_handlers;
+pub mod auth_handlers;
+pub mod customer_handlers;
 pub mod estimator_handlers;
 pub mod field_handlers;
 pub mod flow_handlers;
 pub mod mappers;
+pub mod quote_handlers;
+pub mod runner_handlers;
+pub mod share_link_handlers;
 pub mod step_handlers;
+pub use api_key_handlers::*;
+pub use audit_handlers::*;
+pub use auth_handlers::*;
+pub use customer_handlers::*;
 pub use estimator_handlers::*;
 pub use field_handlers::*;
 pub use flow_handlers::*;
+pub use quote_handlers::*;
+pub use runner_handlers::*;
+pub use share_link_handlers::*;
 pub use step_handlers::*;
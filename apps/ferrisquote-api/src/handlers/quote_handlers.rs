@@ -0,0 +1,470 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use ferrisquote_domain::domain::{
+    estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+    quote::ports::QuoteService,
+};
+use ferrisquote_domain::{
+    CustomerId, FlowId, Permission, Quote, QuoteDiff, QuoteId, QuoteStatus, QuoteStatusChange,
+};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        AnswerChangeResponse, ApiResponse, CreateQuoteRequest, IterationAnswerChangeResponse,
+        LineItemChangeResponse, QuoteDiffResponse, QuoteHistoryResponse, QuoteListResponse,
+        QuoteResponse, QuoteStatusChangeResponse, QuoteStatusDto, QuoteTransitionDto,
+        UpdateQuoteRequest, UpdateQuoteStatusRequest,
+    },
+    error::ApiResult,
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
+};
+
+fn map_quote(q: Quote) -> QuoteResponse {
+    QuoteResponse {
+        id: q.id.into_uuid(),
+        root_id: q.root_id.into_uuid(),
+        revision: q.revision,
+        flow_id: q.flow_id.into_uuid(),
+        estimator_id: q.estimator_id.into_uuid(),
+        customer_id: q.customer_id.map(CustomerId::into_uuid),
+        status: map_status_to_dto(q.status),
+        field_values: q.submission.field_values,
+        iteration_values: q.submission.iteration_values,
+        iteration_counts: q.submission.iteration_counts,
+        results: q.results,
+        total: q.total,
+        validity_days: q.validity_days,
+        valid_until: q.valid_until,
+        created_at: q.created_at,
+        updated_at: q.updated_at,
+    }
+}
+
+fn map_status_change(c: QuoteStatusChange) -> QuoteStatusChangeResponse {
+    QuoteStatusChangeResponse {
+        from: map_status_to_dto(c.from),
+        to: map_status_to_dto(c.to),
+        reason: c.reason,
+        changed_at: c.changed_at,
+    }
+}
+
+fn map_diff(d: QuoteDiff) -> QuoteDiffResponse {
+    QuoteDiffResponse {
+        from_id: d.from_id.into_uuid(),
+        to_id: d.to_id.into_uuid(),
+        from_revision: d.from_revision,
+        to_revision: d.to_revision,
+        changed_answers: d
+            .changed_answers
+            .into_iter()
+            .map(|c| AnswerChangeResponse {
+                key: c.key,
+                before: c.before,
+                after: c.after,
+            })
+            .collect(),
+        changed_iteration_answers: d
+            .changed_iteration_answers
+            .into_iter()
+            .map(|c| IterationAnswerChangeResponse {
+                key: c.key,
+                before: c.before,
+                after: c.after,
+            })
+            .collect(),
+        changed_line_items: d
+            .changed_line_items
+            .into_iter()
+            .map(|c| LineItemChangeResponse {
+                name: c.key,
+                before: c.before,
+                after: c.after,
+            })
+            .collect(),
+        total_before: d.total_before,
+        total_after: d.total_after,
+        total_delta: d.total_delta,
+    }
+}
+
+fn map_status_to_dto(status: QuoteStatus) -> QuoteStatusDto {
+    match status {
+        QuoteStatus::Draft => QuoteStatusDto::Draft,
+        QuoteStatus::Sent => QuoteStatusDto::Sent,
+        QuoteStatus::Accepted => QuoteStatusDto::Accepted,
+        QuoteStatus::Rejected => QuoteStatusDto::Rejected,
+        QuoteStatus::Expired => QuoteStatusDto::Expired,
+        QuoteStatus::Superseded => QuoteStatusDto::Superseded,
+    }
+}
+
+fn map_transition_from_dto(status: QuoteTransitionDto) -> QuoteStatus {
+    match status {
+        QuoteTransitionDto::Sent => QuoteStatus::Sent,
+        QuoteTransitionDto::Accepted => QuoteStatus::Accepted,
+        QuoteTransitionDto::Rejected => QuoteStatus::Rejected,
+    }
+}
+
+// ============================================================================
+// Quote CRUD
+// ============================================================================
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/estimators/{estimator_id}/quotes",
+    params(("estimator_id" = String, Path, description = "Estimator UUID")),
+    request_body = CreateQuoteRequest,
+    responses(
+        (status = 201, description = "Draft quote created", body = QuoteResponse),
+        (status = 400, description = "Validation or evaluation error"),
+        (status = 404, description = "Estimator or customer not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn create_quote<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(estimator_id): Path<String>,
+    Json(request): Json<CreateQuoteRequest>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<QuoteResponse>>)> {
+    identity.authorize(&state.policy, Permission::QuoteWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    request.validate()?;
+
+    let estimator_id = EstimatorId::from_uuid(uuid::Uuid::parse_str(&estimator_id)?);
+    let submission = SubmissionData {
+        field_values: request.field_values,
+        iteration_values: request.iteration_values,
+        iteration_counts: request.iteration_counts,
+    };
+    let customer_id = request.customer_id.map(CustomerId::from_uuid);
+    let quote = state
+        .quote_service
+        .create_quote(
+            &organization,
+            &actor,
+            estimator_id,
+            submission,
+            customer_id,
+            request.validity_days,
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(map_quote(quote))),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/flows/{flow_id}/quotes",
+    params(("flow_id" = String, Path, description = "Flow UUID")),
+    responses(
+        (status = 200, description = "List of quotes", body = QuoteListResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn list_quotes<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(flow_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuoteListResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
+    let quotes = state
+        .quote_service
+        .list_quotes_for_flow(&organization, flow_id)
+        .await?;
+
+    let response = QuoteListResponse {
+        quotes: quotes.into_iter().map(map_quote).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/customers/{customer_id}/quotes",
+    params(("customer_id" = String, Path, description = "Customer UUID")),
+    responses(
+        (status = 200, description = "Quotes prepared for the customer", body = QuoteListResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn list_customer_quotes<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(customer_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuoteListResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let customer_id = CustomerId::from_uuid(uuid::Uuid::parse_str(&customer_id)?);
+    let quotes = state
+        .quote_service
+        .list_quotes_for_customer(&organization, customer_id)
+        .await?;
+
+    let response = QuoteListResponse {
+        quotes: quotes.into_iter().map(map_quote).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quotes/{quote_id}",
+    params(("quote_id" = String, Path, description = "Quote UUID")),
+    responses(
+        (status = 200, description = "Quote found", body = QuoteResponse),
+        (status = 404, description = "Quote not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn get_quote<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuoteResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let quote = state.quote_service.get_quote(&organization, id).await?;
+
+    Ok(Json(ApiResponse::success(map_quote(quote))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/quotes/{quote_id}",
+    params(("quote_id" = String, Path, description = "Quote UUID")),
+    request_body = UpdateQuoteRequest,
+    responses(
+        (status = 200, description = "Draft quote updated and re-evaluated", body = QuoteResponse),
+        (status = 400, description = "Validation or evaluation error"),
+        (status = 404, description = "Quote not found"),
+        (status = 409, description = "Quote is no longer a draft"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn update_quote<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+    Json(request): Json<UpdateQuoteRequest>,
+) -> ApiResult<Json<ApiResponse<QuoteResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    request.validate()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let submission = SubmissionData {
+        field_values: request.field_values,
+        iteration_values: request.iteration_values,
+        iteration_counts: request.iteration_counts,
+    };
+    let quote = state
+        .quote_service
+        .update_quote_submission(&organization, &actor, id, submission, request.validity_days)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_quote(quote))))
+}
+
+// ============================================================================
+// Revisions
+// ============================================================================
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/quotes/{quote_id}/revisions",
+    params(("quote_id" = String, Path, description = "UUID of the revision to copy")),
+    responses(
+        (status = 201, description = "Draft revision created", body = QuoteResponse),
+        (status = 404, description = "Quote not found"),
+        (status = 409, description = "A draft revision already exists"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn create_quote_revision<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<QuoteResponse>>)> {
+    identity.authorize(&state.policy, Permission::QuoteWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let quote = state
+        .quote_service
+        .create_revision(&organization, &actor, id)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(map_quote(quote))),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quotes/{quote_id}/revisions",
+    params(("quote_id" = String, Path, description = "UUID of any revision of the quote")),
+    responses(
+        (status = 200, description = "Revisions, oldest first", body = QuoteListResponse),
+        (status = 404, description = "Quote not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn list_quote_revisions<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuoteListResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let quotes = state
+        .quote_service
+        .list_revisions(&organization, id)
+        .await?;
+
+    let response = QuoteListResponse {
+        quotes: quotes.into_iter().map(map_quote).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quotes/{quote_id}/diff/{other_id}",
+    params(
+        ("quote_id" = String, Path, description = "UUID of the base revision"),
+        ("other_id" = String, Path, description = "UUID of the revision to compare against"),
+    ),
+    responses(
+        (status = 200, description = "Differences from quote_id to other_id", body = QuoteDiffResponse),
+        (status = 400, description = "Quotes are not revisions of the same quote"),
+        (status = 404, description = "Quote not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn diff_quote_revisions<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path((quote_id, other_id)): Path<(String, String)>,
+) -> ApiResult<Json<ApiResponse<QuoteDiffResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let from_id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let to_id = QuoteId::from_uuid(uuid::Uuid::parse_str(&other_id)?);
+    let diff = state
+        .quote_service
+        .diff_revisions(&organization, from_id, to_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_diff(diff))))
+}
+
+// ============================================================================
+// Status transitions
+// ============================================================================
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/quotes/{quote_id}/status",
+    params(("quote_id" = String, Path, description = "Quote UUID")),
+    request_body = UpdateQuoteStatusRequest,
+    responses(
+        (status = 200, description = "Quote status changed", body = QuoteResponse),
+        (status = 404, description = "Quote not found"),
+        (status = 409, description = "Transition not allowed from the current status"),
+        (status = 403, description = "Missing permission; accepting or rejecting requires quote:approve"),
+    ),
+    tag = "quotes"
+)]
+pub async fn update_quote_status<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+    Json(request): Json<UpdateQuoteStatusRequest>,
+) -> ApiResult<Json<ApiResponse<QuoteResponse>>> {
+    request.validate()?;
+
+    let status = map_transition_from_dto(request.status);
+    let permission = match status {
+        QuoteStatus::Accepted | QuoteStatus::Rejected => Permission::QuoteApprove,
+        _ => Permission::QuoteWrite,
+    };
+    identity.authorize(&state.policy, permission)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let quote = state
+        .quote_service
+        .transition_quote(&organization, &actor, id, status)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_quote(quote))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quotes/{quote_id}/history",
+    params(("quote_id" = String, Path, description = "Quote UUID")),
+    responses(
+        (status = 200, description = "Status history, oldest first", body = QuoteHistoryResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "quotes"
+)]
+pub async fn get_quote_history<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(quote_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<QuoteHistoryResponse>>> {
+    identity.authorize(&state.policy, Permission::QuoteRead)?;
+    let organization = identity.organization()?;
+
+    let id = QuoteId::from_uuid(uuid::Uuid::parse_str(&quote_id)?);
+    let history = state
+        .quote_service
+        .get_quote_history(&organization, id)
+        .await?;
+
+    let response = QuoteHistoryResponse {
+        history: history.into_iter().map(map_status_change).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
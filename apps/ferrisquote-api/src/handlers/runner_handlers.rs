@@ -0,0 +1,164 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use ferrisquote_domain::domain::runner::ports::RunnerService;
+use ferrisquote_domain::{Flow, RunnerSession, RunnerSessionId, StepId};
+use validator::Validate;
+
+use crate::{
+    dto::{
+        ApiResponse, EstimateResponse, PublicFieldResponse, PublicFlowResponse, PublicStepResponse,
+        RunnerSessionResponse, SubmitStepRequest,
+    },
+    error::ApiResult,
+    handlers::mappers::map_field_config_to_dto,
+    state::{AppServices, AppState},
+};
+
+/// Strip internal details (ranks, field ids) from a flow before exposing it publicly.
+fn map_public_flow(flow: Flow) -> PublicFlowResponse {
+    PublicFlowResponse {
+        name: flow.name,
+        description: flow.description,
+        steps: flow
+            .steps
+            .into_iter()
+            .map(|step| PublicStepResponse {
+                id: step.id.into_uuid(),
+                title: step.title,
+                description: step.description,
+                is_repeatable: step.is_repeatable,
+                repeat_label: step.repeat_label,
+                min_repeats: step.min_repeats,
+                max_repeats: step.max_repeats,
+                fields: step
+                    .fields
+                    .into_iter()
+                    .map(|field| PublicFieldResponse {
+                        key: field.key,
+                        label: field.label,
+                        description: field.description,
+                        config: map_field_config_to_dto(field.config),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn map_session(session: RunnerSession) -> RunnerSessionResponse {
+    RunnerSessionResponse {
+        session_id: session.id.into_uuid(),
+        completed_steps: session
+            .completed_steps
+            .into_iter()
+            .map(StepId::into_uuid)
+            .collect(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/flows/{token}",
+    params(("token" = String, Path, description = "Share link token")),
+    responses(
+        (status = 200, description = "Shared flow", body = PublicFlowResponse),
+        (status = 404, description = "Unknown, revoked or expired link"),
+    ),
+    security(()),
+    tag = "public"
+)]
+pub async fn get_shared_flow<S: AppServices>(
+    State(state): State<AppState<S>>,
+    Path(token): Path<String>,
+) -> ApiResult<Json<ApiResponse<PublicFlowResponse>>> {
+    let flow = state.runner_service.get_shared_flow(&token).await?;
+
+    Ok(Json(ApiResponse::success(map_public_flow(flow))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/public/flows/{token}/sessions",
+    params(("token" = String, Path, description = "Share link token")),
+    responses(
+        (status = 201, description = "Session started", body = RunnerSessionResponse),
+        (status = 404, description = "Unknown, revoked or expired link"),
+    ),
+    security(()),
+    tag = "public"
+)]
+pub async fn start_runner_session<S: AppServices>(
+    State(state): State<AppState<S>>,
+    Path(token): Path<String>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<RunnerSessionResponse>>)> {
+    let session = state.runner_service.start_session(&token).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(map_session(session))),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/public/flows/{token}/sessions/{session_id}/steps/{step_id}",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+        ("session_id" = String, Path, description = "Runner session UUID"),
+        ("step_id" = String, Path, description = "Step UUID"),
+    ),
+    request_body = SubmitStepRequest,
+    responses(
+        (status = 200, description = "Step answers recorded", body = RunnerSessionResponse),
+        (status = 400, description = "Invalid answers"),
+        (status = 404, description = "Link, session or step not found"),
+    ),
+    security(()),
+    tag = "public"
+)]
+pub async fn submit_runner_step<S: AppServices>(
+    State(state): State<AppState<S>>,
+    Path((token, session_id, step_id)): Path<(String, String, String)>,
+    Json(request): Json<SubmitStepRequest>,
+) -> ApiResult<Json<ApiResponse<RunnerSessionResponse>>> {
+    request.validate()?;
+
+    let session_id = RunnerSessionId::from_uuid(uuid::Uuid::parse_str(&session_id)?);
+    let step_id = StepId::from_uuid(uuid::Uuid::parse_str(&step_id)?);
+
+    let session = state
+        .runner_service
+        .submit_step(&token, session_id, step_id, request.iterations)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_session(session))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/public/flows/{token}/sessions/{session_id}/estimate",
+    params(
+        ("token" = String, Path, description = "Share link token"),
+        ("session_id" = String, Path, description = "Runner session UUID"),
+    ),
+    responses(
+        (status = 200, description = "Estimated total", body = EstimateResponse),
+        (status = 400, description = "Some steps are still unanswered"),
+        (status = 404, description = "Link or session not found"),
+    ),
+    security(()),
+    tag = "public"
+)]
+pub async fn estimate_runner_session<S: AppServices>(
+    State(state): State<AppState<S>>,
+    Path((token, session_id)): Path<(String, String)>,
+) -> ApiResult<Json<ApiResponse<EstimateResponse>>> {
+    let session_id = RunnerSessionId::from_uuid(uuid::Uuid::parse_str(&session_id)?);
+
+    let total = state.runner_service.estimate(&token, session_id).await?;
+
+    Ok(Json(ApiResponse::success(EstimateResponse { total })))
+}
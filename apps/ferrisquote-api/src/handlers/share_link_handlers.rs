@@ -0,0 +1,135 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use ferrisquote_domain::domain::runner::ports::ShareLinkService;
+use ferrisquote_domain::{EstimatorId, FlowId, Permission, ShareLink, ShareLinkId};
+use validator::Validate;
+
+use crate::{
+    dto::{ApiResponse, CreateShareLinkRequest, ShareLinkListResponse, ShareLinkResponse},
+    error::ApiResult,
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
+};
+
+fn map_share_link(link: ShareLink) -> ShareLinkResponse {
+    ShareLinkResponse {
+        active: link.is_active(Utc::now()),
+        id: link.id.into_uuid(),
+        flow_id: link.flow_id.into_uuid(),
+        estimator_id: link.estimator_id.into_uuid(),
+        token: link.token,
+        created_at: link.created_at,
+        expires_at: link.expires_at,
+        revoked_at: link.revoked_at,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/flows/{flow_id}/share-links",
+    params(("flow_id" = String, Path, description = "Flow UUID")),
+    request_body = CreateShareLinkRequest,
+    responses(
+        (status = 201, description = "Share link created", body = ShareLinkResponse),
+        (status = 400, description = "Estimator does not belong to the flow"),
+        (status = 404, description = "Flow or estimator not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "share_links"
+)]
+pub async fn create_share_link<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(flow_id): Path<String>,
+    Json(request): Json<CreateShareLinkRequest>,
+) -> ApiResult<(StatusCode, Json<ApiResponse<ShareLinkResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    request.validate()?;
+
+    let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
+
+    let link = state
+        .runner_service
+        .create_share_link(
+            &organization,
+            &actor,
+            flow_id,
+            EstimatorId::from_uuid(request.estimator_id),
+            request.expires_at,
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success(map_share_link(link))),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/flows/{flow_id}/share-links",
+    params(("flow_id" = String, Path, description = "Flow UUID")),
+    responses(
+        (status = 200, description = "Share links of the flow", body = ShareLinkListResponse),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "share_links"
+)]
+pub async fn list_share_links<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(flow_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<ShareLinkListResponse>>> {
+    identity.authorize(&state.policy, Permission::FlowRead)?;
+    let organization = identity.organization()?;
+
+    let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
+
+    let links = state
+        .runner_service
+        .list_share_links(&organization, flow_id)
+        .await?;
+
+    let response = ShareLinkListResponse {
+        share_links: links.into_iter().map(map_share_link).collect(),
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/share-links/{share_link_id}",
+    params(("share_link_id" = String, Path, description = "Share link UUID")),
+    responses(
+        (status = 200, description = "Share link revoked", body = ShareLinkResponse),
+        (status = 404, description = "Share link not found"),
+        (status = 403, description = "Missing permission"),
+    ),
+    tag = "share_links"
+)]
+pub async fn revoke_share_link<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
+    Path(share_link_id): Path<String>,
+) -> ApiResult<Json<ApiResponse<ShareLinkResponse>>> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
+    let id = ShareLinkId::from_uuid(uuid::Uuid::parse_str(&share_link_id)?);
+
+    let link = state
+        .runner_service
+        .revoke_share_link(&organization, &actor, id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(map_share_link(link))))
+}
@@ -3,16 +3,15 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use ferrisquote_domain::{
-    domain::{estimator::ports::EstimatorService, flows::ports::{FieldService, FlowService, StepService}},
-    FlowId, StepId,
-};
+use ferrisquote_domain::{FlowId, Permission, StepId, domain::flows::ports::StepService};
 use validator::Validate;
 
 use crate::{
     dto::{ApiResponse, CreateStepRequest, FlowResponse, MessageResponse, ReorderStepRequest, StepResponse, UpdateStepMetadataRequest},
     error::ApiResult,
-    state::AppState,
+    etag::{ETag, IfMatch},
+    middleware::CurrentIdentity,
+    state::{AppServices, AppState},
 };
 
 use super::mappers::{map_flow_to_response, map_step_to_response};
@@ -24,43 +23,72 @@ use super::mappers::{map_flow_to_response, map_step_to_response};
     params(("flow_id" = String, Path, description = "Flow UUID")),
     request_body = CreateStepRequest,
     responses(
-        (status = 201, description = "Step created", body = StepResponse),
+        (status = 201, description = "Step created", body = StepResponse, headers(("ETag" = String, description = "Version of the step"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Flow not found"),
+        (status = 403, description = "Missing permission"),
     ),
     tag = "steps"
 )]
-pub async fn add_step<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn add_step<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(flow_id): Path<String>,
     Json(request): Json<CreateStepRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<StepResponse>>)> {
+) -> ApiResult<(StatusCode, ETag, Json<ApiResponse<StepResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let flow_id = FlowId::from_uuid(uuid::Uuid::parse_str(&flow_id)?);
-    let step = state.flow_service.add_step(flow_id, request.title).await?;
+    let step = state
+        .flow_service
+        .add_step(&organization, &actor, flow_id, request.title)
+        .await?;
+    let etag = ETag(step.version);
     let response = map_step_to_response(step);
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+    Ok((
+        StatusCode::CREATED,
+        etag,
+        Json(ApiResponse::success(response)),
+    ))
 }
 
 /// Remove a step from a flow
 #[utoipa::path(
     delete,
     path = "/api/v1/flows/steps/{step_id}",
-    params(("step_id" = String, Path, description = "Step UUID")),
+    params(
+        ("step_id" = String, Path, description = "Step UUID"),
+        ("If-Match" = String, Header, description = "ETag of the step"),
+    ),
     responses(
         (status = 200, description = "Step removed", body = MessageResponse),
         (status = 404, description = "Step not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Step changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "steps"
 )]
-pub async fn remove_step<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn remove_step<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(step_id): Path<String>,
+    IfMatch(version): IfMatch,
 ) -> ApiResult<(StatusCode, Json<ApiResponse<MessageResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     let step_id = StepId::from_uuid(uuid::Uuid::parse_str(&step_id)?);
-    state.flow_service.remove_step(step_id).await?;
+    state
+        .flow_service
+        .remove_step(&organization, &actor, step_id, version)
+        .await?;
 
     Ok((
         StatusCode::OK,
@@ -74,32 +102,41 @@ pub async fn remove_step<FS: FlowService + StepService + FieldService, ES: Estim
 #[utoipa::path(
     put,
     path = "/api/v1/flows/steps/{step_id}/reorder",
-    params(("step_id" = String, Path, description = "Step UUID")),
+    params(
+        ("step_id" = String, Path, description = "Step UUID"),
+        ("If-Match" = String, Header, description = "ETag of the step"),
+    ),
     request_body = ReorderStepRequest,
     responses(
         (status = 200, description = "Step reordered, returns updated flow", body = FlowResponse),
+        (status = 400, description = "Neighbours are not steps of the same flow, or are out of order"),
         (status = 404, description = "Step not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Step changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "steps"
 )]
-pub async fn reorder_step<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn reorder_step<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(step_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<ReorderStepRequest>,
 ) -> ApiResult<Json<ApiResponse<crate::dto::FlowResponse>>> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let step_id = StepId::from_uuid(uuid::Uuid::parse_str(&step_id)?);
-    let after_id = request
-        .after_id
-        .map(|id| StepId::from_uuid(id));
-    let before_id = request
-        .before_id
-        .map(|id| StepId::from_uuid(id));
+    let after_id = request.after_id.map(StepId::from_uuid);
+    let before_id = request.before_id.map(StepId::from_uuid);
 
     let flow = state
         .flow_service
-        .reorder_step(step_id, after_id, before_id)
+        .reorder_step(&organization, &actor, step_id, after_id, before_id, version)
         .await?;
 
     let response = map_flow_to_response(flow);
@@ -111,26 +148,40 @@ pub async fn reorder_step<FS: FlowService + StepService + FieldService, ES: Esti
 #[utoipa::path(
     put,
     path = "/api/v1/flows/steps/{step_id}",
-    params(("step_id" = String, Path, description = "Step UUID")),
+    params(
+        ("step_id" = String, Path, description = "Step UUID"),
+        ("If-Match" = String, Header, description = "ETag of the step"),
+    ),
     request_body = UpdateStepMetadataRequest,
     responses(
-        (status = 200, description = "Step updated", body = StepResponse),
+        (status = 200, description = "Step updated", body = StepResponse, headers(("ETag" = String, description = "Version of the step"))),
         (status = 400, description = "Validation error"),
         (status = 404, description = "Step not found"),
+        (status = 403, description = "Missing permission"),
+        (status = 409, description = "Step changed since the given ETag"),
+        (status = 428, description = "If-Match header missing"),
     ),
     tag = "steps"
 )]
-pub async fn update_step_metadata<FS: FlowService + StepService + FieldService, ES: EstimatorService>(
-    State(state): State<AppState<FS, ES>>,
+pub async fn update_step_metadata<S: AppServices>(
+    State(state): State<AppState<S>>,
+    identity: CurrentIdentity,
     Path(step_id): Path<String>,
+    IfMatch(version): IfMatch,
     Json(request): Json<UpdateStepMetadataRequest>,
-) -> ApiResult<Json<ApiResponse<StepResponse>>> {
+) -> ApiResult<(ETag, Json<ApiResponse<StepResponse>>)> {
+    identity.authorize(&state.policy, Permission::FlowWrite)?;
+    let organization = identity.organization()?;
+    let actor = identity.actor()?;
+
     request.validate()?;
 
     let step_id = StepId::from_uuid(uuid::Uuid::parse_str(&step_id)?);
     let step = state
         .flow_service
         .update_step_metadata(
+            &organization,
+            &actor,
             step_id,
             request.title,
             request.description,
@@ -138,10 +189,12 @@ pub async fn update_step_metadata<FS: FlowService + StepService + FieldService,
             request.repeat_label,
             request.min_repeats,
             request.max_repeats,
+            version,
         )
         .await?;
 
+    let etag = ETag(step.version);
     let response = map_step_to_response(step);
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((etag, Json(ApiResponse::success(response))))
 }
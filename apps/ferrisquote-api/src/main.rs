@@ -1,27 +1,86 @@
+use ferrisquote_auth::{
+    domain::entities::{claims::DEFAULT_ORGANIZATION_CLAIM, role_mapping::RoleMapping},
+    infrastructure::{
+        ferriskey_repository::FerrisKeyRepository,
+        static_key_repository::StaticKeyRepository,
+        token_validation::{DEFAULT_LEEWAY, TokenValidation},
+    },
+};
 use ferrisquote_domain::domain::{
-    estimator::services::EstimatorServiceImpl,
-    flows::services::FlowServiceImpl,
-    rank::services::LexoRankProvider,
+    api_key::services::ApiKeyServiceImpl, audit::services::AuditServiceImpl,
+    authorization::entities::policy::RolePolicy, customer::services::CustomerServiceImpl,
+    estimator::services::EstimatorServiceImpl, flows::services::FlowServiceImpl,
+    quote::services::QuoteServiceImpl, rank::services::LexoRankProvider,
+    runner::services::RunnerServiceImpl,
 };
 use ferrisquote_postgres::repositories::{
-    estimator_repository::PostgresEstimatorRepository,
-    flow_repository::PostgresFlowRepository,
+    api_key_repository::PostgresApiKeyRepository, audit_repository::PostgresAuditRepository,
+    customer_repository::PostgresCustomerRepository,
+    estimator_repository::PostgresEstimatorRepository, flow_repository::PostgresFlowRepository,
+    quote_repository::PostgresQuoteRepository, runner_repository::PostgresRunnerRepository,
 };
+use ferrisquote_postgres::unit_of_work::PostgresUnitOfWork;
 use sqlx::postgres::PgPoolOptions;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod api_key_auth;
 mod error;
+mod etag;
+mod middleware;
 mod openapi;
+mod scheduler;
 mod state;
+#[cfg(test)]
+mod tests;
 
+use api_key_auth::ApiKeyAuthRepository;
 use routes::build_routes::build_routes;
-use state::AppState;
+use state::{AppServices, AppState};
 
 pub mod dto;
 pub mod handlers;
 pub mod routes;
 
+/// The services the API runs on, backed by Postgres.
+struct PostgresServices;
+
+impl AppServices for PostgresServices {
+    type Flows = FlowServiceImpl<
+        PostgresFlowRepository,
+        PostgresFlowRepository,
+        PostgresFlowRepository,
+        LexoRankProvider,
+        PostgresAuditRepository,
+        PostgresUnitOfWork,
+    >;
+    type Estimators = EstimatorServiceImpl<
+        PostgresEstimatorRepository,
+        LexoRankProvider,
+        PostgresCustomerRepository,
+        PostgresAuditRepository,
+        PostgresUnitOfWork,
+    >;
+    type Quotes = QuoteServiceImpl<
+        PostgresQuoteRepository,
+        PostgresEstimatorRepository,
+        PostgresCustomerRepository,
+        PostgresAuditRepository,
+        PostgresUnitOfWork,
+    >;
+    type Customers = CustomerServiceImpl<PostgresCustomerRepository>;
+    type Runner = RunnerServiceImpl<
+        PostgresRunnerRepository,
+        PostgresRunnerRepository,
+        PostgresFlowRepository,
+        PostgresEstimatorRepository,
+        PostgresAuditRepository,
+        PostgresUnitOfWork,
+    >;
+    type ApiKeys = ApiKeyServiceImpl<PostgresApiKeyRepository>;
+    type Audit = AuditServiceImpl<PostgresAuditRepository>;
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Env
@@ -36,6 +95,13 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // `migrate` (or `--migrate`) applies pending migrations and exits without serving.
+    let migrate_only = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("migrate" | "--migrate") => true,
+        Some(other) => anyhow::bail!("Unknown command {other}, expected migrate"),
+    };
+
     tracing::info!("Starting FerrisQuote API (minimal)");
 
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
@@ -46,24 +112,150 @@ async fn main() -> anyhow::Result<()> {
         .await
         .expect("failed to connect to Postgres");
 
+    if migrate_only {
+        ferrisquote_postgres::migrate(&pool).await?;
+        return Ok(());
+    }
+    if env_flag("AUTO_MIGRATE") {
+        ferrisquote_postgres::migrate(&pool).await?;
+    }
+
     let pg_pool = Arc::new(pool);
 
     let flow_repo = PostgresFlowRepository::with_pool(pg_pool.clone());
-    let estimator_repo = PostgresEstimatorRepository::with_pool(pg_pool);
+    let estimator_repo = PostgresEstimatorRepository::with_pool(pg_pool.clone());
+    let quote_repo = PostgresQuoteRepository::with_pool(pg_pool.clone());
+    let customer_repo = PostgresCustomerRepository::with_pool(pg_pool.clone());
+    let runner_repo = PostgresRunnerRepository::with_pool(pg_pool.clone());
+    let api_key_repo = PostgresApiKeyRepository::with_pool(pg_pool.clone());
+    let unit_of_work = PostgresUnitOfWork::with_pool(pg_pool.clone());
+    let audit_repo = PostgresAuditRepository::with_pool(pg_pool);
     let rank_service = LexoRankProvider;
 
     let flow_service = FlowServiceImpl::new(
         flow_repo.clone(),
         flow_repo.clone(),
         flow_repo.clone(),
+        rank_service.clone(),
+        audit_repo.clone(),
+        unit_of_work.clone(),
+    );
+
+    let estimator_service = EstimatorServiceImpl::new(
+        estimator_repo.clone(),
         rank_service,
+        customer_repo.clone(),
+        audit_repo.clone(),
+        unit_of_work.clone(),
+    );
+
+    let quote_service = Arc::new(QuoteServiceImpl::new(
+        quote_repo,
+        estimator_repo.clone(),
+        customer_repo.clone(),
+        audit_repo.clone(),
+        unit_of_work.clone(),
+    ));
+
+    let customer_service = CustomerServiceImpl::new(customer_repo);
+
+    let runner_service = RunnerServiceImpl::new(
+        runner_repo.clone(),
+        runner_repo,
+        flow_repo,
+        estimator_repo,
+        audit_repo.clone(),
+        unit_of_work,
     );
 
-    let estimator_service = EstimatorServiceImpl::new(estimator_repo);
+    let api_key_service = Arc::new(ApiKeyServiceImpl::new(api_key_repo));
 
-    let app_state = AppState::new(Arc::new(flow_service), Arc::new(estimator_service));
+    let audit_service = AuditServiceImpl::new(audit_repo);
 
-    let app = build_routes(app_state);
+    let expiry_interval = std::env::var("QUOTE_EXPIRY_INTERVAL_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(60);
+    scheduler::spawn_quote_expiry(quote_service.clone(), Duration::from_secs(expiry_interval));
+
+    let app_state = AppState::<PostgresServices>::new(
+        Arc::new(flow_service),
+        Arc::new(estimator_service),
+        quote_service,
+        Arc::new(customer_service),
+        Arc::new(runner_service),
+        api_key_service.clone(),
+        Arc::new(audit_service),
+        Arc::new(RolePolicy::standard()),
+    );
+
+    let auth_issuer = std::env::var("AUTH_ISSUER").expect("AUTH_ISSUER must be set");
+    let token_validation = TokenValidation {
+        audiences: env_list("AUTH_AUDIENCE"),
+        leeway: std::env::var("AUTH_LEEWAY_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map_or(DEFAULT_LEEWAY, Duration::from_secs),
+        ..TokenValidation::default()
+    };
+    let role_mapping = RoleMapping {
+        client_roles: env_list("AUTH_ROLE_CLIENTS"),
+        claim_paths: env_list("AUTH_ROLE_CLAIMS"),
+        ..RoleMapping::default()
+    };
+    let organization_claim = std::env::var("AUTH_ORGANIZATION_CLAIM")
+        .ok()
+        .map(|claim| claim.trim().to_string())
+        .filter(|claim| !claim.is_empty())
+        .unwrap_or_else(|| DEFAULT_ORGANIZATION_CLAIM.to_string());
+
+    let api_key_auth = Arc::new(ApiKeyAuthRepository::new(api_key_service));
+
+    // Bearer tokens are verified against the identity provider, or against local keys
+    // when the stack runs without one.
+    let auth_mode = std::env::var("AUTH_MODE").unwrap_or_else(|_| "oidc".to_string());
+    let app = match auth_mode.as_str() {
+        "oidc" => {
+            let auth_repo = FerrisKeyRepository::new(auth_issuer, None)
+                .with_validation(token_validation)
+                .with_role_mapping(role_mapping)
+                .with_organization_claim(organization_claim);
+            build_routes(app_state, Arc::new(auth_repo), api_key_auth)
+        }
+        "jwks_file" | "pem_file" | "hs256" => {
+            let auth_repo = match auth_mode.as_str() {
+                "jwks_file" => StaticKeyRepository::from_jwks_file(
+                    auth_issuer,
+                    std::env::var("AUTH_JWKS_FILE").expect("AUTH_JWKS_FILE must be set"),
+                )?,
+                "pem_file" => StaticKeyRepository::from_pem_file(
+                    auth_issuer,
+                    std::env::var("AUTH_PEM_FILE").expect("AUTH_PEM_FILE must be set"),
+                )?,
+                _ => StaticKeyRepository::from_secret(
+                    auth_issuer,
+                    std::env::var("AUTH_HS256_SECRET")
+                        .expect("AUTH_HS256_SECRET must be set")
+                        .as_bytes(),
+                ),
+            };
+            tracing::warn!("Validating bearer tokens with local keys ({auth_mode})");
+
+            // Keep the algorithms implied by the key source (HS256 only for a shared secret).
+            let algorithms = auth_repo.validation.algorithms.clone();
+            let auth_repo = auth_repo
+                .with_validation(TokenValidation {
+                    algorithms,
+                    ..token_validation
+                })
+                .with_role_mapping(role_mapping)
+                .with_organization_claim(organization_claim);
+            build_routes(app_state, Arc::new(auth_repo), api_key_auth)
+        }
+        other => {
+            anyhow::bail!("Unknown AUTH_MODE {other}, expected oidc, jwks_file, pem_file or hs256")
+        }
+    };
 
     let port = std::env::var("PORT")
         .ok()
@@ -77,3 +269,24 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Read a boolean environment variable, false when unset.
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        )
+    })
+}
+
+/// Read a comma separated environment variable, empty when unset.
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderMap, HeaderName, header::AUTHORIZATION, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use ferrisquote_auth::domain::{
+    entities::identity::Identity, error::AuthError, ports::AuthRepository,
+};
+use ferrisquote_domain::{
+    Actor, ActorKind, DomainError, OrganizationId, Permission, Principal, PrincipalKind, RolePolicy,
+};
+
+use crate::error::ApiError;
+
+/// Header carrying the secret of an API key.
+pub const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+
+/// Identity of the caller, as attached by [`require_identity`] or [`anonymous_identity`].
+///
+/// Rejects with 401 when used on a route that has neither layer.
+#[derive(Debug, Clone)]
+pub struct CurrentIdentity(pub Identity);
+
+impl CurrentIdentity {
+    /// The caller as seen by the authorization policy.
+    pub fn principal(&self) -> Principal {
+        let kind = match self.0 {
+            Identity::User(_) => PrincipalKind::User,
+            Identity::Client(_) => PrincipalKind::Client,
+            Identity::Anonymous => PrincipalKind::Anonymous,
+        };
+
+        Principal {
+            kind,
+            id: self.0.id().map(str::to_string),
+            roles: self.0.roles(),
+            scopes: self.0.scopes(),
+        }
+    }
+
+    /// Fail with 403 unless `policy` grants `permission` to the caller.
+    pub fn authorize(
+        &self,
+        policy: &RolePolicy,
+        permission: Permission,
+    ) -> Result<(), DomainError> {
+        policy.authorize(&self.principal(), permission)
+    }
+
+    /// The organization the caller acts for; every editor resource is scoped to it.
+    ///
+    /// Fails with 403 when the token names no single organization.
+    pub fn organization(&self) -> Result<OrganizationId, DomainError> {
+        self.0
+            .organization()
+            .map(OrganizationId::new)
+            .ok_or_else(|| DomainError::forbidden("Token is not bound to an organization"))
+    }
+
+    /// The caller as recorded in the audit log.
+    pub fn actor(&self) -> Result<Actor, DomainError> {
+        let kind = match self.0 {
+            Identity::User(_) => ActorKind::User,
+            Identity::Client(_) => ActorKind::Client,
+            Identity::Anonymous => {
+                return Err(DomainError::unauthorized("Authentication required"));
+            }
+        };
+
+        Ok(Actor::new(
+            kind,
+            self.0.id().map(str::to_string),
+            self.0.username().map(str::to_string),
+        ))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentIdentity {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Identity>()
+            .cloned()
+            .map(CurrentIdentity)
+            .ok_or_else(|| DomainError::unauthorized("Authentication required").into())
+    }
+}
+
+/// The ways [`require_identity`] can authenticate a caller.
+pub struct Authenticators<AR, KR> {
+    /// Validates OIDC bearer tokens.
+    pub bearer: Arc<AR>,
+    /// Validates API keys sent in [`API_KEY_HEADER`].
+    pub api_keys: Arc<KR>,
+}
+
+impl<AR, KR> Clone for Authenticators<AR, KR> {
+    fn clone(&self) -> Self {
+        Self {
+            bearer: self.bearer.clone(),
+            api_keys: self.api_keys.clone(),
+        }
+    }
+}
+
+/// Authenticate the API key or, failing that, the bearer token of the request and
+/// attach the resulting `Identity`.
+///
+/// Intended for `axum::middleware::from_fn_with_state` on the route groups that need a
+/// signed-in caller.
+pub async fn require_identity<AR: AuthRepository + 'static, KR: AuthRepository + 'static>(
+    State(auth): State<Authenticators<AR, KR>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let identity = if let Some(key) = api_key(request.headers()) {
+        let key = key.to_string();
+        auth.api_keys.identity(&key).await
+    } else {
+        let token = bearer_token(request.headers())
+            .ok_or_else(|| DomainError::unauthorized("Missing bearer token"))?
+            .to_string();
+        auth.bearer.identity(&token).await
+    }
+    .map_err(map_auth_error)?;
+
+    request.extensions_mut().insert(identity);
+    Ok(next.run(request).await)
+}
+
+/// Mark requests as coming from an anonymous visitor.
+///
+/// Applied to the public runner routes, which are reachable without credentials.
+pub async fn anonymous_identity(mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(Identity::Anonymous);
+    next.run(request).await
+}
+
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    let key = headers.get(API_KEY_HEADER)?.to_str().ok()?.trim();
+    (!key.is_empty()).then_some(key)
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let token = token.trim();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Token problems are the caller's fault (401); failing to reach the identity provider is ours.
+fn map_auth_error(error: AuthError) -> DomainError {
+    match error {
+        AuthError::Network { message } => {
+            DomainError::internal(format!("Identity provider unavailable: {message}"))
+        }
+        AuthError::Internal { message } => DomainError::internal(message),
+        other => DomainError::unauthorized(other.to_string()),
+    }
+}
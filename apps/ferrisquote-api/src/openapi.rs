@@ -1,12 +1,25 @@
-use utoipa::OpenApi;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
 
 use crate::dto::{
-    ApiResponse, CreateEstimatorRequest, CreateFieldRequest, CreateFlowRequest,
-    CreateStepRequest, CreateVariableRequest, EstimatorListResponse, EstimatorResponse,
-    EvaluateRequest, EvaluateResponse, EvaluateSubmissionRequest, FieldConfigDto, FieldResponse,
-    FlowListResponse, FlowResponse, FlowSummaryResponse, MessageResponse, MoveFieldRequest,
-    ReorderStepRequest, StepResponse, UpdateEstimatorRequest, UpdateFieldConfigRequest,
-    UpdateFlowMetadataRequest, UpdateStepMetadataRequest, UpdateVariableRequest, VariableResponse,
+    ActorResponse, AddressDto, AnswerChangeResponse, ApiKeyListResponse, ApiKeyResponse,
+    ApiResponse, AuditChangeResponse, AuditEntryResponse, AuditPageResponse, CreateApiKeyRequest,
+    CreateEstimatorRequest, CreateFieldRequest, CreateFlowRequest, CreateQuoteRequest,
+    CreateShareLinkRequest, CreateStepRequest, CreateVariableRequest, CreatedApiKeyResponse,
+    CustomerListResponse, CustomerRequest, CustomerResponse, EstimateResponse,
+    EstimatorListResponse, EstimatorResponse, EvaluateRequest, EvaluateResponse,
+    EvaluateSubmissionRequest, FieldConfigDto, FieldResponse, FlowListResponse, FlowResponse,
+    FlowSummaryResponse, IdentityKindDto, IdentityResponse, IterationAnswerChangeResponse,
+    LineItemChangeResponse, MessageResponse, MoveFieldRequest, PublicFieldResponse,
+    PublicFlowResponse, PublicStepResponse, QuoteDiffResponse, QuoteHistoryResponse,
+    QuoteListResponse, QuoteResponse, QuoteStatusChangeResponse, QuoteStatusDto,
+    QuoteTransitionDto, ReorderFlowRequest, ReorderStepRequest, ReorderVariableRequest,
+    RunnerSessionResponse, ShareLinkListResponse, ShareLinkResponse, StepOrderRequest,
+    StepResponse, SubmitStepRequest, UpdateEstimatorRequest, UpdateFieldConfigRequest,
+    UpdateFlowMetadataRequest, UpdateQuoteRequest, UpdateQuoteStatusRequest,
+    UpdateStepMetadataRequest, UpdateVariableRequest, VariableResponse,
 };
 
 #[derive(OpenApi)]
@@ -14,14 +27,19 @@ use crate::dto::{
     info(
         title = "FerrisQuote API",
         version = "0.1.0",
-        description = "API for managing quote flows, steps, fields and estimators"
+        description = "API for managing quote flows, steps, fields, estimators, quotes, customers and the public flow runner"
     ),
+    modifiers(&BearerAuth),
+    security(("bearer_auth" = []), ("api_key" = [])),
     paths(
+        crate::handlers::auth_handlers::get_current_identity,
         crate::handlers::flow_handlers::create_flow,
         crate::handlers::flow_handlers::list_flows,
         crate::handlers::flow_handlers::get_flow,
         crate::handlers::flow_handlers::update_flow_metadata,
         crate::handlers::flow_handlers::delete_flow,
+        crate::handlers::flow_handlers::reorder_flow,
+        crate::handlers::flow_handlers::rebalance_flow,
         crate::handlers::step_handlers::add_step,
         crate::handlers::step_handlers::remove_step,
         crate::handlers::step_handlers::reorder_step,
@@ -38,12 +56,43 @@ use crate::dto::{
         crate::handlers::estimator_handlers::add_variable,
         crate::handlers::estimator_handlers::update_variable,
         crate::handlers::estimator_handlers::remove_variable,
+        crate::handlers::estimator_handlers::reorder_variable,
         crate::handlers::estimator_handlers::evaluate,
         crate::handlers::estimator_handlers::evaluate_submission,
+        crate::handlers::quote_handlers::create_quote,
+        crate::handlers::quote_handlers::list_quotes,
+        crate::handlers::quote_handlers::list_customer_quotes,
+        crate::handlers::quote_handlers::get_quote,
+        crate::handlers::quote_handlers::update_quote,
+        crate::handlers::quote_handlers::create_quote_revision,
+        crate::handlers::quote_handlers::list_quote_revisions,
+        crate::handlers::quote_handlers::diff_quote_revisions,
+        crate::handlers::quote_handlers::update_quote_status,
+        crate::handlers::quote_handlers::get_quote_history,
+        crate::handlers::customer_handlers::create_customer,
+        crate::handlers::customer_handlers::list_customers,
+        crate::handlers::customer_handlers::get_customer,
+        crate::handlers::customer_handlers::update_customer,
+        crate::handlers::customer_handlers::delete_customer,
+        crate::handlers::share_link_handlers::create_share_link,
+        crate::handlers::share_link_handlers::list_share_links,
+        crate::handlers::share_link_handlers::revoke_share_link,
+        crate::handlers::runner_handlers::get_shared_flow,
+        crate::handlers::runner_handlers::start_runner_session,
+        crate::handlers::runner_handlers::submit_runner_step,
+        crate::handlers::runner_handlers::estimate_runner_session,
+        crate::handlers::api_key_handlers::create_api_key,
+        crate::handlers::api_key_handlers::list_api_keys,
+        crate::handlers::api_key_handlers::revoke_api_key,
+        crate::handlers::audit_handlers::list_audit_entries,
     ),
     components(schemas(
+        IdentityKindDto,
+        IdentityResponse,
         CreateFlowRequest,
         UpdateFlowMetadataRequest,
+        ReorderFlowRequest,
+        StepOrderRequest,
         FlowResponse,
         FlowListResponse,
         FlowSummaryResponse,
@@ -62,11 +111,47 @@ use crate::dto::{
         EstimatorListResponse,
         CreateVariableRequest,
         UpdateVariableRequest,
+        ReorderVariableRequest,
         VariableResponse,
         EvaluateRequest,
         EvaluateSubmissionRequest,
         EvaluateResponse,
+        CreateQuoteRequest,
+        UpdateQuoteRequest,
+        UpdateQuoteStatusRequest,
+        QuoteTransitionDto,
+        QuoteStatusDto,
+        QuoteResponse,
+        QuoteListResponse,
+        QuoteStatusChangeResponse,
+        QuoteHistoryResponse,
+        AnswerChangeResponse,
+        IterationAnswerChangeResponse,
+        LineItemChangeResponse,
+        QuoteDiffResponse,
+        AddressDto,
+        CustomerRequest,
+        CustomerResponse,
+        CustomerListResponse,
+        CreateShareLinkRequest,
+        ShareLinkResponse,
+        ShareLinkListResponse,
+        PublicFlowResponse,
+        PublicStepResponse,
+        PublicFieldResponse,
+        SubmitStepRequest,
+        RunnerSessionResponse,
+        EstimateResponse,
+        CreateApiKeyRequest,
+        ApiKeyResponse,
+        CreatedApiKeyResponse,
+        ApiKeyListResponse,
+        ActorResponse,
+        AuditChangeResponse,
+        AuditEntryResponse,
+        AuditPageResponse,
         MessageResponse,
+        ApiResponse<IdentityResponse>,
         ApiResponse<FlowResponse>,
         ApiResponse<FlowListResponse>,
         ApiResponse<StepResponse>,
@@ -75,14 +160,58 @@ use crate::dto::{
         ApiResponse<EstimatorListResponse>,
         ApiResponse<VariableResponse>,
         ApiResponse<EvaluateResponse>,
+        ApiResponse<QuoteResponse>,
+        ApiResponse<QuoteListResponse>,
+        ApiResponse<QuoteHistoryResponse>,
+        ApiResponse<QuoteDiffResponse>,
+        ApiResponse<CustomerResponse>,
+        ApiResponse<CustomerListResponse>,
+        ApiResponse<ShareLinkResponse>,
+        ApiResponse<ShareLinkListResponse>,
+        ApiResponse<PublicFlowResponse>,
+        ApiResponse<RunnerSessionResponse>,
+        ApiResponse<EstimateResponse>,
+        ApiResponse<ApiKeyResponse>,
+        ApiResponse<CreatedApiKeyResponse>,
+        ApiResponse<ApiKeyListResponse>,
+        ApiResponse<AuditPageResponse>,
         ApiResponse<MessageResponse>,
     )),
     tags(
+        (name = "auth", description = "Authenticated caller"),
         (name = "flows", description = "Flow management"),
         (name = "steps", description = "Step management"),
         (name = "fields", description = "Field management"),
         (name = "estimators", description = "Estimator management"),
         (name = "estimator_variables", description = "Estimator variable management"),
+        (name = "quotes", description = "Quote lifecycle management"),
+        (name = "customers", description = "Customer management"),
+        (name = "share_links", description = "Share link management"),
+        (name = "public", description = "Anonymous flow runner"),
+        (name = "api_keys", description = "API keys for machine-to-machine integrations"),
+        (name = "audit", description = "Audit log of changes"),
     )
 )]
 pub struct ApiDoc;
+
+/// Declare the bearer token and API key schemes accepted by editor routes.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+    }
+}
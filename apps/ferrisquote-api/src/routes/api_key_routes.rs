@@ -0,0 +1,17 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
+};
+
+/// API key routes under /api-keys
+pub fn api_key_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/", post(handlers::create_api_key))
+        .route("/", get(handlers::list_api_keys))
+        .route("/{api_key_id}", delete(handlers::revoke_api_key))
+}
@@ -0,0 +1,11 @@
+use axum::{Router, routing::get};
+
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
+};
+
+/// Audit log routes under /audit
+pub fn audit_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new().route("/", get(handlers::list_audit_entries))
+}
@@ -1,25 +1,31 @@
-use axum::{Router, routing::get};
+use std::sync::Arc;
+
+use axum::{Router, http::header::ETAG, middleware, routing::get};
 use tower_http::cors::{AllowHeaders, AllowMethods, CorsLayer};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use ferrisquote_domain::domain::{
-    estimator::ports::EstimatorService,
-    flows::ports::{FieldService, FlowService, StepService},
-};
+use ferrisquote_auth::domain::ports::AuthRepository;
 
 use crate::{
+    handlers,
+    middleware::{Authenticators, require_identity},
     openapi::ApiDoc,
-    routes::{estimator_routes, flow_routes},
-    state::AppState,
+    routes::{
+        api_key_routes, audit_routes, customer_routes, estimator_routes, flow_routes, quote_routes,
+        runner_routes,
+    },
+    state::{AppServices, AppState},
 };
 
-/// Build the complete API router with all routes
-pub fn build_routes<
-    FS: FlowService + StepService + FieldService + Clone + 'static,
-    ES: EstimatorService + Clone + 'static,
->(
-    state: AppState<FS, ES>,
+/// Build the complete API router with all routes.
+///
+/// Editor routes require a bearer token validated by `auth` or an API key validated by
+/// `api_keys`; the health check and the public runner routes stay open.
+pub fn build_routes<S: AppServices, AR: AuthRepository + 'static, KR: AuthRepository + 'static>(
+    state: AppState<S>,
+    auth: Arc<AR>,
+    api_keys: Arc<KR>,
 ) -> Router {
     let allowed_origins = std::env::var("ALLOWED_ORIGINS")
         .unwrap_or_else(|_| "http://localhost:5173".to_string());
@@ -32,14 +38,35 @@ pub fn build_routes<
                 .collect::<Vec<_>>(),
         )
         .allow_methods(AllowMethods::any())
-        .allow_headers(AllowHeaders::any());
+        .allow_headers(AllowHeaders::any())
+        .expose_headers([ETAG]);
 
-    let api = Router::new()
-        .route("/health", get(health_check))
+    let editor = Router::new()
+        .route("/api/v1/me", get(handlers::get_current_identity))
         .nest("/api/v1/flows", flow_routes::flow_routes())
         .nest("/api/v1/flows", estimator_routes::estimator_flow_routes())
         .nest("/api/v1/estimators", estimator_routes::estimator_routes())
         .nest("/api/v1/variables", estimator_routes::variable_routes())
+        .nest("/api/v1/flows", quote_routes::quote_flow_routes())
+        .nest("/api/v1/estimators", quote_routes::quote_estimator_routes())
+        .nest("/api/v1/quotes", quote_routes::quote_routes())
+        .nest("/api/v1/customers", customer_routes::customer_routes())
+        .nest("/api/v1/flows", runner_routes::share_link_flow_routes())
+        .nest("/api/v1/share-links", runner_routes::share_link_routes())
+        .nest("/api/v1/api-keys", api_key_routes::api_key_routes())
+        .nest("/api/v1/audit", audit_routes::audit_routes())
+        .route_layer(middleware::from_fn_with_state(
+            Authenticators {
+                bearer: auth,
+                api_keys,
+            },
+            require_identity::<AR, KR>,
+        ));
+
+    let api = Router::new()
+        .route("/health", get(health_check))
+        .merge(editor)
+        .nest("/api/v1/public", runner_routes::public_routes())
         .with_state(state);
 
     Router::new()
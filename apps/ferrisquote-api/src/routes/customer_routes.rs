@@ -0,0 +1,20 @@
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
+};
+
+/// Customer routes under /customers
+pub fn customer_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/", post(handlers::create_customer))
+        .route("/", get(handlers::list_customers))
+        .route("/{customer_id}", get(handlers::get_customer))
+        .route("/{customer_id}", put(handlers::update_customer))
+        .route("/{customer_id}", delete(handlers::delete_customer))
+        .route("/{customer_id}/quotes", get(handlers::list_customer_quotes))
+}
@@ -3,24 +3,20 @@ use axum::{
     routing::{delete, get, post, put},
 };
 
-use ferrisquote_domain::domain::{
-    estimator::ports::EstimatorService,
-    flows::ports::{FieldService, FlowService, StepService},
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
 };
 
-use crate::{handlers, state::AppState};
-
 /// Estimator routes nested under /flows (create + list by flow)
-pub fn estimator_flow_routes<FS: FlowService + StepService + FieldService + Clone + 'static, ES: EstimatorService + Clone + 'static>(
-) -> Router<AppState<FS, ES>> {
+pub fn estimator_flow_routes<S: AppServices>() -> Router<AppState<S>> {
     Router::new()
         .route("/{flow_id}/estimators", post(handlers::create_estimator))
         .route("/{flow_id}/estimators", get(handlers::list_estimators))
 }
 
 /// Standalone estimator routes under /estimators
-pub fn estimator_routes<FS: FlowService + StepService + FieldService + Clone + 'static, ES: EstimatorService + Clone + 'static>(
-) -> Router<AppState<FS, ES>> {
+pub fn estimator_routes<S: AppServices>() -> Router<AppState<S>> {
     Router::new()
         .route("/{estimator_id}", get(handlers::get_estimator))
         .route("/{estimator_id}", put(handlers::update_estimator))
@@ -34,9 +30,9 @@ pub fn estimator_routes<FS: FlowService + StepService + FieldService + Clone + '
 }
 
 /// Variable routes under /variables
-pub fn variable_routes<FS: FlowService + StepService + FieldService + Clone + 'static, ES: EstimatorService + Clone + 'static>(
-) -> Router<AppState<FS, ES>> {
+pub fn variable_routes<S: AppServices>() -> Router<AppState<S>> {
     Router::new()
         .route("/{variable_id}", put(handlers::update_variable))
         .route("/{variable_id}", delete(handlers::remove_variable))
+        .route("/{variable_id}/reorder", put(handlers::reorder_variable))
 }
@@ -3,16 +3,13 @@ use axum::{
     routing::{delete, get, post, put},
 };
 
-use ferrisquote_domain::domain::{
-    estimator::ports::EstimatorService,
-    flows::ports::{FieldService, FlowService, StepService},
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
 };
 
-use crate::{handlers, state::AppState};
-
 /// Flow-specific routes
-pub fn flow_routes<FS: FlowService + StepService + FieldService + Clone + 'static, ES: EstimatorService + Clone + 'static>(
-) -> Router<AppState<FS, ES>> {
+pub fn flow_routes<S: AppServices>() -> Router<AppState<S>> {
     Router::new()
         // Flow CRUD
         .route("/", post(handlers::create_flow))
@@ -20,6 +17,8 @@ pub fn flow_routes<FS: FlowService + StepService + FieldService + Clone + 'stati
         .route("/{flow_id}", get(handlers::get_flow))
         .route("/{flow_id}", put(handlers::update_flow_metadata))
         .route("/{flow_id}", delete(handlers::delete_flow))
+        .route("/{flow_id}/layout", put(handlers::reorder_flow))
+        .route("/{flow_id}/rebalance", post(handlers::rebalance_flow))
         // Step management
         .route("/{flow_id}/steps", post(handlers::add_step))
         .route("/steps/{step_id}", put(handlers::update_step_metadata))
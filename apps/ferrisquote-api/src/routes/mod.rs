@@ -1,3 +1,8 @@
+pub mod api_key_routes;
+pub mod audit_routes;
 pub mod build_routes;
+pub mod customer_routes;
 pub mod estimator_routes;
 pub mod flow_routes;
+pub mod quote_routes;
+pub mod runner_routes;
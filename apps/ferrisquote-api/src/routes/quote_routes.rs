@@ -0,0 +1,37 @@
+use axum::{
+    Router,
+    routing::{get, post, put},
+};
+
+use crate::{
+    handlers,
+    state::{AppServices, AppState},
+};
+
+/// Quote routes nested under /flows (list by flow)
+pub fn quote_flow_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new().route("/{flow_id}/quotes", get(handlers::list_quotes))
+}
+
+/// Quote routes nested under /estimators (create from a submission)
+pub fn quote_estimator_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new().route("/{estimator_id}/quotes", post(handlers::create_quote))
+}
+
+/// Standalone quote routes under /quotes
+pub fn quote_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/{quote_id}", get(handlers::get_quote))
+        .route("/{quote_id}", put(handlers::update_quote))
+        .route(
+            "/{quote_id}/revisions",
+            post(handlers::create_quote_revision),
+        )
+        .route("/{quote_id}/revisions", get(handlers::list_quote_revisions))
+        .route(
+            "/{quote_id}/diff/{other_id}",
+            get(handlers::diff_quote_revisions),
+        )
+        .route("/{quote_id}/status", put(handlers::update_quote_status))
+        .route("/{quote_id}/history", get(handlers::get_quote_history))
+}
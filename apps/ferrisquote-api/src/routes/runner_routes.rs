@@ -0,0 +1,41 @@
+use axum::{
+    Router, middleware,
+    routing::{delete, get, post, put},
+};
+
+use crate::{
+    handlers,
+    middleware::anonymous_identity,
+    state::{AppServices, AppState},
+};
+
+/// Share link routes nested under /flows
+pub fn share_link_flow_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/{flow_id}/share-links", post(handlers::create_share_link))
+        .route("/{flow_id}/share-links", get(handlers::list_share_links))
+}
+
+/// Share link routes under /share-links
+pub fn share_link_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new().route("/{share_link_id}", delete(handlers::revoke_share_link))
+}
+
+/// Anonymous runner routes under /public
+pub fn public_routes<S: AppServices>() -> Router<AppState<S>> {
+    Router::new()
+        .route("/flows/{token}", get(handlers::get_shared_flow))
+        .route(
+            "/flows/{token}/sessions",
+            post(handlers::start_runner_session),
+        )
+        .route(
+            "/flows/{token}/sessions/{session_id}/steps/{step_id}",
+            put(handlers::submit_runner_step),
+        )
+        .route(
+            "/flows/{token}/sessions/{session_id}/estimate",
+            post(handlers::estimate_runner_session),
+        )
+        .layer(middleware::from_fn(anonymous_identity))
+}
@@ -0,0 +1,33 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use ferrisquote_domain::domain::quote::ports::QuoteService;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// Spawn a background task that periodically expires quotes whose validity
+/// period has elapsed.
+///
+/// Each tick asks the quote service to move overdue `Sent` quotes to `Expired`.
+/// Failures are logged and retried on the next tick, so a transient database
+/// outage does not stop the scheduler.
+pub fn spawn_quote_expiry<QS: QuoteService + 'static>(
+    quote_service: Arc<QS>,
+    period: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            match quote_service.expire_overdue_quotes(Utc::now()).await {
+                Ok(expired) if !expired.is_empty() => {
+                    tracing::info!("Expired {} overdue quote(s)", expired.len());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Quote expiry run failed: {}", e),
+            }
+        }
+    })
+}
@@ -1,22 +1,77 @@
 use std::sync::Arc;
 
 use ferrisquote_domain::domain::{
+    api_key::ports::ApiKeyService,
+    audit::ports::AuditService,
+    authorization::entities::policy::RolePolicy,
+    customer::ports::CustomerService,
     estimator::ports::EstimatorService,
     flows::ports::{FieldService, FlowService, StepService},
+    quote::ports::QuoteService,
+    runner::ports::{RunnerService, ShareLinkService},
 };
 
+/// The services behind the API, named once so that routes and handlers take a
+/// single `S: AppServices` parameter.
+pub trait AppServices: Send + Sync + 'static {
+    type Flows: FlowService + StepService + FieldService + 'static;
+    type Estimators: EstimatorService + 'static;
+    type Quotes: QuoteService + 'static;
+    type Customers: CustomerService + 'static;
+    type Runner: RunnerService + ShareLinkService + 'static;
+    type ApiKeys: ApiKeyService + 'static;
+    type Audit: AuditService + 'static;
+}
+
 /// Application state shared across all handlers
-#[derive(Clone)]
-pub struct AppState<FS: FlowService + StepService + FieldService, ES: EstimatorService> {
-    pub flow_service: Arc<FS>,
-    pub estimator_service: Arc<ES>,
+pub struct AppState<S: AppServices> {
+    pub flow_service: Arc<S::Flows>,
+    pub estimator_service: Arc<S::Estimators>,
+    pub quote_service: Arc<S::Quotes>,
+    pub customer_service: Arc<S::Customers>,
+    pub runner_service: Arc<S::Runner>,
+    pub api_key_service: Arc<S::ApiKeys>,
+    pub audit_service: Arc<S::Audit>,
+    pub policy: Arc<RolePolicy>,
+}
+
+// Derived `Clone` would require the services themselves to be `Clone`.
+impl<S: AppServices> Clone for AppState<S> {
+    fn clone(&self) -> Self {
+        Self {
+            flow_service: self.flow_service.clone(),
+            estimator_service: self.estimator_service.clone(),
+            quote_service: self.quote_service.clone(),
+            customer_service: self.customer_service.clone(),
+            runner_service: self.runner_service.clone(),
+            api_key_service: self.api_key_service.clone(),
+            audit_service: self.audit_service.clone(),
+            policy: self.policy.clone(),
+        }
+    }
 }
 
-impl<FS: FlowService + StepService + FieldService, ES: EstimatorService> AppState<FS, ES> {
-    pub fn new(flow_service: Arc<FS>, estimator_service: Arc<ES>) -> Self {
+impl<S: AppServices> AppState<S> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        flow_service: Arc<S::Flows>,
+        estimator_service: Arc<S::Estimators>,
+        quote_service: Arc<S::Quotes>,
+        customer_service: Arc<S::Customers>,
+        runner_service: Arc<S::Runner>,
+        api_key_service: Arc<S::ApiKeys>,
+        audit_service: Arc<S::Audit>,
+        policy: Arc<RolePolicy>,
+    ) -> Self {
         Self {
             flow_service,
             estimator_service,
+            quote_service,
+            customer_service,
+            runner_service,
+            api_key_service,
+            audit_service,
+            policy,
         }
     }
 }
@@ -0,0 +1,152 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::{ACME_ADMIN, ACME_EDITOR, GLOBEX_ADMIN, app, send};
+use crate::middleware::API_KEY_HEADER;
+
+/// Issue a key of `acme` with `scopes` and return the created key and its secret.
+async fn issue(app: &Router, scopes: &[&str]) -> (Value, String) {
+    let (status, body) = send(
+        app,
+        Method::POST,
+        "/api/v1/api-keys",
+        Some(ACME_ADMIN),
+        Some(json!({ "name": "erp", "scopes": scopes })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "unexpected response: {body}");
+    let secret = body["data"]["secret"].as_str().unwrap().to_string();
+    (body["data"]["api_key"].clone(), secret)
+}
+
+/// Send a request authenticated by the API key `secret` and return its status.
+async fn send_with_key(app: &Router, method: Method, uri: &str, secret: &str) -> StatusCode {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(API_KEY_HEADER, secret)
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "name": "Kitchen" }).to_string()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_api_keys_are_managed_by_admins() {
+    let app = app();
+    let request = json!({ "name": "erp", "scopes": ["flow:read"] });
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/api-keys",
+        Some(ACME_EDITOR),
+        Some(request.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        "/api/v1/api-keys",
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/api-keys",
+        Some(ACME_ADMIN),
+        Some(json!({ "name": "erp", "scopes": ["flow:teleport"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "validation_error");
+
+    let (api_key, _) = issue(&app, &["flow:read"]).await;
+    assert_eq!(api_key["active"], true);
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/api-keys",
+        Some(ACME_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["api_keys"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_api_keys_grant_their_scopes_until_revoked() {
+    let app = app();
+    let (api_key, secret) = issue(&app, &["flow:read"]).await;
+
+    assert_eq!(
+        send_with_key(&app, Method::GET, "/api/v1/flows", &secret).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        send_with_key(&app, Method::POST, "/api/v1/flows", &secret).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        send_with_key(&app, Method::GET, "/api/v1/flows", "fq_unknown").await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let (status, body) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/api-keys/{}", api_key["id"].as_str().unwrap()),
+        Some(ACME_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["active"], false);
+    assert_eq!(
+        send_with_key(&app, Method::GET, "/api/v1/flows", &secret).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_see_or_revoke_api_keys() {
+    let app = app();
+    let (api_key, secret) = issue(&app, &["flow:read"]).await;
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/api-keys",
+        Some(GLOBEX_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["api_keys"].as_array().unwrap().is_empty());
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/api-keys/{}", api_key["id"].as_str().unwrap()),
+        Some(GLOBEX_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        send_with_key(&app, Method::GET, "/api/v1/flows", &secret).await,
+        StatusCode::OK
+    );
+}
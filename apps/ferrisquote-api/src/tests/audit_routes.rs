@@ -0,0 +1,64 @@
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+use super::{ACME_ADMIN, ACME_EDITOR, GLOBEX_ADMIN, app, create_flow, send};
+
+#[tokio::test]
+async fn test_audit_log_is_read_by_admins_of_the_organization() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(ACME_EDITOR),
+        Some(json!({ "name": "Bathroom" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, Method::GET, "/api/v1/audit", Some(ACME_EDITOR), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+
+    let (status, body) = send(&app, Method::GET, "/api/v1/audit", Some(ACME_ADMIN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 2);
+    let newest = &body["data"]["entries"][0];
+    assert_eq!(newest["entity"], "flow");
+    assert_eq!(newest["entity_id"], flow_id.as_str());
+    assert_eq!(newest["action"], "update");
+    assert_eq!(newest["actor"]["name"], "alice");
+
+    let (status, body) = send(&app, Method::GET, "/api/v1/audit", Some(GLOBEX_ADMIN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 0);
+}
+
+#[tokio::test]
+async fn test_audit_log_is_filtered_by_entity() {
+    let app = app();
+    create_flow(&app, "Kitchen").await;
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/audit?entity=quote",
+        Some(ACME_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 0);
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/audit?entity=invoice",
+        Some(ACME_ADMIN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "validation_error");
+}
@@ -0,0 +1,186 @@
+use axum::{
+    Router,
+    http::{Method, StatusCode},
+};
+use serde_json::json;
+
+use super::{
+    ACME_EDITOR, ACME_VIEWER, GLOBEX_EDITOR, app, create_estimator, create_flow, expect_data, send,
+};
+
+/// Create a customer of `acme` with a 10 % discount and return its id.
+async fn create_customer(app: &Router) -> String {
+    let customer = expect_data(
+        app,
+        Method::POST,
+        "/api/v1/customers",
+        Some(json!({
+            "contact_name": "Jane",
+            "email": "jane@example.com",
+            "discount_rate": 0.1,
+        })),
+        StatusCode::CREATED,
+    )
+    .await;
+    customer["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_customers_are_managed_by_editors_and_read_by_viewers() {
+    let app = app();
+    let customer = json!({ "contact_name": "Jane", "email": "jane@example.com" });
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/customers",
+        Some(ACME_VIEWER),
+        Some(customer.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/customers",
+        Some(ACME_EDITOR),
+        Some(json!({ "contact_name": "Jane", "email": "not an email" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "validation_error");
+
+    let customer_id = create_customer(&app).await;
+    let uri = format!("/api/v1/customers/{customer_id}");
+
+    let (status, body) = send(&app, Method::GET, &uri, Some(ACME_VIEWER), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["contact_name"], "Jane");
+
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_VIEWER),
+        Some(customer.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, Method::DELETE, &uri, Some(ACME_VIEWER), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let updated = expect_data(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(json!({ "contact_name": "Janet", "email": "janet@example.com" })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(updated["contact_name"], "Janet");
+
+    expect_data(&app, Method::DELETE, &uri, None, StatusCode::OK).await;
+    let (status, _) = send(&app, Method::GET, &uri, Some(ACME_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_see_or_change_a_customer() {
+    let app = app();
+    let customer_id = create_customer(&app).await;
+    let uri = format!("/api/v1/customers/{customer_id}");
+
+    let (status, _) = send(&app, Method::GET, &uri, Some(GLOBEX_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "contact_name": "Mallory", "email": "mallory@example.com" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, Method::DELETE, &uri, Some(GLOBEX_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/customers",
+        Some(GLOBEX_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["customers"].as_array().unwrap().is_empty());
+
+    let (status, body) = send(&app, Method::GET, &uri, Some(ACME_EDITOR), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["contact_name"], "Jane");
+}
+
+#[tokio::test]
+async fn test_quotes_apply_the_discount_of_a_customer_of_the_same_organization() {
+    let app = app();
+    let customer_id = create_customer(&app).await;
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator_id = create_estimator(
+        &app,
+        &flow_id,
+        "@surface * 100.0 * (1.0 - @customer_discount_rate)",
+    )
+    .await;
+
+    let quote = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/estimators/{estimator_id}/quotes"),
+        Some(json!({ "field_values": { "surface": 10.0 }, "customer_id": customer_id })),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(quote["total"], 900.0);
+    let quotes = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/customers/{customer_id}/quotes"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(quotes["quotes"].as_array().unwrap().len(), 1);
+
+    // A customer of `acme` cannot be billed on a quote of `globex`.
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "name": "Garage" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let globex_flow = body["data"]["id"].as_str().unwrap();
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{globex_flow}/estimators"),
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "name": "Pricing" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let globex_estimator = body["data"]["id"].as_str().unwrap();
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/estimators/{globex_estimator}/quotes"),
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "field_values": {}, "customer_id": customer_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
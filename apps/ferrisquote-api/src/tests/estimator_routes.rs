@@ -0,0 +1,303 @@
+use axum::http::{Method, StatusCode, header};
+use serde_json::json;
+
+use super::{
+    ACME_EDITOR, ACME_VIEWER, GLOBEX_EDITOR, app, create_flow, expect_data, send, send_if_match,
+};
+
+#[tokio::test]
+async fn test_estimators_need_a_flow_of_the_caller_organization() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "name": "Pricing" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(estimator["flow_id"], flow_id.as_str());
+
+    let listed = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(listed["estimators"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_variables_drive_evaluation() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let estimator_id = estimator["id"].as_str().unwrap();
+    for (name, expression) in [("ht", "@surface * 100.0"), ("ttc", "@ht * 1.2")] {
+        expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/estimators/{estimator_id}/variables"),
+            Some(json!({ "name": name, "expression": expression })),
+            StatusCode::CREATED,
+        )
+        .await;
+    }
+
+    let evaluated = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/estimators/{estimator_id}/evaluate"),
+        Some(json!({ "field_values": { "surface": 10.0 } })),
+        StatusCode::OK,
+    )
+    .await;
+
+    assert_eq!(evaluated["results"]["ht"], 1000.0);
+    assert!((evaluated["results"]["ttc"].as_f64().unwrap() - 1200.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_variables_can_be_updated_reordered_and_removed() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let estimator_id = estimator["id"].as_str().unwrap();
+    let mut variable_ids = Vec::new();
+    for name in ["labour", "materials"] {
+        let variable = expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/estimators/{estimator_id}/variables"),
+            Some(json!({ "name": name, "expression": "1.0", "description": "Flat fee" })),
+            StatusCode::CREATED,
+        )
+        .await;
+        variable_ids.push(variable["id"].as_str().unwrap().to_string());
+    }
+
+    let updated = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/variables/{}", variable_ids[0]),
+        Some(json!({ "expression": "2.0" })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(updated["name"], "labour");
+    assert_eq!(updated["expression"], "2.0");
+    assert_eq!(updated["description"], "Flat fee");
+
+    let reordered = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/variables/{}/reorder", variable_ids[1]),
+        Some(json!({ "before_id": variable_ids[0] })),
+        StatusCode::OK,
+    )
+    .await;
+    let names: Vec<&str> = reordered["variables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["materials", "labour"]);
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/variables/{}", variable_ids[0]),
+        Some(GLOBEX_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    expect_data(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/variables/{}", variable_ids[0]),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    let estimator = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/estimators/{estimator_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(estimator["variables"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_estimator_lifecycle_and_permissions() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let estimator_id = estimator["id"].as_str().unwrap();
+    let uri = format!("/api/v1/estimators/{estimator_id}");
+
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_VIEWER),
+        Some(json!({ "name": "Labour" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, Method::GET, &uri, Some(GLOBEX_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let renamed = expect_data(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(json!({ "name": "Labour" })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(renamed["name"], "Labour");
+
+    expect_data(&app, Method::DELETE, &uri, None, StatusCode::OK).await;
+    let (status, _) = send(&app, Method::GET, &uri, Some(ACME_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_deleting_a_flow_deletes_its_estimators() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+
+    expect_data(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/{flow_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/estimators/{}", estimator["id"].as_str().unwrap()),
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_stale_variable_changes_are_rejected() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let estimator = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let (status, headers, body) = send_if_match(
+        &app,
+        Method::POST,
+        &format!(
+            "/api/v1/estimators/{}/variables",
+            estimator["id"].as_str().unwrap()
+        ),
+        Some(ACME_EDITOR),
+        None,
+        Some(json!({ "name": "ht", "expression": "@surface * 100.0" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let etag = headers[header::ETAG].to_str().unwrap().to_string();
+    let uri = format!("/api/v1/variables/{}", body["data"]["id"].as_str().unwrap());
+
+    let (status, headers, body) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some(&etag),
+        Some(json!({ "expression": "@surface * 120.0" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(headers[header::ETAG], etag.as_str());
+    assert_eq!(body["data"]["version"], 2);
+
+    let (status, _, _) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some(&etag),
+        Some(json!({ "expression": "@surface * 90.0" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _, _) =
+        send_if_match(&app, Method::DELETE, &uri, Some(ACME_EDITOR), None, None).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    let (status, _, _) = send_if_match(
+        &app,
+        Method::DELETE,
+        &uri,
+        Some(ACME_EDITOR),
+        Some(&etag),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
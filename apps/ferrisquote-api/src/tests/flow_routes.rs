@@ -0,0 +1,647 @@
+use axum::http::{Method, StatusCode, header};
+use serde_json::json;
+
+use super::{
+    ACME_EDITOR, ACME_VIEWER, GLOBEX_EDITOR, UNBOUND_EDITOR, app, create_flow, expect_data, send,
+    send_if_match,
+};
+
+#[tokio::test]
+async fn test_editor_routes_require_authentication() {
+    let app = app();
+
+    let (status, _) = send(&app, Method::GET, "/api/v1/flows", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(&app, Method::GET, "/api/v1/flows", Some("forged"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(&app, Method::GET, "/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_viewers_cannot_change_flows() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(ACME_VIEWER),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(ACME_VIEWER),
+        Some(json!({ "name": "Bathroom" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(ACME_VIEWER),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_tokens_without_organization_are_forbidden() {
+    let app = app();
+
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        "/api/v1/flows",
+        Some(UNBOUND_EDITOR),
+        None,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_flow_lifecycle() {
+    let app = app();
+    let flow = expect_data(
+        &app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(json!({ "name": "Kitchen" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let flow_id = flow["id"].as_str().unwrap();
+    assert_eq!(flow["name"], "Kitchen");
+
+    expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(json!({ "name": "Kitchen", "description": "Full renovation" })),
+        StatusCode::OK,
+    )
+    .await;
+    let updated = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(json!({ "name": "Bathroom" })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(updated["name"], "Bathroom");
+    assert_eq!(updated["description"], "Full renovation");
+
+    let listed = expect_data(&app, Method::GET, "/api/v1/flows", None, StatusCode::OK).await;
+    assert_eq!(listed["flows"].as_array().unwrap().len(), 1);
+    assert_eq!(listed["flows"][0]["id"], flow_id);
+
+    expect_data(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/{flow_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows/{flow_id}"),
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"]["type"], "not_found");
+}
+
+#[tokio::test]
+async fn test_malformed_ids_are_rejected() {
+    let app = app();
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        "/api/v1/flows/not-a-uuid",
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "bad_request");
+}
+
+#[tokio::test]
+async fn test_other_organization_gets_not_found() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let step = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/steps"),
+        Some(json!({ "title": "Size" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let step_id = step["id"].as_str().unwrap();
+
+    let requests = [
+        (Method::GET, format!("/api/v1/flows/{flow_id}"), None),
+        (
+            Method::PUT,
+            format!("/api/v1/flows/{flow_id}"),
+            Some(json!({ "name": "Mine" })),
+        ),
+        (Method::DELETE, format!("/api/v1/flows/{flow_id}"), None),
+        (
+            Method::POST,
+            format!("/api/v1/flows/{flow_id}/steps"),
+            Some(json!({ "title": "Extra" })),
+        ),
+        (
+            Method::PUT,
+            format!("/api/v1/flows/steps/{step_id}"),
+            Some(json!({ "title": "Mine" })),
+        ),
+        (
+            Method::DELETE,
+            format!("/api/v1/flows/steps/{step_id}"),
+            None,
+        ),
+    ];
+    for (method, uri, body) in requests {
+        let (status, _) = send(&app, method.clone(), &uri, Some(GLOBEX_EDITOR), body).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{method} {uri}");
+    }
+
+    let listed = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows/{flow_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(listed["name"], "Kitchen");
+    assert_eq!(listed["steps"][0]["title"], "Size");
+}
+
+#[tokio::test]
+async fn test_steps_can_be_updated_reordered_and_removed() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let mut step_ids = Vec::new();
+    for title in ["Size", "Finish"] {
+        let step = expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/flows/{flow_id}/steps"),
+            Some(json!({ "title": title })),
+            StatusCode::CREATED,
+        )
+        .await;
+        step_ids.push(step["id"].as_str().unwrap().to_string());
+    }
+
+    let step = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/steps/{}", step_ids[0]),
+        Some(json!({ "is_repeatable": true, "repeat_label": "Room", "max_repeats": 4 })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(step["title"], "Size");
+    assert_eq!(step["is_repeatable"], true);
+    assert_eq!(step["repeat_label"], "Room");
+    assert_eq!(step["max_repeats"], 4);
+
+    let flow = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/steps/{}/reorder", step_ids[1]),
+        Some(json!({ "before_id": step_ids[0] })),
+        StatusCode::OK,
+    )
+    .await;
+    let titles: Vec<&str> = flow["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Finish", "Size"]);
+
+    expect_data(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/steps/{}", step_ids[1]),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/steps/{}", step_ids[1]),
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_fields_can_be_updated_moved_and_removed() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let mut step_ids = Vec::new();
+    for title in ["Size", "Finish"] {
+        let step = expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/flows/{flow_id}/steps"),
+            Some(json!({ "title": title })),
+            StatusCode::CREATED,
+        )
+        .await;
+        step_ids.push(step["id"].as_str().unwrap().to_string());
+    }
+    let field = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/steps/{}/fields", step_ids[0]),
+        Some(json!({
+            "label": "Surface",
+            "key": "surface",
+            "config": { "type": "number", "min": 1.0, "max": null },
+        })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let field_id = field["id"].as_str().unwrap();
+    assert_eq!(field["config"]["type"], "number");
+
+    let updated = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/fields/{field_id}"),
+        Some(json!({
+            "label": "Floor area",
+            "config": { "type": "number", "min": 1.0, "max": 500.0 },
+        })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(updated["label"], "Floor area");
+    assert_eq!(updated["key"], "surface");
+    assert_eq!(updated["config"]["max"], 500.0);
+
+    let flow = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/fields/{field_id}/move"),
+        Some(json!({ "target_step_id": step_ids[1] })),
+        StatusCode::OK,
+    )
+    .await;
+    assert!(flow["steps"][0]["fields"].as_array().unwrap().is_empty());
+    assert_eq!(flow["steps"][1]["fields"][0]["key"], "surface");
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/fields/{field_id}"),
+        Some(GLOBEX_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    expect_data(
+        &app,
+        Method::DELETE,
+        &format!("/api/v1/flows/fields/{field_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    let flow = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows/{flow_id}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert!(flow["steps"][1]["fields"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_flow_ranks_can_be_rebalanced() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    for title in ["Size", "Finish"] {
+        expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/flows/{flow_id}/steps"),
+            Some(json!({ "title": title })),
+            StatusCode::CREATED,
+        )
+        .await;
+    }
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/rebalance"),
+        Some(ACME_VIEWER),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let flow = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/rebalance"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    let titles: Vec<&str> = flow["steps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Size", "Finish"]);
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/rebalance"),
+        Some(GLOBEX_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_flow_layout_is_applied_in_one_request() {
+    let app = app();
+    let flow_id = create_flow(&app, "Kitchen").await;
+    let mut step_ids = Vec::new();
+    for title in ["Size", "Finish"] {
+        let step = expect_data(
+            &app,
+            Method::POST,
+            &format!("/api/v1/flows/{flow_id}/steps"),
+            Some(json!({ "title": title })),
+            StatusCode::CREATED,
+        )
+        .await;
+        step_ids.push(step["id"].as_str().unwrap().to_string());
+    }
+    let field = expect_data(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/steps/{}/fields", step_ids[0]),
+        Some(json!({
+            "label": "Surface",
+            "key": "surface",
+            "config": { "type": "number", "min": null, "max": null },
+        })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let field_id = field["id"].as_str().unwrap();
+
+    let flow = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/{flow_id}/layout"),
+        Some(json!({ "steps": [
+            { "step_id": step_ids[1], "field_ids": [field_id] },
+            { "step_id": step_ids[0], "field_ids": [] },
+        ] })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(flow["steps"][0]["title"], "Finish");
+    assert_eq!(flow["steps"][0]["fields"][0]["id"], field_id);
+    assert!(flow["steps"][1]["fields"].as_array().unwrap().is_empty());
+
+    let (status, body) = send(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/flows/{flow_id}/layout"),
+        Some(ACME_EDITOR),
+        Some(json!({ "steps": [{ "step_id": step_ids[0], "field_ids": [] }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "validation_error");
+}
+
+#[tokio::test]
+async fn test_flow_changes_require_the_current_etag() {
+    let app = app();
+    let (status, headers, body) = send_if_match(
+        &app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(ACME_EDITOR),
+        None,
+        Some(json!({ "name": "Kitchen" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(headers[header::ETAG], "\"1\"");
+    assert_eq!(body["data"]["version"], 1);
+    let uri = format!("/api/v1/flows/{}", body["data"]["id"].as_str().unwrap());
+    let rename = Some(json!({ "name": "Bathroom" }));
+
+    let (status, _, body) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        None,
+        rename.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    assert_eq!(body["error"]["type"], "precondition_required");
+
+    let (status, _, _) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("latest"),
+        rename.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, headers, body) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("\"1\""),
+        rename.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::ETAG], "\"2\"");
+    assert_eq!(body["data"]["version"], 2);
+
+    // A second editor still holding version 1 no longer overwrites the rename.
+    let (status, _, body) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("\"1\""),
+        Some(json!({ "name": "Garage" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["type"], "conflict");
+    let (status, _, _) = send_if_match(
+        &app,
+        Method::DELETE,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("\"1\""),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, headers, body) =
+        send_if_match(&app, Method::GET, &uri, Some(ACME_EDITOR), None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::ETAG], "\"2\"");
+    assert_eq!(body["data"]["name"], "Bathroom");
+
+    let (status, _, _) = send_if_match(
+        &app,
+        Method::DELETE,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("\"2\""),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_weak_etags_stand_for_their_version() {
+    let app = app();
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(ACME_EDITOR),
+        Some(json!({ "name": "Kitchen" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uri = format!("/api/v1/flows/{}", body["data"]["id"].as_str().unwrap());
+    let rename = Some(json!({ "name": "Bathroom" }));
+
+    let (status, headers, _) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("W/\"1\""),
+        rename.clone(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::ETAG], "\"2\"");
+
+    let (status, _, body) = send_if_match(
+        &app,
+        Method::PUT,
+        &uri,
+        Some(ACME_EDITOR),
+        Some("W/\"1\""),
+        rename,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["type"], "conflict");
+}
+
+#[tokio::test]
+async fn test_flows_are_listed_one_page_at_a_time() {
+    let app = app();
+    for name in ["Kitchen", "Bathroom", "Kitchenette"] {
+        create_flow(&app, name).await;
+    }
+
+    let first = expect_data(
+        &app,
+        Method::GET,
+        "/api/v1/flows?search=kitchen&sort=name&limit=1",
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(first["flows"][0]["name"], "Kitchen");
+    let cursor = first["next_cursor"].as_str().unwrap();
+
+    let second = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/flows?search=kitchen&sort=name&limit=1&cursor={cursor}"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(second["flows"][0]["name"], "Kitchenette");
+    assert!(second["next_cursor"].is_null());
+
+    for query in [
+        "sort=rank",
+        "order=up",
+        "limit=0",
+        "limit=101",
+        "cursor=not-a-cursor",
+    ] {
+        let (status, _) = send(
+            &app,
+            Method::GET,
+            &format!("/api/v1/flows?{query}"),
+            Some(ACME_EDITOR),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+    }
+}
@@ -0,0 +1,307 @@
+//! HTTP-level tests: the full router over the in-memory adapters.
+
+mod api_key_routes;
+mod audit_routes;
+mod customer_routes;
+mod estimator_routes;
+mod flow_routes;
+mod quote_routes;
+mod share_link_routes;
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Router,
+    body::Body,
+    http::{HeaderMap, Method, Request, StatusCode, header},
+};
+use ferrisquote_auth::domain::{
+    entities::{claims::Claims, identity::Identity, user::User},
+    error::AuthError,
+    ports::AuthRepository,
+};
+use ferrisquote_domain::domain::{
+    api_key::services::ApiKeyServiceImpl, audit::services::AuditServiceImpl,
+    authorization::entities::policy::RolePolicy, customer::services::CustomerServiceImpl,
+    estimator::services::EstimatorServiceImpl, flows::services::FlowServiceImpl,
+    quote::services::QuoteServiceImpl, rank::services::LexoRankProvider,
+    runner::services::RunnerServiceImpl,
+};
+use ferrisquote_memory::{
+    InMemoryApiKeyRepository, InMemoryAuditRepository, InMemoryCustomerRepository,
+    InMemoryEstimatorRepository, InMemoryFlowRepository, InMemoryQuoteRepository,
+    InMemoryRunnerRepository, InMemoryStore, InMemoryUnitOfWork,
+};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::{
+    api_key_auth::ApiKeyAuthRepository,
+    routes::build_routes::build_routes,
+    state::{AppServices, AppState},
+};
+
+/// Token of an editor of `acme`.
+pub const ACME_EDITOR: &str = "acme-editor";
+/// Token of a viewer of `acme`.
+pub const ACME_VIEWER: &str = "acme-viewer";
+/// Token of an approver of `acme`.
+pub const ACME_APPROVER: &str = "acme-approver";
+/// Token of an admin of `acme`.
+pub const ACME_ADMIN: &str = "acme-admin";
+/// Token of an editor of `globex`.
+pub const GLOBEX_EDITOR: &str = "globex-editor";
+/// Token of an admin of `globex`.
+pub const GLOBEX_ADMIN: &str = "globex-admin";
+/// Token of an editor bound to no organization.
+pub const UNBOUND_EDITOR: &str = "unbound-editor";
+
+/// Resolves the fixed test tokens above to users, without any signature check.
+struct FakeAuthRepository {
+    identities: HashMap<&'static str, Identity>,
+}
+
+fn user(id: &str, role: &str, organization: Option<&str>) -> Identity {
+    Identity::User(User {
+        id: id.to_string(),
+        username: id.to_string(),
+        email: None,
+        name: None,
+        roles: vec![role.to_string()],
+        scopes: Vec::new(),
+        organization: organization.map(str::to_string),
+    })
+}
+
+impl Default for FakeAuthRepository {
+    fn default() -> Self {
+        Self {
+            identities: HashMap::from([
+                (ACME_EDITOR, user("alice", "editor", Some("acme"))),
+                (ACME_VIEWER, user("bob", "viewer", Some("acme"))),
+                (ACME_APPROVER, user("erin", "approver", Some("acme"))),
+                (ACME_ADMIN, user("frank", "admin", Some("acme"))),
+                (GLOBEX_EDITOR, user("carol", "editor", Some("globex"))),
+                (GLOBEX_ADMIN, user("grace", "admin", Some("globex"))),
+                (UNBOUND_EDITOR, user("dave", "editor", None)),
+            ]),
+        }
+    }
+}
+
+impl AuthRepository for FakeAuthRepository {
+    async fn validate_token(&self, _token: &str) -> Result<Claims, AuthError> {
+        Err(AuthError::InvalidToken {
+            message: "test tokens carry no claims".to_string(),
+        })
+    }
+
+    async fn identity(&self, token: &str) -> Result<Identity, AuthError> {
+        self.identities
+            .get(token)
+            .cloned()
+            .ok_or_else(|| AuthError::InvalidToken {
+                message: "unknown test token".to_string(),
+            })
+    }
+}
+
+/// The services under test, backed by the in-memory store.
+struct InMemoryServices;
+
+impl AppServices for InMemoryServices {
+    type Flows = FlowServiceImpl<
+        InMemoryFlowRepository,
+        InMemoryFlowRepository,
+        InMemoryFlowRepository,
+        LexoRankProvider,
+        InMemoryAuditRepository,
+        InMemoryUnitOfWork,
+    >;
+    type Estimators = EstimatorServiceImpl<
+        InMemoryEstimatorRepository,
+        LexoRankProvider,
+        InMemoryCustomerRepository,
+        InMemoryAuditRepository,
+        InMemoryUnitOfWork,
+    >;
+    type Quotes = QuoteServiceImpl<
+        InMemoryQuoteRepository,
+        InMemoryEstimatorRepository,
+        InMemoryCustomerRepository,
+        InMemoryAuditRepository,
+        InMemoryUnitOfWork,
+    >;
+    type Customers = CustomerServiceImpl<InMemoryCustomerRepository>;
+    type Runner = RunnerServiceImpl<
+        InMemoryRunnerRepository,
+        InMemoryRunnerRepository,
+        InMemoryFlowRepository,
+        InMemoryEstimatorRepository,
+        InMemoryAuditRepository,
+        InMemoryUnitOfWork,
+    >;
+    type ApiKeys = ApiKeyServiceImpl<InMemoryApiKeyRepository>;
+    type Audit = AuditServiceImpl<InMemoryAuditRepository>;
+}
+
+/// The API router wired to a fresh in-memory store.
+pub fn app() -> Router {
+    let store = Arc::new(InMemoryStore::new());
+    let flow_repo = InMemoryFlowRepository::with_store(store.clone());
+    let estimator_repo = InMemoryEstimatorRepository::with_store(store.clone());
+    let customer_repo = InMemoryCustomerRepository::with_store(store.clone());
+    let runner_repo = InMemoryRunnerRepository::with_store(store.clone());
+    let audit_repo = InMemoryAuditRepository::with_store(store.clone());
+
+    let flow_service = FlowServiceImpl::new(
+        flow_repo.clone(),
+        flow_repo.clone(),
+        flow_repo.clone(),
+        LexoRankProvider,
+        audit_repo.clone(),
+        InMemoryUnitOfWork::with_store(store.clone()),
+    );
+    let estimator_service = EstimatorServiceImpl::new(
+        estimator_repo.clone(),
+        LexoRankProvider,
+        customer_repo.clone(),
+        audit_repo.clone(),
+        InMemoryUnitOfWork::with_store(store.clone()),
+    );
+    let quote_service = QuoteServiceImpl::new(
+        InMemoryQuoteRepository::with_store(store.clone()),
+        estimator_repo.clone(),
+        customer_repo.clone(),
+        audit_repo.clone(),
+        InMemoryUnitOfWork::with_store(store.clone()),
+    );
+    let runner_service = RunnerServiceImpl::new(
+        runner_repo.clone(),
+        runner_repo,
+        flow_repo,
+        estimator_repo,
+        audit_repo.clone(),
+        InMemoryUnitOfWork::with_store(store.clone()),
+    );
+    let api_key_service = Arc::new(ApiKeyServiceImpl::new(
+        InMemoryApiKeyRepository::with_store(store),
+    ));
+
+    let state = AppState::<InMemoryServices>::new(
+        Arc::new(flow_service),
+        Arc::new(estimator_service),
+        Arc::new(quote_service),
+        Arc::new(CustomerServiceImpl::new(customer_repo)),
+        Arc::new(runner_service),
+        api_key_service.clone(),
+        Arc::new(AuditServiceImpl::new(audit_repo)),
+        Arc::new(RolePolicy::standard()),
+    );
+
+    build_routes(
+        state,
+        Arc::new(FakeAuthRepository::default()),
+        Arc::new(ApiKeyAuthRepository::new(api_key_service)),
+    )
+}
+
+/// Send a request as the caller holding `token` and return the status and JSON body.
+///
+/// Changes are sent with `If-Match: *`, so they apply whatever the current
+/// version; use [`send_if_match`] to exercise the version check.
+pub async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let (status, _, body) = send_if_match(app, method, uri, token, Some("*"), body).await;
+    (status, body)
+}
+
+/// Like [`send`] with an explicit `If-Match` header, also returning the response headers.
+pub async fn send_if_match(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    if_match: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+    }
+    if let Some(if_match) = if_match {
+        request = request.header(header::IF_MATCH, if_match);
+    }
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    // Plain-text bodies (the health check) come back as a JSON string.
+    let body = serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+
+    (status, headers, body)
+}
+
+/// Send a request and return the `data` of its successful response.
+pub async fn expect_data(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+    expected: StatusCode,
+) -> Value {
+    let (status, body) = send(app, method, uri, Some(ACME_EDITOR), body).await;
+    assert_eq!(status, expected, "unexpected response: {body}");
+    body["data"].clone()
+}
+
+/// Create a flow of `acme` and return its id.
+pub async fn create_flow(app: &Router, name: &str) -> String {
+    let flow = expect_data(
+        app,
+        Method::POST,
+        "/api/v1/flows",
+        Some(serde_json::json!({ "name": name })),
+        StatusCode::CREATED,
+    )
+    .await;
+    flow["id"].as_str().unwrap().to_string()
+}
+
+/// Create an estimator of `acme` on the flow whose single variable `total`
+/// computes `expression`, and return its id.
+pub async fn create_estimator(app: &Router, flow_id: &str, expression: &str) -> String {
+    let estimator = expect_data(
+        app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/estimators"),
+        Some(serde_json::json!({ "name": "Pricing" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let estimator_id = estimator["id"].as_str().unwrap().to_string();
+    expect_data(
+        app,
+        Method::POST,
+        &format!("/api/v1/estimators/{estimator_id}/variables"),
+        Some(serde_json::json!({ "name": "total", "expression": expression })),
+        StatusCode::CREATED,
+    )
+    .await;
+    estimator_id
+}
@@ -0,0 +1,179 @@
+use axum::{
+    Router,
+    http::{Method, StatusCode},
+};
+use serde_json::{Value, json};
+
+use super::{
+    ACME_APPROVER, ACME_EDITOR, ACME_VIEWER, GLOBEX_EDITOR, app, create_estimator, create_flow,
+    expect_data, send,
+};
+
+/// A draft quote of `acme` for 10 square metres at 100 each.
+async fn draft_quote(app: &Router) -> Value {
+    let flow_id = create_flow(app, "Kitchen").await;
+    let estimator_id = create_estimator(app, &flow_id, "@surface * 100.0").await;
+    expect_data(
+        app,
+        Method::POST,
+        &format!("/api/v1/estimators/{estimator_id}/quotes"),
+        Some(json!({ "field_values": { "surface": 10.0 } })),
+        StatusCode::CREATED,
+    )
+    .await
+}
+
+async fn move_quote(
+    app: &Router,
+    quote_id: &str,
+    token: &str,
+    status: &str,
+) -> (StatusCode, Value) {
+    send(
+        app,
+        Method::PUT,
+        &format!("/api/v1/quotes/{quote_id}/status"),
+        Some(token),
+        Some(json!({ "status": status })),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_quotes_are_priced_and_sent_by_editors() {
+    let app = app();
+    let quote = draft_quote(&app).await;
+    assert_eq!(quote["status"], "draft");
+    assert_eq!(quote["total"], 1000.0);
+    let quote_id = quote["id"].as_str().unwrap();
+
+    let (status, body) = move_quote(&app, quote_id, ACME_VIEWER, "sent").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+
+    let updated = expect_data(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/quotes/{quote_id}"),
+        Some(json!({ "field_values": { "surface": 12.0 } })),
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(updated["total"], 1200.0);
+
+    let (status, body) = move_quote(&app, quote_id, ACME_EDITOR, "sent").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "sent");
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/quotes/{quote_id}"),
+        Some(ACME_VIEWER),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 1200.0);
+}
+
+#[tokio::test]
+async fn test_only_approvers_accept_quotes() {
+    let app = app();
+    let quote = draft_quote(&app).await;
+    let quote_id = quote["id"].as_str().unwrap();
+    move_quote(&app, quote_id, ACME_EDITOR, "sent").await;
+
+    let (status, _) = move_quote(&app, quote_id, ACME_EDITOR, "accepted").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = move_quote(&app, quote_id, ACME_APPROVER, "accepted").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "accepted");
+
+    let history = expect_data(
+        &app,
+        Method::GET,
+        &format!("/api/v1/quotes/{quote_id}/history"),
+        None,
+        StatusCode::OK,
+    )
+    .await;
+    assert_eq!(history["history"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_quotes_past_their_draft_conflict() {
+    let app = app();
+    let quote = draft_quote(&app).await;
+    let quote_id = quote["id"].as_str().unwrap();
+    move_quote(&app, quote_id, ACME_EDITOR, "sent").await;
+
+    let (status, body) = send(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/quotes/{quote_id}"),
+        Some(ACME_EDITOR),
+        Some(json!({ "field_values": { "surface": 12.0 } })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["type"], "conflict");
+
+    let (status, _) = move_quote(&app, quote_id, ACME_EDITOR, "sent").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let uri = format!("/api/v1/quotes/{quote_id}/revisions");
+    let revision = expect_data(&app, Method::POST, &uri, None, StatusCode::CREATED).await;
+    assert_eq!(revision["revision"], 2);
+    assert_eq!(revision["status"], "draft");
+    let (status, _) = send(&app, Method::POST, &uri, Some(ACME_EDITOR), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_see_or_change_quotes() {
+    let app = app();
+    let quote = draft_quote(&app).await;
+    let quote_id = quote["id"].as_str().unwrap();
+    let estimator_id = quote["estimator_id"].as_str().unwrap();
+
+    for (method, uri, body) in [
+        (Method::GET, format!("/api/v1/quotes/{quote_id}"), None),
+        (
+            Method::PUT,
+            format!("/api/v1/quotes/{quote_id}"),
+            Some(json!({ "field_values": { "surface": 1.0 } })),
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/quotes/{quote_id}/revisions"),
+            None,
+        ),
+        (
+            Method::PUT,
+            format!("/api/v1/quotes/{quote_id}/status"),
+            Some(json!({ "status": "sent" })),
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/estimators/{estimator_id}/quotes"),
+            Some(json!({ "field_values": { "surface": 1.0 } })),
+        ),
+    ] {
+        let (status, _) = send(&app, method, &uri, Some(GLOBEX_EDITOR), body).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+    }
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/quotes/{quote_id}"),
+        Some(ACME_EDITOR),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["status"], "draft");
+    assert_eq!(body["data"]["total"], 1000.0);
+}
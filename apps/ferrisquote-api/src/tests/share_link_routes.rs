@@ -0,0 +1,168 @@
+use axum::{
+    Router,
+    http::{Method, StatusCode},
+};
+use serde_json::json;
+
+use super::{
+    ACME_EDITOR, ACME_VIEWER, GLOBEX_EDITOR, app, create_estimator, create_flow, expect_data, send,
+};
+
+/// A flow of `acme` asking for a surface on its one step, priced 100 per square
+/// metre; returns the flow, step and estimator ids.
+async fn priced_flow(app: &Router) -> (String, String, String) {
+    let flow_id = create_flow(app, "Kitchen").await;
+    let step = expect_data(
+        app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/steps"),
+        Some(json!({ "title": "Size" })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let step_id = step["id"].as_str().unwrap().to_string();
+    expect_data(
+        app,
+        Method::POST,
+        &format!("/api/v1/flows/steps/{step_id}/fields"),
+        Some(json!({
+            "label": "Surface",
+            "key": "surface",
+            "config": { "type": "number", "min": null, "max": null },
+        })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let estimator_id = create_estimator(app, &flow_id, "@surface * 100.0").await;
+    (flow_id, step_id, estimator_id)
+}
+
+#[tokio::test]
+async fn test_shared_flow_is_run_anonymously() {
+    let app = app();
+    let (flow_id, step_id, estimator_id) = priced_flow(&app).await;
+    let uri = format!("/api/v1/flows/{flow_id}/share-links");
+    let request = json!({ "estimator_id": estimator_id });
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        &uri,
+        Some(ACME_VIEWER),
+        Some(request.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["type"], "forbidden");
+
+    let link = expect_data(&app, Method::POST, &uri, Some(request), StatusCode::CREATED).await;
+    assert_eq!(link["active"], true);
+    let token = link["token"].as_str().unwrap();
+
+    let (status, body) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/public/flows/{token}"),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Kitchen");
+    assert_eq!(body["data"]["steps"][0]["fields"][0]["key"], "surface");
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/public/flows/{token}/sessions"),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let session_id = body["data"]["session_id"].as_str().unwrap();
+    let estimate = format!("/api/v1/public/flows/{token}/sessions/{session_id}/estimate");
+
+    let (status, _) = send(&app, Method::POST, &estimate, None, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        Method::PUT,
+        &format!("/api/v1/public/flows/{token}/sessions/{session_id}/steps/{step_id}"),
+        None,
+        Some(json!({ "iterations": [{ "surface": 10.0 }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["completed_steps"][0], step_id.as_str());
+
+    let (status, body) = send(&app, Method::POST, &estimate, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["total"], 1000.0);
+}
+
+#[tokio::test]
+async fn test_links_need_an_estimator_of_their_flow() {
+    let app = app();
+    let (flow_id, _, _) = priced_flow(&app).await;
+    let other_flow = create_flow(&app, "Bathroom").await;
+    let other_estimator = create_estimator(&app, &other_flow, "1.0").await;
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        &format!("/api/v1/flows/{flow_id}/share-links"),
+        Some(ACME_EDITOR),
+        Some(json!({ "estimator_id": other_estimator })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["type"], "validation_error");
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_share_or_revoke_links() {
+    let app = app();
+    let (flow_id, _, estimator_id) = priced_flow(&app).await;
+    let uri = format!("/api/v1/flows/{flow_id}/share-links");
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        &uri,
+        Some(GLOBEX_EDITOR),
+        Some(json!({ "estimator_id": estimator_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let link = expect_data(
+        &app,
+        Method::POST,
+        &uri,
+        Some(json!({ "estimator_id": estimator_id })),
+        StatusCode::CREATED,
+    )
+    .await;
+    let link_uri = format!("/api/v1/share-links/{}", link["id"].as_str().unwrap());
+    let token = link["token"].as_str().unwrap();
+
+    let (status, _) = send(&app, Method::DELETE, &link_uri, Some(GLOBEX_EDITOR), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, body) = send(&app, Method::GET, &uri, Some(GLOBEX_EDITOR), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"]["share_links"].as_array().unwrap().is_empty());
+
+    let revoked = expect_data(&app, Method::DELETE, &link_uri, None, StatusCode::OK).await;
+    assert_eq!(revoked["active"], false);
+    let (status, _) = send(
+        &app,
+        Method::GET,
+        &format!("/api/v1/public/flows/{token}"),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
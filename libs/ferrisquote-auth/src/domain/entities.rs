@@ -1,5 +1,6 @@
 pub mod claims;
 pub mod client;
 pub mod identity;
+pub mod role_mapping;
 pub mod token;
 pub mod user;
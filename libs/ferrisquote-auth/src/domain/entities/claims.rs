@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::domain::entities::token::Token;
 
+/// Claim holding the caller's organization by default (Keycloak's `organization` scope).
+pub const DEFAULT_ORGANIZATION_CLAIM: &str = "organization";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Role(pub String);
 
@@ -11,12 +15,31 @@ pub struct Scope(pub String);
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Subject(pub String);
 
+/// The `aud` claim, which JWTs carry either as a single string or as an array.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Audience {
+    pub fn contains(&self, audience: &str) -> bool {
+        match self {
+            Audience::One(aud) => aud == audience,
+            Audience::Many(auds) => auds.iter().any(|a| a == audience),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: Subject,
     pub iss: String,
-    pub aud: Option<String>,
+    pub aud: Option<Audience>,
     pub exp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
 
     pub email: Option<String>,
     pub email_verified: bool,
@@ -31,6 +54,32 @@ pub struct Claims {
     pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
+impl Claims {
+    /// The organization named by the custom claim at the dotted `path`.
+    ///
+    /// The claim may be a string, an array of strings or an object keyed by organization
+    /// (the shape Keycloak uses). A claim naming several organizations is ambiguous and
+    /// yields `None`, as does a missing or empty one.
+    pub fn organization(&self, path: &str) -> Option<String> {
+        let mut segments = path.split('.');
+        let first = self.extra.get(segments.next()?)?;
+        let value = segments.try_fold(first, |value, segment| value.get(segment))?;
+
+        let mut organizations: Vec<&str> = match value {
+            Value::String(org) => vec![org.as_str()],
+            Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+            Value::Object(map) => map.keys().map(String::as_str).collect(),
+            _ => vec![],
+        };
+        organizations.retain(|org| !org.is_empty());
+
+        match organizations.as_slice() {
+            [org] => Some(org.to_string()),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Jwt {
     pub claims: Claims,
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::domain::entities::{claims::Claims, client::Client, user::User};
+use crate::domain::entities::{
+    claims::{Claims, DEFAULT_ORGANIZATION_CLAIM},
+    client::Client,
+    role_mapping::RoleMapping,
+    user::User,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Identity {
@@ -49,20 +54,56 @@ impl Identity {
     pub fn has_role(&self, role: &str) -> bool {
         self.roles().iter().any(|r| r == role)
     }
-}
 
-impl From<Claims> for Identity {
-    fn from(claims: Claims) -> Self {
+    pub fn scopes(&self) -> Vec<String> {
+        match self {
+            Identity::User(user) => user.scopes.clone(),
+            Identity::Client(client) => client.scopes.clone(),
+            Identity::Anonymous => vec![],
+        }
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().iter().any(|s| s == scope)
+    }
+
+    /// The organization (tenant) the caller acts for, if its token names exactly one.
+    pub fn organization(&self) -> Option<&str> {
+        match self {
+            Identity::User(user) => user.organization.as_deref(),
+            Identity::Client(client) => client.organization.as_deref(),
+            Identity::Anonymous => None,
+        }
+    }
+
+    /// Replace the organization, e.g. when it is read from a non-default claim.
+    pub fn with_organization(mut self, organization: Option<String>) -> Self {
+        match &mut self {
+            Identity::User(user) => user.organization = organization,
+            Identity::Client(client) => client.organization = organization,
+            Identity::Anonymous => {}
+        }
+        self
+    }
+
+    /// Build an identity from validated claims, reading roles as described by `mapping`
+    /// and the organization from the default `organization` claim.
+    pub fn from_claims(claims: Claims, mapping: &RoleMapping) -> Self {
+        let roles = mapping.extract(&claims);
+        let organization = claims.organization(DEFAULT_ORGANIZATION_CLAIM);
+        let scopes = claims
+            .scope
+            .split_whitespace()
+            .map(|s| s.to_string())
+            .collect();
+
         if let Some(client_id) = claims.client_id.clone() {
             Identity::Client(Client {
                 id: claims.sub.0.clone(),
                 client_id,
-                roles: vec![],
-                scopes: claims
-                    .scope
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect(),
+                roles,
+                scopes,
+                organization,
             })
         } else {
             Identity::User(User {
@@ -70,29 +111,39 @@ impl From<Claims> for Identity {
                 username: claims.preferred_username.clone(),
                 email: claims.email.clone(),
                 name: claims.name.clone(),
-                roles: vec![],
+                roles,
+                scopes,
+                organization,
             })
         }
     }
 }
 
+impl From<Claims> for Identity {
+    fn from(claims: Claims) -> Self {
+        Identity::from_claims(claims, &RoleMapping::default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
 
     use crate::domain::entities::{
-        claims::{Claims, Subject},
+        claims::{Audience, Claims, Subject},
         identity::Identity,
+        role_mapping::RoleMapping,
     };
 
     fn create_user_claims() -> Claims {
         Claims {
             sub: Subject("user-123".to_string()),
             iss: "https://auth.ferriscord.com".to_string(),
-            aud: Some("ferriscord-api".to_string()),
+            aud: Some(Audience::One("ferriscord-api".to_string())),
             email: Some("john.doe@example.com".to_string()),
             email_verified: true,
             exp: None,
+            nbf: None,
             name: Some("John Doe".to_string()),
             preferred_username: "johndoe".to_string(),
             given_name: Some("John".to_string()),
@@ -116,11 +167,12 @@ mod tests {
         Claims {
             sub: Subject("service-123".to_string()),
             iss: "https://auth.ferriscord.com".to_string(),
-            aud: Some("ferriscord-api".to_string()),
+            aud: Some(Audience::One("ferriscord-api".to_string())),
             email: None,
             email_verified: false,
             name: None,
             exp: None,
+            nbf: None,
             preferred_username: "service-account-bot".to_string(),
             given_name: None,
             family_name: None,
@@ -180,8 +232,10 @@ mod tests {
         assert!(!identity.is_client());
         assert_eq!(identity.id(), Some("user-123"));
         assert_eq!(identity.username(), Some("johndoe"));
-        assert!(identity.roles().is_empty());
+        assert_eq!(identity.roles(), vec!["user", "moderator"]);
+        assert!(identity.has_role("moderator"));
         assert!(!identity.has_role("admin"));
+        assert!(identity.has_scope("email"));
     }
 
     #[test]
@@ -193,7 +247,74 @@ mod tests {
         assert!(!identity.is_user());
         assert_eq!(identity.id(), Some("service-123"));
         assert_eq!(identity.username(), Some("ferriscord-bot"));
+        assert_eq!(identity.roles(), vec!["service", "bot"]);
+        assert!(identity.has_role("service"));
+        assert!(identity.has_scope("admin:all"));
+    }
+
+    #[test]
+    fn test_from_claims_reads_client_roles_from_mapping() {
+        let mut claims = create_user_claims();
+        claims.extra.insert(
+            "resource_access".to_string(),
+            json!({ "ferrisquote-api": { "roles": ["editor"] } }),
+        );
+        let mapping = RoleMapping {
+            realm_roles: false,
+            client_roles: vec!["ferrisquote-api".to_string()],
+            claim_paths: vec![],
+        };
+
+        let identity = Identity::from_claims(claims, &mapping);
+
+        assert_eq!(identity.roles(), vec!["editor"]);
+    }
+
+    #[test]
+    fn test_organization_is_read_from_claims() {
+        let mut claims = create_user_claims();
+        claims.extra.insert(
+            "organization".to_string(),
+            json!({ "acme": { "id": "42" } }),
+        );
+
+        let identity: Identity = claims.into();
+
+        assert_eq!(identity.organization(), Some("acme"));
+    }
+
+    #[test]
+    fn test_ambiguous_organization_is_dropped() {
+        let mut claims = create_service_account_claims();
+        claims
+            .extra
+            .insert("organization".to_string(), json!(["acme", "globex"]));
+
+        let identity: Identity = claims.into();
+
+        assert_eq!(identity.organization(), None);
+    }
+
+    #[test]
+    fn test_with_organization_overrides_claim() {
+        let identity: Identity = create_user_claims().into();
+
+        let identity = identity.with_organization(Some("globex".to_string()));
+
+        assert_eq!(identity.organization(), Some("globex"));
+        assert_eq!(
+            Identity::Anonymous
+                .with_organization(Some("globex".to_string()))
+                .organization(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_anonymous_has_no_roles_or_scopes() {
+        let identity = Identity::Anonymous;
+
         assert!(identity.roles().is_empty());
-        assert!(!identity.has_role("service"));
+        assert!(identity.scopes().is_empty());
     }
 }
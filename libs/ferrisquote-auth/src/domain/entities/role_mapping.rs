@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::entities::claims::Claims;
+
+/// Where roles are read from in the custom claims of a token.
+///
+/// The default reads Keycloak realm roles (`realm_access.roles`) only.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoleMapping {
+    /// Read `realm_access.roles`.
+    pub realm_roles: bool,
+    /// Clients whose `resource_access.<client>.roles` are read.
+    pub client_roles: Vec<String>,
+    /// Additional dotted claim paths (e.g. `groups` or `app.roles`) holding either an
+    /// array of strings or a space separated string.
+    pub claim_paths: Vec<String>,
+}
+
+impl Default for RoleMapping {
+    fn default() -> Self {
+        Self {
+            realm_roles: true,
+            client_roles: vec![],
+            claim_paths: vec![],
+        }
+    }
+}
+
+impl RoleMapping {
+    /// Collect the roles granted by `claims`, deduplicated, in the order they are found.
+    pub fn extract(&self, claims: &Claims) -> Vec<String> {
+        let mut roles: Vec<String> = vec![];
+        let mut push_all = |value: Option<&Value>| {
+            for role in string_list(value) {
+                if !roles.contains(&role) {
+                    roles.push(role);
+                }
+            }
+        };
+
+        if self.realm_roles {
+            push_all(lookup(&claims.extra, &["realm_access", "roles"]));
+        }
+
+        for client in &self.client_roles {
+            push_all(lookup(
+                &claims.extra,
+                &["resource_access", client.as_str(), "roles"],
+            ));
+        }
+
+        for path in &self.claim_paths {
+            let segments: Vec<&str> = path.split('.').collect();
+            push_all(lookup(&claims.extra, &segments));
+        }
+
+        roles
+    }
+}
+
+fn lookup<'a>(extra: &'a serde_json::Map<String, Value>, path: &[&str]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    rest.iter()
+        .try_fold(extra.get(*first)?, |value, segment| value.get(segment))
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(s)) => s.split_whitespace().map(str::to_string).collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::domain::entities::claims::{Claims, Subject};
+
+    use super::RoleMapping;
+
+    fn claims_with(extra: serde_json::Value) -> Claims {
+        Claims {
+            sub: Subject("user-123".to_string()),
+            iss: "https://auth.ferriscord.com".to_string(),
+            aud: None,
+            exp: None,
+            nbf: None,
+            email: None,
+            email_verified: false,
+            name: None,
+            preferred_username: "johndoe".to_string(),
+            given_name: None,
+            family_name: None,
+            scope: String::new(),
+            client_id: None,
+            extra: extra.as_object().cloned().unwrap_or_default(),
+        }
+    }
+
+    fn keycloak_claims() -> Claims {
+        claims_with(json!({
+            "realm_access": { "roles": ["user", "offline_access"] },
+            "resource_access": {
+                "ferrisquote-api": { "roles": ["editor", "user"] },
+                "account": { "roles": ["manage-account"] }
+            },
+            "groups": ["sales"],
+            "app": { "roles": "approver auditor" }
+        }))
+    }
+
+    #[test]
+    fn test_default_mapping_reads_realm_roles_only() {
+        let roles = RoleMapping::default().extract(&keycloak_claims());
+
+        assert_eq!(roles, vec!["user", "offline_access"]);
+    }
+
+    #[test]
+    fn test_client_roles_are_read_for_configured_clients_and_deduplicated() {
+        let mapping = RoleMapping {
+            client_roles: vec!["ferrisquote-api".to_string()],
+            ..RoleMapping::default()
+        };
+
+        let roles = mapping.extract(&keycloak_claims());
+
+        assert_eq!(roles, vec!["user", "offline_access", "editor"]);
+    }
+
+    #[test]
+    fn test_custom_claim_paths_accept_arrays_and_strings() {
+        let mapping = RoleMapping {
+            realm_roles: false,
+            client_roles: vec![],
+            claim_paths: vec!["groups".to_string(), "app.roles".to_string()],
+        };
+
+        let roles = mapping.extract(&keycloak_claims());
+
+        assert_eq!(roles, vec!["sales", "approver", "auditor"]);
+    }
+
+    #[test]
+    fn test_missing_or_malformed_claims_yield_no_roles() {
+        let mapping = RoleMapping {
+            realm_roles: true,
+            client_roles: vec!["unknown".to_string()],
+            claim_paths: vec!["groups.nested".to_string()],
+        };
+
+        assert!(mapping.extract(&claims_with(json!({}))).is_empty());
+        assert!(
+            mapping
+                .extract(&claims_with(json!({ "realm_access": { "roles": 42 } })))
+                .is_empty()
+        );
+    }
+}
@@ -7,4 +7,7 @@ pub struct User {
     pub email: Option<String>,
     pub name: Option<String>,
     pub roles: Vec<String>,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub organization: Option<String>,
 }
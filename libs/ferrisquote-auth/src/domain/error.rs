@@ -16,4 +16,25 @@ pub enum AuthError {
 
     #[error("token expired")]
     Expired,
+
+    #[error("token not yet valid")]
+    NotYetValid,
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("invalid issuer, expected {expected}")]
+    InvalidIssuer { expected: String },
+
+    #[error("invalid audience")]
+    InvalidAudience,
+
+    #[error("missing claim: {claim}")]
+    MissingClaim { claim: String },
+
+    #[error("algorithm not allowed: {algorithm}")]
+    UnsupportedAlgorithm { algorithm: String },
+
+    #[error("algorithm {algorithm} cannot be used with a {key_type} key")]
+    AlgorithmMismatch { algorithm: String, key_type: String },
 }
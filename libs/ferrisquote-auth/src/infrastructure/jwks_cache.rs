@@ -0,0 +1,270 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{Mutex, RwLock};
+
+use crate::domain::error::AuthError;
+
+use super::ferriskey_repository::Jwks;
+
+/// How long a key set is kept when the IdP response carries no `Cache-Control: max-age`.
+pub const DEFAULT_JWKS_TTL: Duration = Duration::from_secs(300);
+
+/// Minimum delay between two refetches triggered by an unknown `kid`.
+pub const DEFAULT_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A key set as returned by the IdP, with the lifetime it advertised.
+#[derive(Debug)]
+pub struct FetchedJwks {
+    pub jwks: Jwks,
+    /// `None` when the response did not say how long it may be cached.
+    pub max_age: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedJwks {
+    jwks: Arc<Jwks>,
+    fetched_at: Instant,
+    ttl: Duration,
+}
+
+impl CachedJwks {
+    fn is_fresh(&self, now: Instant) -> bool {
+        now.duration_since(self.fetched_at) < self.ttl
+    }
+}
+
+/// In-memory JWKS cache shared by every clone of a repository.
+///
+/// Keys are refetched when the TTL elapses, or earlier when a token references a `kid`
+/// the cached set does not know (key rotation), at most once per `min_refresh_interval`.
+/// Concurrent callers needing a refresh wait for a single fetch instead of each issuing one.
+#[derive(Debug)]
+pub struct JwksCache {
+    entry: RwLock<Option<CachedJwks>>,
+    refresh: Mutex<()>,
+    default_ttl: Duration,
+    min_refresh_interval: Duration,
+}
+
+impl Default for JwksCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_JWKS_TTL, DEFAULT_MIN_REFRESH_INTERVAL)
+    }
+}
+
+impl JwksCache {
+    pub fn new(default_ttl: Duration, min_refresh_interval: Duration) -> Self {
+        Self {
+            entry: RwLock::new(None),
+            refresh: Mutex::new(()),
+            default_ttl,
+            min_refresh_interval,
+        }
+    }
+
+    /// Return a key set that should contain `kid`, calling `fetch` only when needed.
+    ///
+    /// The returned set may still lack `kid` when a refetch was rate limited or the IdP
+    /// does not publish it; callers report that as `KeyNotFound`.
+    pub async fn key_set<F, Fut>(&self, kid: &str, fetch: F) -> Result<Arc<Jwks>, AuthError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<FetchedJwks, AuthError>>,
+    {
+        let seen = self.entry.read().await.clone();
+        if let Some(entry) = &seen
+            && entry.is_fresh(Instant::now())
+            && entry.jwks.find(kid).is_some()
+        {
+            return Ok(entry.jwks.clone());
+        }
+
+        let _refresh = self.refresh.lock().await;
+
+        // Re-read: another caller may have refreshed while we were waiting for the lock.
+        if let Some(entry) = self.entry.read().await.clone() {
+            let now = Instant::now();
+            let refreshed_meanwhile = seen
+                .as_ref()
+                .is_none_or(|s| s.fetched_at != entry.fetched_at);
+            let recently_fetched = now.duration_since(entry.fetched_at) < self.min_refresh_interval;
+
+            if entry.is_fresh(now)
+                && (entry.jwks.find(kid).is_some() || refreshed_meanwhile || recently_fetched)
+            {
+                return Ok(entry.jwks);
+            }
+        }
+
+        let fetched = fetch().await?;
+        let entry = CachedJwks {
+            jwks: Arc::new(fetched.jwks),
+            fetched_at: Instant::now(),
+            ttl: fetched.max_age.unwrap_or(self.default_ttl),
+        };
+        let jwks = entry.jwks.clone();
+        *self.entry.write().await = Some(entry);
+
+        Ok(jwks)
+    }
+}
+
+/// Read the cache lifetime from a `Cache-Control` header value.
+///
+/// `no-store` and `no-cache` disable caching; otherwise `max-age` is used when present.
+pub fn max_age_from_cache_control(value: &str) -> Option<Duration> {
+    let mut max_age = None;
+
+    for directive in value.split(',').map(str::trim) {
+        let (name, arg) = directive
+            .split_once('=')
+            .map_or((directive, None), |(n, a)| (n.trim(), Some(a.trim())));
+
+        if name.eq_ignore_ascii_case("no-store") || name.eq_ignore_ascii_case("no-cache") {
+            return Some(Duration::ZERO);
+        }
+
+        if name.eq_ignore_ascii_case("max-age") {
+            max_age = arg
+                .and_then(|a| a.trim_matches('"').parse().ok())
+                .map(Duration::from_secs);
+        }
+    }
+
+    max_age
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    fn jwks(kids: &[&str]) -> Jwks {
+        let keys: Vec<String> = kids
+            .iter()
+            .map(|kid| format!(r#"{{"kid":"{kid}","n":"n","e":"AQAB"}}"#))
+            .collect();
+        serde_json::from_str(&format!(r#"{{"keys":[{}]}}"#, keys.join(","))).unwrap()
+    }
+
+    fn fetched(kids: &[&str], max_age: Option<Duration>) -> Result<FetchedJwks, AuthError> {
+        Ok(FetchedJwks {
+            jwks: jwks(kids),
+            max_age,
+        })
+    }
+
+    #[test]
+    fn test_max_age_from_cache_control() {
+        assert_eq!(
+            max_age_from_cache_control("public, max-age=600"),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            max_age_from_cache_control("no-cache, max-age=600"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(max_age_from_cache_control("no-store"), Some(Duration::ZERO));
+        assert_eq!(max_age_from_cache_control("public"), None);
+        assert_eq!(max_age_from_cache_control("max-age=abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_entry_is_served_without_fetching() {
+        let cache = JwksCache::default();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            cache
+                .key_set("a", || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    fetched(&["a"], None)
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_refetched() {
+        let cache = JwksCache::default();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            cache
+                .key_set("a", || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    fetched(&["a"], Some(Duration::ZERO))
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refetch_is_rate_limited() {
+        let cache = JwksCache::new(DEFAULT_JWKS_TTL, Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let set = cache
+                .key_set("rotated", || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    fetched(&["old"], None)
+                })
+                .await
+                .unwrap();
+            assert!(set.find("rotated").is_none());
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refetches_after_interval() {
+        let cache = JwksCache::new(DEFAULT_JWKS_TTL, Duration::ZERO);
+
+        cache
+            .key_set("old", || async { fetched(&["old"], None) })
+            .await
+            .unwrap();
+        let set = cache
+            .key_set("rotated", || async { fetched(&["old", "rotated"], None) })
+            .await
+            .unwrap();
+
+        assert!(set.find("rotated").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_error_is_returned_and_not_cached() {
+        let cache = JwksCache::default();
+
+        let err = cache
+            .key_set("a", || async {
+                Err(AuthError::Network {
+                    message: "down".into(),
+                })
+            })
+            .await
+            .expect_err("expected network error");
+        assert!(matches!(err, AuthError::Network { .. }));
+
+        let set = cache
+            .key_set("a", || async { fetched(&["a"], None) })
+            .await
+            .unwrap();
+        assert!(set.find("a").is_some());
+    }
+}
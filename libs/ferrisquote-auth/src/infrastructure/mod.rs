@@ -1 +1,5 @@
 pub mod ferriskey_repository;
+pub mod jwks_cache;
+pub mod oidc_discovery;
+pub mod static_key_repository;
+pub mod token_validation;
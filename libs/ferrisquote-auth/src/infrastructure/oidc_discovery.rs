@@ -0,0 +1,310 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::domain::error::AuthError;
+
+/// How long a discovered configuration is kept before it is fetched again.
+pub const DEFAULT_DISCOVERY_TTL: Duration = Duration::from_secs(3600);
+
+/// Minimum delay before retrying a discovery that failed.
+pub const DEFAULT_DISCOVERY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The subset of an OpenID Provider configuration document used to validate tokens.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OidcConfiguration {
+    pub issuer: String,
+    pub jwks_uri: String,
+    #[serde(default)]
+    pub id_token_signing_alg_values_supported: Vec<String>,
+}
+
+impl OidcConfiguration {
+    /// Whether the provider advertises `algorithm`; providers listing nothing accept anything.
+    pub fn supports(&self, algorithm: Algorithm) -> bool {
+        self.id_token_signing_alg_values_supported.is_empty()
+            || self
+                .id_token_signing_alg_values_supported
+                .iter()
+                .any(|alg| alg.parse::<Algorithm>().ok() == Some(algorithm))
+    }
+}
+
+/// `{issuer}/.well-known/openid-configuration`
+pub fn discovery_url(issuer: &str) -> String {
+    format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    )
+}
+
+/// Keycloak-style JWKS location, used when discovery is unavailable.
+pub fn legacy_jwks_url(issuer: &str) -> String {
+    format!(
+        "{}/protocol/openid-connect/certs",
+        issuer.trim_end_matches('/')
+    )
+}
+
+#[derive(Debug, Default)]
+struct DiscoveryState {
+    /// The last configuration fetched successfully, with when it was fetched.
+    config: Option<(Arc<OidcConfiguration>, Instant)>,
+    /// When the last fetch failed, cleared by the next success.
+    failed_at: Option<Instant>,
+}
+
+/// In-memory cache of the provider configuration, shared by every clone of a repository.
+///
+/// The configuration is refetched once `ttl` has elapsed. After a failed fetch, discovery
+/// is not attempted again before `retry_interval`; meanwhile the last configuration found,
+/// if any, keeps being served. Concurrent callers wait for a single fetch.
+#[derive(Debug)]
+pub struct DiscoveryCache {
+    state: RwLock<DiscoveryState>,
+    refresh: Mutex<()>,
+    ttl: Duration,
+    retry_interval: Duration,
+}
+
+impl Default for DiscoveryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISCOVERY_TTL, DEFAULT_DISCOVERY_RETRY_INTERVAL)
+    }
+}
+
+impl DiscoveryCache {
+    pub fn new(ttl: Duration, retry_interval: Duration) -> Self {
+        Self {
+            state: RwLock::new(DiscoveryState::default()),
+            refresh: Mutex::new(()),
+            ttl,
+            retry_interval,
+        }
+    }
+
+    /// The last configuration discovered, fresh or not.
+    pub async fn current(&self) -> Option<Arc<OidcConfiguration>> {
+        self.state
+            .read()
+            .await
+            .config
+            .as_ref()
+            .map(|(config, _)| config.clone())
+    }
+
+    /// Return the provider configuration, calling `fetch` only when needed.
+    ///
+    /// Fails with the fetch error, or `Network` while backing off after one, when no
+    /// configuration was ever discovered.
+    pub async fn configuration<F, Fut>(&self, fetch: F) -> Result<Arc<OidcConfiguration>, AuthError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<OidcConfiguration, AuthError>>,
+    {
+        if let Some(config) = self.cached(Instant::now()).await? {
+            return Ok(config);
+        }
+
+        let _refresh = self.refresh.lock().await;
+
+        // Re-check: another caller may have fetched while we were waiting for the lock.
+        if let Some(config) = self.cached(Instant::now()).await? {
+            return Ok(config);
+        }
+
+        let fetched = fetch().await;
+        let mut state = self.state.write().await;
+        match fetched {
+            Ok(config) => {
+                let config = Arc::new(config);
+                state.config = Some((config.clone(), Instant::now()));
+                state.failed_at = None;
+                Ok(config)
+            }
+            Err(e) => {
+                state.failed_at = Some(Instant::now());
+                state
+                    .config
+                    .as_ref()
+                    .map(|(config, _)| config.clone())
+                    .ok_or(e)
+            }
+        }
+    }
+
+    /// The configuration to serve without fetching, `None` when a fetch is due.
+    async fn cached(&self, now: Instant) -> Result<Option<Arc<OidcConfiguration>>, AuthError> {
+        let state = self.state.read().await;
+
+        if let Some((config, fetched_at)) = &state.config
+            && now.duration_since(*fetched_at) < self.ttl
+        {
+            return Ok(Some(config.clone()));
+        }
+
+        match state.failed_at {
+            Some(failed_at) if now.duration_since(failed_at) < self.retry_interval => {
+                match &state.config {
+                    Some((config, _)) => Ok(Some(config.clone())),
+                    None => Err(AuthError::Network {
+                        message: "openid configuration unavailable, retrying later".into(),
+                    }),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use jsonwebtoken::Algorithm;
+
+    use super::*;
+
+    #[test]
+    fn test_urls_are_built_from_issuer_without_trailing_slash() {
+        assert_eq!(
+            discovery_url("https://idp.example.com/realms/app/"),
+            "https://idp.example.com/realms/app/.well-known/openid-configuration"
+        );
+        assert_eq!(
+            legacy_jwks_url("https://idp.example.com/realms/app"),
+            "https://idp.example.com/realms/app/protocol/openid-connect/certs"
+        );
+    }
+
+    #[test]
+    fn test_supports_checks_advertised_algorithms() {
+        let config: OidcConfiguration = serde_json::from_str(
+            r#"{"issuer":"https://idp","jwks_uri":"https://idp/keys","id_token_signing_alg_values_supported":["RS256","ES256"]}"#,
+        )
+        .unwrap();
+
+        assert!(config.supports(Algorithm::ES256));
+        assert!(!config.supports(Algorithm::EdDSA));
+    }
+
+    #[test]
+    fn test_supports_anything_when_nothing_is_advertised() {
+        let config: OidcConfiguration =
+            serde_json::from_str(r#"{"issuer":"https://idp","jwks_uri":"https://idp/keys"}"#)
+                .unwrap();
+
+        assert!(config.supports(Algorithm::EdDSA));
+    }
+
+    fn configuration(jwks_uri: &str) -> Result<OidcConfiguration, AuthError> {
+        Ok(OidcConfiguration {
+            issuer: "https://idp".to_string(),
+            jwks_uri: jwks_uri.to_string(),
+            id_token_signing_alg_values_supported: Vec::new(),
+        })
+    }
+
+    fn unavailable() -> Result<OidcConfiguration, AuthError> {
+        Err(AuthError::Network {
+            message: "down".into(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_fresh_configuration_is_served_without_fetching() {
+        let cache = DiscoveryCache::default();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            cache
+                .configuration(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    configuration("https://idp/keys")
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_configuration_is_refetched() {
+        let cache = DiscoveryCache::new(Duration::ZERO, DEFAULT_DISCOVERY_RETRY_INTERVAL);
+
+        cache
+            .configuration(|| async { configuration("https://idp/keys") })
+            .await
+            .unwrap();
+        let config = cache
+            .configuration(|| async { configuration("https://idp/rotated") })
+            .await
+            .unwrap();
+
+        assert_eq!(config.jwks_uri, "https://idp/rotated");
+    }
+
+    #[tokio::test]
+    async fn test_failed_discovery_is_not_retried_before_interval() {
+        let cache = DiscoveryCache::default();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let err = cache
+                .configuration(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    unavailable()
+                })
+                .await
+                .expect_err("expected discovery to fail");
+            assert!(matches!(err, AuthError::Network { .. }));
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_discovery_is_retried_after_interval() {
+        let cache = DiscoveryCache::new(DEFAULT_DISCOVERY_TTL, Duration::ZERO);
+
+        cache
+            .configuration(|| async { unavailable() })
+            .await
+            .expect_err("expected discovery to fail");
+        let config = cache
+            .configuration(|| async { configuration("https://idp/keys") })
+            .await
+            .unwrap();
+
+        assert_eq!(config.jwks_uri, "https://idp/keys");
+    }
+
+    #[tokio::test]
+    async fn test_last_configuration_is_kept_when_refresh_fails() {
+        let cache = DiscoveryCache::new(Duration::ZERO, Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        cache
+            .configuration(|| async { configuration("https://idp/keys") })
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let config = cache
+                .configuration(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    unavailable()
+                })
+                .await
+                .unwrap();
+            assert_eq!(config.jwks_uri, "https://idp/keys");
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
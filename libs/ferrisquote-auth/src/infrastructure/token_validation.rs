@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use jsonwebtoken::{Algorithm, Validation, errors::ErrorKind};
+
+use crate::domain::error::AuthError;
+
+/// Clock skew tolerated on `exp` and `nbf` by default.
+pub const DEFAULT_LEEWAY: Duration = Duration::from_secs(60);
+
+/// Rules applied to a token once its signing key is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenValidation {
+    /// Accepted `aud` values; the token must carry at least one. Empty disables the check.
+    pub audiences: Vec<String>,
+    /// Clock skew tolerated on `exp` and `nbf`.
+    pub leeway: Duration,
+    /// Algorithms a token header may declare.
+    pub algorithms: Vec<Algorithm>,
+}
+
+impl Default for TokenValidation {
+    fn default() -> Self {
+        Self {
+            audiences: vec![],
+            leeway: DEFAULT_LEEWAY,
+            algorithms: vec![Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA],
+        }
+    }
+}
+
+impl TokenValidation {
+    /// Check `algorithm` against the allowed list.
+    pub fn check_algorithm(&self, algorithm: Algorithm) -> Result<(), AuthError> {
+        if self.algorithms.contains(&algorithm) {
+            Ok(())
+        } else {
+            Err(AuthError::UnsupportedAlgorithm {
+                algorithm: format!("{algorithm:?}"),
+            })
+        }
+    }
+
+    /// `jsonwebtoken` settings for a token signed with `algorithm` by `issuer`.
+    ///
+    /// `exp` and `iss` are always required, `aud` only when audiences are configured.
+    pub fn to_jwt_validation(&self, algorithm: Algorithm, issuer: &str) -> Validation {
+        let mut validation = Validation::new(algorithm);
+
+        validation.leeway = self.leeway.as_secs();
+        validation.validate_exp = true;
+        validation.validate_nbf = true;
+        validation.set_issuer(&[issuer]);
+
+        if self.audiences.is_empty() {
+            validation.validate_aud = false;
+            validation.set_required_spec_claims(&["exp", "iss"]);
+        } else {
+            validation.set_audience(&self.audiences);
+            validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        }
+
+        validation
+    }
+}
+
+/// Map a `jsonwebtoken` decoding failure to the matching `AuthError`.
+pub fn map_jwt_error(error: jsonwebtoken::errors::Error, issuer: &str) -> AuthError {
+    match error.kind() {
+        ErrorKind::ExpiredSignature => AuthError::Expired,
+        ErrorKind::ImmatureSignature => AuthError::NotYetValid,
+        ErrorKind::InvalidSignature => AuthError::InvalidSignature,
+        ErrorKind::InvalidIssuer => AuthError::InvalidIssuer {
+            expected: issuer.to_string(),
+        },
+        ErrorKind::InvalidAudience => AuthError::InvalidAudience,
+        ErrorKind::MissingRequiredClaim(claim) => AuthError::MissingClaim {
+            claim: claim.clone(),
+        },
+        ErrorKind::InvalidAlgorithm => AuthError::UnsupportedAlgorithm {
+            algorithm: error.to_string(),
+        },
+        _ => AuthError::InvalidToken {
+            message: error.to_string(),
+        },
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::organization::entities::ids::OrganizationId;
+
+use super::ids::ApiKeyId;
+
+/// Prefix of every secret, so leaked keys are easy to recognise and scan for.
+pub const API_KEY_PREFIX: &str = "fq_";
+
+/// Length of the secret kept in clear (`prefix`) to tell keys apart in listings.
+const DISPLAY_PREFIX_LEN: usize = 11;
+
+/// A long-lived credential for machine-to-machine integrations.
+///
+/// Only the SHA-256 hash of the secret is stored; the secret itself is returned
+/// once, when the key is issued. The key acts for `organization_id` with the
+/// permissions named by `scopes` (e.g. `estimator:evaluate`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    pub organization_id: OrganizationId,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A freshly issued key together with its secret, which cannot be recovered later.
+#[derive(Debug, Clone)]
+pub struct IssuedApiKey {
+    pub api_key: ApiKey,
+    pub secret: String,
+}
+
+impl ApiKey {
+    /// Generate a new secret and the key storing its hash.
+    pub fn issue(
+        organization_id: OrganizationId,
+        name: String,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> IssuedApiKey {
+        // Two v4 UUIDs carry 244 random bits.
+        let secret = format!(
+            "{API_KEY_PREFIX}{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+
+        let api_key = Self {
+            id: ApiKeyId::new(),
+            organization_id,
+            name,
+            prefix: secret[..DISPLAY_PREFIX_LEN].to_string(),
+            key_hash: Self::hash_secret(&secret),
+            scopes,
+            created_at: Utc::now(),
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+        };
+
+        IssuedApiKey { api_key, secret }
+    }
+
+    /// Hex-encoded SHA-256 of `secret`, the form keys are stored and looked up by.
+    pub fn hash_secret(secret: &str) -> String {
+        format!("{:x}", Sha256::digest(secret.as_bytes()))
+    }
+
+    /// A key can be used until it is revoked or expires.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|at| now < at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_issue_stores_only_the_hash_of_the_secret() {
+        let issued = ApiKey::issue(
+            OrganizationId::new("acme"),
+            "erp".to_string(),
+            vec!["estimator:evaluate".to_string()],
+            None,
+        );
+
+        assert!(issued.secret.starts_with(API_KEY_PREFIX));
+        assert_eq!(issued.secret.len(), API_KEY_PREFIX.len() + 64);
+        assert!(issued.secret.starts_with(&issued.api_key.prefix));
+        assert_ne!(issued.api_key.key_hash, issued.secret);
+        assert_eq!(issued.api_key.key_hash, ApiKey::hash_secret(&issued.secret));
+        assert_eq!(issued.api_key.key_hash.len(), 64);
+    }
+
+    #[test]
+    fn test_issued_secrets_are_distinct() {
+        let org = OrganizationId::new("acme");
+        let a = ApiKey::issue(org.clone(), "a".to_string(), vec![], None);
+        let b = ApiKey::issue(org, "b".to_string(), vec![], None);
+
+        assert_ne!(a.secret, b.secret);
+        assert_ne!(a.api_key.key_hash, b.api_key.key_hash);
+    }
+
+    #[test]
+    fn test_key_is_inactive_once_expired_or_revoked() {
+        let now = Utc::now();
+        let mut key = ApiKey::issue(
+            OrganizationId::new("acme"),
+            "erp".to_string(),
+            vec![],
+            Some(now + Duration::days(1)),
+        )
+        .api_key;
+
+        assert!(key.is_active(now));
+        assert!(!key.is_active(now + Duration::days(1)));
+
+        key.revoked_at = Some(now);
+        assert!(!key.is_active(now));
+    }
+}
@@ -0,0 +1,76 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::entities::{
+    api_key::{ApiKey, IssuedApiKey},
+    ids::ApiKeyId,
+};
+
+/// Repository trait for ApiKey persistence.
+///
+/// Keys are looked up by hash without any organization (that is how callers
+/// authenticate); every other lookup is scoped to `organization`.
+pub trait ApiKeyRepository: Send + Sync {
+    fn create_api_key(
+        &self,
+        api_key: ApiKey,
+    ) -> impl Future<Output = Result<ApiKey, DomainError>> + Send;
+
+    fn get_api_key_by_hash(
+        &self,
+        key_hash: &str,
+    ) -> impl Future<Output = Result<ApiKey, DomainError>> + Send;
+
+    fn list_api_keys(
+        &self,
+        organization: &OrganizationId,
+    ) -> impl Future<Output = Result<Vec<ApiKey>, DomainError>> + Send;
+
+    fn revoke_api_key(
+        &self,
+        organization: &OrganizationId,
+        id: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<ApiKey, DomainError>> + Send;
+
+    /// Record that the key was just used.
+    fn touch_api_key(
+        &self,
+        id: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+}
+
+/// Service issuing, listing, revoking and authenticating API keys.
+pub trait ApiKeyService: Send + Sync {
+    /// Issue a key for `organization`. Every scope must name a `Permission`.
+    fn create_api_key(
+        &self,
+        organization: &OrganizationId,
+        name: String,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<IssuedApiKey, DomainError>> + Send;
+
+    fn list_api_keys(
+        &self,
+        organization: &OrganizationId,
+    ) -> impl Future<Output = Result<Vec<ApiKey>, DomainError>> + Send;
+
+    fn revoke_api_key(
+        &self,
+        organization: &OrganizationId,
+        id: ApiKeyId,
+    ) -> impl Future<Output = Result<ApiKey, DomainError>> + Send;
+
+    /// Resolve the key holding `secret`, recording its use.
+    ///
+    /// Unknown, revoked and expired keys are all reported as `Unauthorized`.
+    fn authenticate(
+        &self,
+        secret: &str,
+    ) -> impl Future<Output = Result<ApiKey, DomainError>> + Send;
+}
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    authorization::entities::permission::Permission, error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+
+use super::{
+    entities::{
+        api_key::{ApiKey, IssuedApiKey},
+        ids::ApiKeyId,
+    },
+    ports::{ApiKeyRepository, ApiKeyService},
+};
+
+const MAX_NAME_LEN: usize = 128;
+
+#[derive(Clone)]
+pub struct ApiKeyServiceImpl<KR> {
+    repo: KR,
+}
+
+impl<KR> ApiKeyServiceImpl<KR> {
+    pub fn new(repo: KR) -> Self {
+        Self { repo }
+    }
+}
+
+impl<KR> ApiKeyService for ApiKeyServiceImpl<KR>
+where
+    KR: ApiKeyRepository + Send + Sync,
+{
+    async fn create_api_key(
+        &self,
+        organization: &OrganizationId,
+        name: String,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<IssuedApiKey, DomainError> {
+        let name = name.trim().to_string();
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(DomainError::validation(format!(
+                "API key name must be between 1 and {MAX_NAME_LEN} characters"
+            )));
+        }
+        if scopes.is_empty() {
+            return Err(DomainError::validation("API key needs at least one scope"));
+        }
+        for scope in &scopes {
+            scope.parse::<Permission>()?;
+        }
+        if expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(DomainError::validation(
+                "API key expiry must be in the future",
+            ));
+        }
+
+        let issued = ApiKey::issue(organization.clone(), name, scopes, expires_at);
+        let api_key = self.repo.create_api_key(issued.api_key).await?;
+
+        Ok(IssuedApiKey {
+            api_key,
+            secret: issued.secret,
+        })
+    }
+
+    async fn list_api_keys(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<ApiKey>, DomainError> {
+        self.repo.list_api_keys(organization).await
+    }
+
+    async fn revoke_api_key(
+        &self,
+        organization: &OrganizationId,
+        id: ApiKeyId,
+    ) -> Result<ApiKey, DomainError> {
+        self.repo.revoke_api_key(organization, id, Utc::now()).await
+    }
+
+    async fn authenticate(&self, secret: &str) -> Result<ApiKey, DomainError> {
+        let invalid = || DomainError::unauthorized("Invalid API key");
+
+        let mut api_key = match self
+            .repo
+            .get_api_key_by_hash(&ApiKey::hash_secret(secret))
+            .await
+        {
+            Ok(api_key) => api_key,
+            Err(DomainError::NotFound { .. }) => return Err(invalid()),
+            Err(e) => return Err(e),
+        };
+
+        let now = Utc::now();
+        if !api_key.is_active(now) {
+            return Err(invalid());
+        }
+
+        self.repo.touch_api_key(api_key.id, now).await?;
+        api_key.last_used_at = Some(now);
+
+        Ok(api_key)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod actor;
+pub mod entry;
+pub mod ids;
+pub mod query;
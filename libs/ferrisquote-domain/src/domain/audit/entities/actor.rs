@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+/// Kind of actor behind a recorded change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorKind {
+    User,
+    /// A service account or API key.
+    Client,
+    /// A background job of the application itself (e.g. the quote expiry sweep).
+    System,
+}
+
+impl ActorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActorKind::User => "user",
+            ActorKind::Client => "client",
+            ActorKind::System => "system",
+        }
+    }
+}
+
+impl std::fmt::Display for ActorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ActorKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(ActorKind::User),
+            "client" => Ok(ActorKind::Client),
+            "system" => Ok(ActorKind::System),
+            other => Err(DomainError::validation(format!(
+                "Unknown actor kind '{other}'"
+            ))),
+        }
+    }
+}
+
+/// Who made a change, as written to the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    pub kind: ActorKind,
+    /// Subject of the user or client. `None` for the system.
+    pub id: Option<String>,
+    /// Display name: username, client id or job name.
+    pub name: Option<String>,
+}
+
+impl Actor {
+    pub fn new(kind: ActorKind, id: Option<String>, name: Option<String>) -> Self {
+        Self { kind, id, name }
+    }
+
+    pub fn system(name: impl Into<String>) -> Self {
+        Self {
+            kind: ActorKind::System,
+            id: None,
+            name: Some(name.into()),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::{
+    error::DomainError, organization::entities::ids::OrganizationId,
+    quote::entities::diff::ValueChange,
+};
+
+use super::{actor::Actor, ids::AuditEntryId};
+
+/// Kind of entity an audit entry is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntity {
+    Flow,
+    Step,
+    Field,
+    Estimator,
+    EstimatorVariable,
+    Quote,
+    ShareLink,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Flow => "flow",
+            AuditEntity::Step => "step",
+            AuditEntity::Field => "field",
+            AuditEntity::Estimator => "estimator",
+            AuditEntity::EstimatorVariable => "estimator_variable",
+            AuditEntity::Quote => "quote",
+            AuditEntity::ShareLink => "share_link",
+        }
+    }
+}
+
+impl std::fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AuditEntity {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flow" => Ok(AuditEntity::Flow),
+            "step" => Ok(AuditEntity::Step),
+            "field" => Ok(AuditEntity::Field),
+            "estimator" => Ok(AuditEntity::Estimator),
+            "estimator_variable" => Ok(AuditEntity::EstimatorVariable),
+            "quote" => Ok(AuditEntity::Quote),
+            "share_link" => Ok(AuditEntity::ShareLink),
+            other => Err(DomainError::validation(format!(
+                "Unknown audited entity '{other}'"
+            ))),
+        }
+    }
+}
+
+/// What happened to the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            other => Err(DomainError::validation(format!(
+                "Unknown audit action '{other}'"
+            ))),
+        }
+    }
+}
+
+/// One change to an entity of an organization, kept forever.
+///
+/// `before` and `after` are JSON snapshots of the entity; `before` is `None` for
+/// a creation and `after` is `None` for a deletion. `changes` lists the leaves
+/// that differ between the two, keyed by dotted path (e.g. `config.min`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: AuditEntryId,
+    pub organization_id: OrganizationId,
+    pub actor: Actor,
+    pub entity: AuditEntity,
+    pub entity_id: String,
+    pub action: AuditAction,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changes: Vec<ValueChange<Value>>,
+    pub at: DateTime<Utc>,
+}
+
+impl AuditEntry {
+    /// Record the move of an entity from `before` to `after`.
+    ///
+    /// The action is derived from which snapshots are present.
+    pub fn new(
+        organization_id: OrganizationId,
+        actor: Actor,
+        entity: AuditEntity,
+        entity_id: impl Into<String>,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> Self {
+        let action = match (&before, &after) {
+            (None, _) => AuditAction::Create,
+            (Some(_), None) => AuditAction::Delete,
+            (Some(_), Some(_)) => AuditAction::Update,
+        };
+        let changes = diff_values(before.as_ref(), after.as_ref());
+
+        Self {
+            id: AuditEntryId::new(),
+            organization_id,
+            actor,
+            entity,
+            entity_id: entity_id.into(),
+            action,
+            before,
+            after,
+            changes,
+            at: Utc::now(),
+        }
+    }
+
+    /// An update that did not change anything.
+    pub fn is_noop(&self) -> bool {
+        self.action == AuditAction::Update && self.changes.is_empty()
+    }
+}
+
+/// Leaves that differ between two JSON documents, sorted by dotted path.
+///
+/// Objects are compared key by key; arrays and scalars are compared as a whole.
+pub fn diff_values(before: Option<&Value>, after: Option<&Value>) -> Vec<ValueChange<Value>> {
+    let mut changes = Vec::new();
+    diff_into(String::new(), before, after, &mut changes);
+    changes
+}
+
+fn diff_into(
+    path: String,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    changes: &mut Vec<ValueChange<Value>>,
+) {
+    let before_map = before.and_then(Value::as_object);
+    let after_map = after.and_then(Value::as_object);
+
+    let descend = match (before, after) {
+        (Some(_), Some(_)) => before_map.is_some() && after_map.is_some(),
+        (Some(_), None) => before_map.is_some(),
+        (None, Some(_)) => after_map.is_some(),
+        (None, None) => false,
+    };
+
+    if descend {
+        let keys: BTreeSet<&String> = before_map
+            .into_iter()
+            .chain(after_map)
+            .flat_map(|map| map.keys())
+            .collect();
+
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            diff_into(
+                child,
+                before_map.and_then(|m| m.get(key)),
+                after_map.and_then(|m| m.get(key)),
+                changes,
+            );
+        }
+    } else if before != after {
+        changes.push(ValueChange {
+            key: path,
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn entry(before: Option<Value>, after: Option<Value>) -> AuditEntry {
+        AuditEntry::new(
+            OrganizationId::new("acme"),
+            Actor::system("test"),
+            AuditEntity::Flow,
+            "flow-1",
+            before,
+            after,
+        )
+    }
+
+    #[test]
+    fn test_action_follows_snapshots() {
+        assert_eq!(entry(None, Some(json!({}))).action, AuditAction::Create);
+        assert_eq!(entry(Some(json!({})), None).action, AuditAction::Delete);
+        assert_eq!(
+            entry(Some(json!({})), Some(json!({}))).action,
+            AuditAction::Update
+        );
+    }
+
+    #[test]
+    fn test_diff_walks_nested_objects() {
+        let before = json!({"name": "Kitchen", "config": {"min": 0, "max": 10}, "tags": [1]});
+        let after = json!({"name": "Kitchen", "config": {"min": 5, "max": 10}, "tags": [1, 2]});
+
+        let changes = diff_values(Some(&before), Some(&after));
+
+        assert_eq!(
+            changes,
+            vec![
+                ValueChange {
+                    key: "config.min".to_string(),
+                    before: Some(json!(0)),
+                    after: Some(json!(5)),
+                },
+                ValueChange {
+                    key: "tags".to_string(),
+                    before: Some(json!([1])),
+                    after: Some(json!([1, 2])),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_of_creation_lists_every_leaf() {
+        let changes = diff_values(
+            None,
+            Some(&json!({"name": "Kitchen", "config": {"min": 0}})),
+        );
+
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["config.min", "name"]);
+        assert!(changes.iter().all(|c| c.before.is_none()));
+    }
+
+    #[test]
+    fn test_added_and_removed_keys_have_no_counterpart() {
+        let changes = diff_values(Some(&json!({"old": 1})), Some(&json!({"new": 2})));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].key, "new");
+        assert_eq!(changes[0].before, None);
+        assert_eq!(changes[1].key, "old");
+        assert_eq!(changes[1].after, None);
+    }
+
+    #[test]
+    fn test_unchanged_update_is_a_noop() {
+        let snapshot = json!({"name": "Kitchen"});
+        assert!(entry(Some(snapshot.clone()), Some(snapshot)).is_noop());
+        assert!(!entry(None, Some(json!({"name": "Kitchen"}))).is_noop());
+    }
+}
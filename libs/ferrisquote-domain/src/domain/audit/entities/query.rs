@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use super::entry::{AuditEntity, AuditEntry};
+
+/// Which audit entries to list. Unset criteria match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditFilter {
+    pub entity: Option<AuditEntity>,
+    pub entity_id: Option<String>,
+}
+
+/// One page of audit entries, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// 1-based page number.
+    pub page: u32,
+    pub per_page: u32,
+    /// Number of entries matching the filter, across all pages.
+    pub total: u64,
+}
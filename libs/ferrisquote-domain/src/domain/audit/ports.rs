@@ -0,0 +1,41 @@
+use std::future::Future;
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::entities::{
+    entry::AuditEntry,
+    query::{AuditFilter, AuditPage},
+};
+
+/// Append-only storage for the audit log.
+///
+/// Entries are never updated nor deleted.
+pub trait AuditRepository: Send + Sync {
+    fn append_entry(
+        &self,
+        entry: AuditEntry,
+    ) -> impl Future<Output = Result<AuditEntry, DomainError>> + Send;
+
+    /// Entries of `organization` matching `filter`, newest first, together
+    /// with the number of matching entries.
+    fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: &AuditFilter,
+        offset: u64,
+        limit: u64,
+    ) -> impl Future<Output = Result<(Vec<AuditEntry>, u64), DomainError>> + Send;
+}
+
+/// Service trait for reading the audit log.
+///
+/// Entries are written by the other services as they change entities.
+pub trait AuditService: Send + Sync {
+    fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: AuditFilter,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<AuditPage, DomainError>> + Send;
+}
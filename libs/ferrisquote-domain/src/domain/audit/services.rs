@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::{
+    entities::{
+        actor::Actor,
+        entry::{AuditEntity, AuditEntry},
+        query::{AuditFilter, AuditPage},
+    },
+    ports::{AuditRepository, AuditService},
+};
+
+const MAX_PER_PAGE: u32 = 100;
+
+#[derive(Clone)]
+pub struct AuditServiceImpl<AR> {
+    repo: AR,
+}
+
+impl<AR> AuditServiceImpl<AR> {
+    pub fn new(repo: AR) -> Self {
+        Self { repo }
+    }
+}
+
+impl<AR> AuditService for AuditServiceImpl<AR>
+where
+    AR: AuditRepository + Send + Sync,
+{
+    async fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: AuditFilter,
+        page: u32,
+        per_page: u32,
+    ) -> Result<AuditPage, DomainError> {
+        if page == 0 {
+            return Err(DomainError::validation("Page numbers start at 1"));
+        }
+        if per_page == 0 || per_page > MAX_PER_PAGE {
+            return Err(DomainError::validation(format!(
+                "Page size must be between 1 and {MAX_PER_PAGE}"
+            )));
+        }
+
+        let offset = u64::from(page - 1) * u64::from(per_page);
+        let (entries, total) = self
+            .repo
+            .list_entries(organization, &filter, offset, u64::from(per_page))
+            .await?;
+
+        Ok(AuditPage {
+            entries,
+            page,
+            per_page,
+            total,
+        })
+    }
+}
+
+/// Append the change of an entity from `before` to `after` to the audit log.
+///
+/// Updates that leave the entity untouched are not recorded. Services pass the
+/// audit repository bound to the transaction of the change, so the entry is
+/// committed with the change or not at all.
+pub(crate) async fn record<AR, T>(
+    repo: &AR,
+    organization: &OrganizationId,
+    actor: &Actor,
+    entity: AuditEntity,
+    entity_id: impl ToString,
+    before: Option<&T>,
+    after: Option<&T>,
+) -> Result<(), DomainError>
+where
+    AR: AuditRepository,
+    T: Serialize,
+{
+    let snapshot = |value: &T| {
+        serde_json::to_value(value).map_err(|e| {
+            DomainError::internal(format!("Failed to snapshot {entity} for audit: {e}"))
+        })
+    };
+
+    let entry = AuditEntry::new(
+        organization.clone(),
+        actor.clone(),
+        entity,
+        entity_id.to_string(),
+        before.map(snapshot).transpose()?,
+        after.map(snapshot).transpose()?,
+    );
+    if entry.is_noop() {
+        return Ok(());
+    }
+
+    repo.append_entry(entry).await?;
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod permission;
+pub mod policy;
+pub mod principal;
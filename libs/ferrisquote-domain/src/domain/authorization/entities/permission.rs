@@ -0,0 +1,85 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+/// An action on a kind of resource, written `resource:action` (e.g. `flow:write`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Permission {
+    #[serde(rename = "flow:read")]
+    FlowRead,
+    #[serde(rename = "flow:write")]
+    FlowWrite,
+    #[serde(rename = "estimator:read")]
+    EstimatorRead,
+    #[serde(rename = "estimator:write")]
+    EstimatorWrite,
+    #[serde(rename = "estimator:evaluate")]
+    EstimatorEvaluate,
+    #[serde(rename = "quote:read")]
+    QuoteRead,
+    #[serde(rename = "quote:write")]
+    QuoteWrite,
+    #[serde(rename = "quote:approve")]
+    QuoteApprove,
+    #[serde(rename = "customer:read")]
+    CustomerRead,
+    #[serde(rename = "customer:write")]
+    CustomerWrite,
+    #[serde(rename = "api_key:manage")]
+    ApiKeyManage,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 12] = [
+        Permission::FlowRead,
+        Permission::FlowWrite,
+        Permission::EstimatorRead,
+        Permission::EstimatorWrite,
+        Permission::EstimatorEvaluate,
+        Permission::QuoteRead,
+        Permission::QuoteWrite,
+        Permission::QuoteApprove,
+        Permission::CustomerRead,
+        Permission::CustomerWrite,
+        Permission::ApiKeyManage,
+        Permission::AuditRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::FlowRead => "flow:read",
+            Permission::FlowWrite => "flow:write",
+            Permission::EstimatorRead => "estimator:read",
+            Permission::EstimatorWrite => "estimator:write",
+            Permission::EstimatorEvaluate => "estimator:evaluate",
+            Permission::QuoteRead => "quote:read",
+            Permission::QuoteWrite => "quote:write",
+            Permission::QuoteApprove => "quote:approve",
+            Permission::CustomerRead => "customer:read",
+            Permission::CustomerWrite => "customer:write",
+            Permission::ApiKeyManage => "api_key:manage",
+            Permission::AuditRead => "audit:read",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|p| p.as_str() == s)
+            .ok_or_else(|| DomainError::validation(format!("Unknown permission: {s}")))
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::domain::error::DomainError;
+
+use super::{
+    permission::Permission,
+    principal::{Principal, PrincipalKind},
+};
+
+pub const ROLE_VIEWER: &str = "viewer";
+pub const ROLE_EDITOR: &str = "editor";
+pub const ROLE_APPROVER: &str = "approver";
+pub const ROLE_ADMIN: &str = "admin";
+
+const READ_PERMISSIONS: [Permission; 4] = [
+    Permission::FlowRead,
+    Permission::EstimatorRead,
+    Permission::QuoteRead,
+    Permission::CustomerRead,
+];
+
+/// Maps roles to permissions.
+///
+/// Users get the permissions of their roles. Service clients additionally get the
+/// permissions named by their scopes (e.g. a `quote:read` scope). Anonymous callers get
+/// nothing: the public runner does not go through this policy.
+#[derive(Debug, Clone, Default)]
+pub struct RolePolicy {
+    grants: HashMap<String, BTreeSet<Permission>>,
+}
+
+impl RolePolicy {
+    /// The built-in roles:
+    ///
+    /// - `viewer` reads everything
+    /// - `editor` also edits flows, estimators, quotes and customers, and evaluates estimators
+    /// - `approver` reads everything and accepts or rejects quotes
+    /// - `admin` has every permission
+    pub fn standard() -> Self {
+        Self::default()
+            .grant(ROLE_VIEWER, READ_PERMISSIONS)
+            .grant(ROLE_EDITOR, READ_PERMISSIONS)
+            .grant(
+                ROLE_EDITOR,
+                [
+                    Permission::FlowWrite,
+                    Permission::EstimatorWrite,
+                    Permission::EstimatorEvaluate,
+                    Permission::QuoteWrite,
+                    Permission::CustomerWrite,
+                ],
+            )
+            .grant(ROLE_APPROVER, READ_PERMISSIONS)
+            .grant(ROLE_APPROVER, [Permission::QuoteApprove])
+            .grant(ROLE_ADMIN, Permission::ALL)
+    }
+
+    /// Add `permissions` to `role`.
+    pub fn grant(
+        mut self,
+        role: impl Into<String>,
+        permissions: impl IntoIterator<Item = Permission>,
+    ) -> Self {
+        self.grants
+            .entry(role.into())
+            .or_default()
+            .extend(permissions);
+        self
+    }
+
+    pub fn permissions(&self, principal: &Principal) -> BTreeSet<Permission> {
+        let mut permissions = BTreeSet::new();
+
+        if principal.kind == PrincipalKind::Anonymous {
+            return permissions;
+        }
+
+        for role in &principal.roles {
+            if let Some(granted) = self.grants.get(role) {
+                permissions.extend(granted);
+            }
+        }
+
+        if principal.kind == PrincipalKind::Client {
+            permissions.extend(
+                principal
+                    .scopes
+                    .iter()
+                    .filter_map(|s| s.parse::<Permission>().ok()),
+            );
+        }
+
+        permissions
+    }
+
+    pub fn allows(&self, principal: &Principal, permission: Permission) -> bool {
+        self.permissions(principal).contains(&permission)
+    }
+
+    /// Fail with `DomainError::Forbidden` unless `principal` holds `permission`.
+    pub fn authorize(
+        &self,
+        principal: &Principal,
+        permission: Permission,
+    ) -> Result<(), DomainError> {
+        if self.allows(principal, permission) {
+            Ok(())
+        } else {
+            Err(DomainError::forbidden(format!(
+                "Missing permission {permission}"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(roles: &[&str]) -> Principal {
+        Principal {
+            kind: PrincipalKind::User,
+            id: Some("user-1".to_string()),
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+            scopes: vec!["openid".to_string(), "flow:write".to_string()],
+        }
+    }
+
+    fn client(roles: &[&str], scopes: &[&str]) -> Principal {
+        Principal {
+            kind: PrincipalKind::Client,
+            id: Some("client-1".to_string()),
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_permission_matrix() {
+        use Permission::*;
+
+        let policy = RolePolicy::standard();
+        let cases: Vec<(&str, Principal, Vec<Permission>)> = vec![
+            ("user without roles", user(&[]), vec![]),
+            (
+                "viewer",
+                user(&[ROLE_VIEWER]),
+                vec![FlowRead, EstimatorRead, QuoteRead, CustomerRead],
+            ),
+            (
+                "editor",
+                user(&[ROLE_EDITOR]),
+                vec![
+                    FlowRead,
+                    FlowWrite,
+                    EstimatorRead,
+                    EstimatorWrite,
+                    EstimatorEvaluate,
+                    QuoteRead,
+                    QuoteWrite,
+                    CustomerRead,
+                    CustomerWrite,
+                ],
+            ),
+            (
+                "approver",
+                user(&[ROLE_APPROVER]),
+                vec![
+                    FlowRead,
+                    EstimatorRead,
+                    QuoteRead,
+                    QuoteApprove,
+                    CustomerRead,
+                ],
+            ),
+            ("admin", user(&[ROLE_ADMIN]), Permission::ALL.to_vec()),
+            ("user with unknown role", user(&["moderator"]), vec![]),
+            (
+                "client with permission scopes",
+                client(&[], &["openid", "estimator:evaluate", "flow:read"]),
+                vec![FlowRead, EstimatorEvaluate],
+            ),
+            (
+                "client with role and scope",
+                client(&[ROLE_VIEWER], &["quote:write"]),
+                vec![FlowRead, EstimatorRead, QuoteRead, QuoteWrite, CustomerRead],
+            ),
+            ("anonymous", Principal::anonymous(), vec![]),
+        ];
+
+        for (name, principal, expected) in cases {
+            for permission in Permission::ALL {
+                assert_eq!(
+                    policy.allows(&principal, permission),
+                    expected.contains(&permission),
+                    "{name}: {permission}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_scopes_do_not_grant_permissions() {
+        let policy = RolePolicy::standard();
+
+        assert!(!policy.allows(&user(&[]), Permission::FlowWrite));
+    }
+
+    #[test]
+    fn test_authorize_returns_forbidden() {
+        let policy = RolePolicy::standard();
+
+        assert!(
+            policy
+                .authorize(&user(&[ROLE_VIEWER]), Permission::FlowRead)
+                .is_ok()
+        );
+
+        let err = policy
+            .authorize(&user(&[ROLE_VIEWER]), Permission::QuoteApprove)
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Forbidden { .. }));
+        assert!(err.to_string().contains("quote:approve"));
+    }
+
+    #[test]
+    fn test_grant_extends_roles() {
+        let policy = RolePolicy::standard().grant("sales", [Permission::QuoteWrite]);
+
+        assert!(policy.allows(&user(&["sales"]), Permission::QuoteWrite));
+        assert!(!policy.allows(&user(&["sales"]), Permission::QuoteRead));
+    }
+
+    #[test]
+    fn test_permission_round_trips_through_str() {
+        for permission in Permission::ALL {
+            assert_eq!(
+                permission.as_str().parse::<Permission>().unwrap(),
+                permission
+            );
+        }
+        assert!("flow:delete".parse::<Permission>().is_err());
+    }
+}
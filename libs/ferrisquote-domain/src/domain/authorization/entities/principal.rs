@@ -0,0 +1,28 @@
+/// Kind of caller behind a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrincipalKind {
+    User,
+    /// A service account authenticating with its own credentials.
+    Client,
+    Anonymous,
+}
+
+/// The caller as seen by authorization: who it is and what the identity provider granted it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub kind: PrincipalKind,
+    pub id: Option<String>,
+    pub roles: Vec<String>,
+    pub scopes: Vec<String>,
+}
+
+impl Principal {
+    pub fn anonymous() -> Self {
+        Self {
+            kind: PrincipalKind::Anonymous,
+            id: None,
+            roles: vec![],
+            scopes: vec![],
+        }
+    }
+}
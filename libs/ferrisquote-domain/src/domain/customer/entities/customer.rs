@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::ids::CustomerId;
+
+/// Prefix under which customer attributes are exposed to estimator expressions.
+pub const CUSTOMER_VARIABLE_PREFIX: &str = "customer_";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub line1: String,
+    pub line2: Option<String>,
+    pub postal_code: String,
+    pub city: String,
+    pub region: Option<String>,
+    pub country: String,
+}
+
+/// Editable information about a customer, used to create or replace one.
+#[derive(Debug, Clone, Default)]
+pub struct CustomerDetails {
+    pub company_name: Option<String>,
+    pub contact_name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub billing_address: Option<Address>,
+    pub vat_number: Option<String>,
+    /// Negotiated discount as a fraction (`0.1` = 10 %).
+    pub discount_rate: f64,
+    /// Additional numeric attributes available to estimator expressions.
+    pub attributes: HashMap<String, f64>,
+}
+
+impl CustomerDetails {
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if !(0.0..=1.0).contains(&self.discount_rate) {
+            return Err(DomainError::validation(
+                "Discount rate must be between 0 and 1",
+            ));
+        }
+
+        for key in self.attributes.keys() {
+            let valid = !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !key.starts_with(|c: char| c.is_ascii_digit());
+            if !valid {
+                return Err(DomainError::validation(format!(
+                    "Customer attribute '{key}' must be an identifier (letters, digits, '_')"
+                )));
+            }
+            if key == "discount_rate" {
+                return Err(DomainError::validation(
+                    "Customer attribute 'discount_rate' is reserved",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The person or company a quote is prepared for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Customer {
+    pub id: CustomerId,
+    pub organization_id: OrganizationId,
+    pub company_name: Option<String>,
+    pub contact_name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub billing_address: Option<Address>,
+    pub vat_number: Option<String>,
+    pub discount_rate: f64,
+    pub attributes: HashMap<String, f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Customer {
+    pub fn new(organization_id: OrganizationId, details: CustomerDetails) -> Self {
+        let now = Utc::now();
+        let mut customer = Self {
+            id: CustomerId::new(),
+            organization_id,
+            company_name: None,
+            contact_name: String::new(),
+            email: String::new(),
+            phone: None,
+            billing_address: None,
+            vat_number: None,
+            discount_rate: 0.0,
+            attributes: HashMap::new(),
+            created_at: now,
+            updated_at: now,
+        };
+        customer.update(details, now);
+        customer
+    }
+
+    /// Replace every editable field with `details`.
+    pub fn update(&mut self, details: CustomerDetails, at: DateTime<Utc>) {
+        self.company_name = details.company_name;
+        self.contact_name = details.contact_name;
+        self.email = details.email;
+        self.phone = details.phone;
+        self.billing_address = details.billing_address;
+        self.vat_number = details.vat_number;
+        self.discount_rate = details.discount_rate;
+        self.attributes = details.attributes;
+        self.updated_at = at;
+    }
+
+    /// Variables exposed to estimator expressions, e.g. `@customer_discount_rate`
+    /// or `@customer_<attribute>`.
+    pub fn expression_variables(&self) -> HashMap<String, f64> {
+        let mut vars: HashMap<String, f64> = self
+            .attributes
+            .iter()
+            .map(|(key, &value)| (format!("{CUSTOMER_VARIABLE_PREFIX}{key}"), value))
+            .collect();
+        vars.insert(
+            format!("{CUSTOMER_VARIABLE_PREFIX}discount_rate"),
+            self.discount_rate,
+        );
+        vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details() -> CustomerDetails {
+        CustomerDetails {
+            contact_name: "Jane Doe".into(),
+            email: "jane@example.com".into(),
+            discount_rate: 0.1,
+            attributes: HashMap::from([("hourly_rate".to_string(), 55.0)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_expression_variables_are_prefixed() {
+        let customer = Customer::new(OrganizationId::new("acme"), details());
+        let vars = customer.expression_variables();
+
+        assert_eq!(vars["customer_discount_rate"], 0.1);
+        assert_eq!(vars["customer_hourly_rate"], 55.0);
+        assert_eq!(vars.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_discount() {
+        let mut d = details();
+        d.discount_rate = 1.5;
+        assert!(matches!(
+            d.validate(),
+            Err(DomainError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_non_identifier_attributes() {
+        for key in ["", "hourly rate", "1st", "discount_rate"] {
+            let mut d = details();
+            d.attributes = HashMap::from([(key.to_string(), 1.0)]);
+            assert!(d.validate().is_err(), "{key:?} should be rejected");
+        }
+        assert!(details().validate().is_ok());
+    }
+}
@@ -0,0 +1,79 @@
+use std::future::Future;
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::entities::{
+    customer::{Customer, CustomerDetails},
+    ids::CustomerId,
+};
+
+/// Repository trait for Customer persistence.
+///
+/// Customers of another organization must be reported as
+/// `DomainError::NotFound`, as if they did not exist.
+pub trait CustomerRepository: Send + Sync {
+    /// Create `customer` in `customer.organization_id`.
+    fn create_customer(
+        &self,
+        customer: Customer,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn get_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn list_customers(
+        &self,
+        organization: &OrganizationId,
+    ) -> impl Future<Output = Result<Vec<Customer>, DomainError>> + Send;
+
+    fn update_customer(
+        &self,
+        organization: &OrganizationId,
+        customer: Customer,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn delete_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+}
+
+/// Service trait for Customer domain logic.
+///
+/// Every operation acts on behalf of `organization` and only sees its customers.
+pub trait CustomerService: Send + Sync {
+    fn create_customer(
+        &self,
+        organization: &OrganizationId,
+        details: CustomerDetails,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn get_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn list_customers(
+        &self,
+        organization: &OrganizationId,
+    ) -> impl Future<Output = Result<Vec<Customer>, DomainError>> + Send;
+
+    /// Replace the editable details of a customer.
+    fn update_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+        details: CustomerDetails,
+    ) -> impl Future<Output = Result<Customer, DomainError>> + Send;
+
+    fn delete_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+}
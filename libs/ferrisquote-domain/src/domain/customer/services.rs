@@ -0,0 +1,73 @@
+use chrono::Utc;
+
+use crate::domain::{error::DomainError, organization::entities::ids::OrganizationId};
+
+use super::{
+    entities::{
+        customer::{Customer, CustomerDetails},
+        ids::CustomerId,
+    },
+    ports::{CustomerRepository, CustomerService},
+};
+
+#[derive(Clone)]
+pub struct CustomerServiceImpl<CR> {
+    repo: CR,
+}
+
+impl<CR> CustomerServiceImpl<CR> {
+    pub fn new(repo: CR) -> Self {
+        Self { repo }
+    }
+}
+
+impl<CR> CustomerService for CustomerServiceImpl<CR>
+where
+    CR: CustomerRepository + Send + Sync,
+{
+    async fn create_customer(
+        &self,
+        organization: &OrganizationId,
+        details: CustomerDetails,
+    ) -> Result<Customer, DomainError> {
+        details.validate()?;
+        self.repo
+            .create_customer(Customer::new(organization.clone(), details))
+            .await
+    }
+
+    async fn get_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<Customer, DomainError> {
+        self.repo.get_customer(organization, id).await
+    }
+
+    async fn list_customers(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<Customer>, DomainError> {
+        self.repo.list_customers(organization).await
+    }
+
+    async fn update_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+        details: CustomerDetails,
+    ) -> Result<Customer, DomainError> {
+        details.validate()?;
+        let mut customer = self.repo.get_customer(organization, id).await?;
+        customer.update(details, Utc::now());
+        self.repo.update_customer(organization, customer).await
+    }
+
+    async fn delete_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<(), DomainError> {
+        self.repo.delete_customer(organization, id).await
+    }
+}
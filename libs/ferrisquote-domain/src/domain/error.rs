@@ -11,6 +11,13 @@ pub enum DomainError {
     #[error("conflict: {message}")]
     Conflict { message: String },
 
+    #[error("invalid transition: {entity} cannot go from {from} to {to}")]
+    InvalidTransition {
+        entity: String,
+        from: String,
+        to: String,
+    },
+
     #[error("repository error: {message}")]
     RepositoryError { message: String },
 
@@ -44,6 +51,30 @@ impl DomainError {
         }
     }
 
+    /// A write made against version `expected` of an entity now at `current`.
+    pub fn stale_version(
+        entity: impl std::fmt::Display,
+        id: impl std::fmt::Display,
+        expected: u32,
+        current: u32,
+    ) -> Self {
+        Self::conflict(format!(
+            "{entity} {id} is at version {current}, not {expected}"
+        ))
+    }
+
+    pub fn invalid_transition(
+        entity: impl Into<String>,
+        from: impl Into<String>,
+        to: impl Into<String>,
+    ) -> Self {
+        Self::InvalidTransition {
+            entity: entity.into(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
     pub fn repository(message: impl Into<String>) -> Self {
         Self::RepositoryError {
             message: message.into(),
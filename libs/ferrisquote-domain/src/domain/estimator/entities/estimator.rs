@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::domain::flows::entities::ids::FlowId;
+use crate::domain::{flows::entities::ids::FlowId, organization::entities::ids::OrganizationId};
 
 use super::{ids::EstimatorId, variable::EstimatorVariable};
 
@@ -12,41 +14,58 @@ use super::{ids::EstimatorId, variable::EstimatorVariable};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Estimator {
     pub id: EstimatorId,
+    pub organization_id: OrganizationId,
     pub flow_id: FlowId,
     pub name: String,
     pub variables: Vec<EstimatorVariable>,
+    /// Optimistic concurrency version of the estimator metadata. Left out of
+    /// serialized snapshots so audit diffs only show content changes.
+    #[serde(skip)]
+    pub version: u32,
 }
 
 impl Estimator {
-    pub fn new(flow_id: FlowId, name: String) -> Self {
+    pub fn new(organization_id: OrganizationId, flow_id: FlowId, name: String) -> Self {
         Self {
             id: EstimatorId::new(),
+            organization_id,
             flow_id,
             name,
             variables: Vec::new(),
+            version: 1,
         }
     }
 
-    pub fn with_id(id: EstimatorId, flow_id: FlowId, name: String) -> Self {
+    pub fn with_id(
+        id: EstimatorId,
+        organization_id: OrganizationId,
+        flow_id: FlowId,
+        name: String,
+    ) -> Self {
         Self {
             id,
+            organization_id,
             flow_id,
             name,
             variables: Vec::new(),
+            version: 1,
         }
     }
 
     pub fn with_variables(
         id: EstimatorId,
+        organization_id: OrganizationId,
         flow_id: FlowId,
         name: String,
         variables: Vec<EstimatorVariable>,
     ) -> Self {
         Self {
             id,
+            organization_id,
             flow_id,
             name,
             variables,
+            version: 1,
         }
     }
 
@@ -64,4 +83,15 @@ impl Estimator {
     pub fn get_variable(&self, id: &super::ids::EstimatorVariableId) -> Option<&EstimatorVariable> {
         self.variables.iter().find(|v| &v.id == id)
     }
+
+    /// The total of an evaluation: the value of the last variable by rank,
+    /// which by convention is the final figure the other variables build up to.
+    pub fn total(&self, results: &HashMap<String, f64>) -> f64 {
+        self.variables
+            .iter()
+            .max_by(|a, b| a.rank.cmp(&b.rank))
+            .and_then(|v| results.get(&v.name))
+            .copied()
+            .unwrap_or(0.0)
+    }
 }
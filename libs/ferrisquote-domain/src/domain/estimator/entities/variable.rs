@@ -18,15 +18,22 @@ pub struct EstimatorVariable {
     /// The mathematical expression to evaluate.
     pub expression: String,
     pub description: String,
+    /// LexoRank string used to order variables within their estimator.
+    pub rank: String,
+    /// Bumped whenever the variable changes. Not serialized, like `Estimator::version`.
+    #[serde(skip)]
+    pub version: u32,
 }
 
 impl EstimatorVariable {
-    pub fn new(name: String, expression: String, description: String) -> Self {
+    pub fn new(name: String, expression: String, description: String, rank: String) -> Self {
         Self {
             id: EstimatorVariableId::new(),
             name,
             expression,
             description,
+            rank,
+            version: 1,
         }
     }
 
@@ -35,12 +42,15 @@ impl EstimatorVariable {
         name: String,
         expression: String,
         description: String,
+        rank: String,
     ) -> Self {
         Self {
             id,
             name,
             expression,
             description,
+            rank,
+            version: 1,
         }
     }
 }
@@ -1,6 +1,15 @@
 use std::{collections::HashMap, future::Future};
 
-use crate::domain::{error::DomainError, flows::entities::ids::FlowId};
+use crate::domain::{
+    audit::entities::actor::Actor,
+    customer::entities::ids::CustomerId,
+    error::DomainError,
+    flows::entities::{
+        ids::FlowId,
+        query::{ListQuery, Page},
+    },
+    organization::entities::ids::OrganizationId,
+};
 
 use super::entities::{
     estimator::Estimator,
@@ -10,7 +19,18 @@ use super::entities::{
 };
 
 /// Repository trait for Estimator persistence.
+///
+/// Estimators and variables of another organization must be reported as
+/// `DomainError::NotFound`, exactly like missing ones.
+///
+/// Writes to an existing estimator or variable increment its version. When
+/// given a `version`, they must first check it against the stored one and
+/// fail with `DomainError::Conflict`, writing nothing, if it differs.
 pub trait EstimatorRepository: Send + Sync {
+    /// Create `estimator` in `estimator.organization_id`.
+    ///
+    /// Must fail with `DomainError::NotFound` when its flow does not belong to that
+    /// organization.
     fn create_estimator(
         &self,
         estimator: Estimator,
@@ -18,110 +38,189 @@ pub trait EstimatorRepository: Send + Sync {
 
     fn get_estimator(
         &self,
+        organization: &OrganizationId,
         id: EstimatorId,
     ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
 
+    /// Estimators of a flow matching `query`, with their variables.
+    ///
+    /// Returns at most `query.limit` estimators following the `query.after`
+    /// cursor, like `FlowRepository::list_flows`.
     fn list_estimators_for_flow(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
+        query: &ListQuery<EstimatorId>,
     ) -> impl Future<Output = Result<Vec<Estimator>, DomainError>> + Send;
 
+    /// Retrieve the estimator owning the given variable, with all its variables.
+    fn get_estimator_for_variable(
+        &self,
+        organization: &OrganizationId,
+        variable_id: EstimatorVariableId,
+    ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
+
     /// Partial update: only fields set to `Some(...)` are written.
     fn update_estimator(
         &self,
+        organization: &OrganizationId,
         id: EstimatorId,
         name: Option<String>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
 
     fn delete_estimator(
         &self,
+        organization: &OrganizationId,
         id: EstimatorId,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<(), DomainError>> + Send;
 
     fn add_variable(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         variable: EstimatorVariable,
     ) -> impl Future<Output = Result<EstimatorVariable, DomainError>> + Send;
 
     /// Partial update: only fields set to `Some(...)` are written.
+    #[allow(clippy::too_many_arguments)]
     fn update_variable(
         &self,
+        organization: &OrganizationId,
         id: EstimatorVariableId,
         name: Option<String>,
         expression: Option<String>,
         description: Option<String>,
+        rank: Option<String>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<EstimatorVariable, DomainError>> + Send;
 
+    /// Set the rank of each variable in `ranks`, all or none of them, leaving
+    /// their versions alone.
+    fn update_variable_ranks(
+        &self,
+        organization: &OrganizationId,
+        ranks: &[(EstimatorVariableId, String)],
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+
     fn remove_variable(
         &self,
+        organization: &OrganizationId,
         id: EstimatorVariableId,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<(), DomainError>> + Send;
 }
 
 /// Service trait for Estimator domain logic.
+///
+/// Every operation acts on behalf of `organization` and only sees its estimators.
+/// Changes to estimators and their variables are recorded under `actor` in the
+/// audit log. Those taking a `version` fail with `DomainError::Conflict` when
+/// the estimator or variable is no longer at that version; `None` skips the
+/// check.
 pub trait EstimatorService: Send + Sync {
     // --- CRUD ---
 
     fn create_estimator(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         flow_id: FlowId,
         name: String,
     ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
 
     fn get_estimator(
         &self,
+        organization: &OrganizationId,
         id: EstimatorId,
     ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
 
     fn list_estimators_for_flow(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
-    ) -> impl Future<Output = Result<Vec<Estimator>, DomainError>> + Send;
+        query: ListQuery<EstimatorId>,
+    ) -> impl Future<Output = Result<Page<Estimator, EstimatorId>, DomainError>> + Send;
 
     fn update_estimator(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorId,
         name: Option<String>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
 
     fn delete_estimator(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorId,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<(), DomainError>> + Send;
 
     fn add_variable(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         estimator_id: EstimatorId,
         name: String,
         expression: String,
         description: String,
     ) -> impl Future<Output = Result<EstimatorVariable, DomainError>> + Send;
 
+    #[allow(clippy::too_many_arguments)]
     fn update_variable(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorVariableId,
         name: Option<String>,
         expression: Option<String>,
         description: Option<String>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<EstimatorVariable, DomainError>> + Send;
 
     fn remove_variable(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorVariableId,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<(), DomainError>> + Send;
 
+    /// Reorder a variable within its estimator.
+    ///
+    /// Neighbours must be other variables of the same estimator, `after_id`
+    /// coming first, or the call fails with `DomainError::ValidationError`.
+    /// Without a neighbour, the variable goes to the end of the estimator.
+    fn reorder_variable(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: EstimatorVariableId,
+        after_id: Option<EstimatorVariableId>,
+        before_id: Option<EstimatorVariableId>,
+        version: Option<u32>,
+    ) -> impl Future<Output = Result<Estimator, DomainError>> + Send;
+
     // --- Evaluation ---
 
     fn evaluate(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         field_values: HashMap<String, f64>,
     ) -> impl Future<Output = Result<HashMap<String, f64>, DomainError>> + Send;
 
+    /// Evaluate a submission, optionally on behalf of a customer whose
+    /// attributes are then available as `@customer_*` variables.
     fn evaluate_submission(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         data: SubmissionData,
+        customer_id: Option<CustomerId>,
     ) -> impl Future<Output = Result<HashMap<String, f64>, DomainError>> + Send;
 }
@@ -1,6 +1,27 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::domain::{error::DomainError, flows::entities::ids::FlowId};
+use crate::domain::{
+    audit::{
+        entities::{actor::Actor, entry::AuditEntity},
+        ports::AuditRepository,
+        services::record,
+    },
+    customer::{
+        entities::{customer::Customer, ids::CustomerId},
+        ports::CustomerRepository,
+    },
+    error::DomainError,
+    flows::entities::{
+        ids::FlowId,
+        query::{ListQuery, Page},
+    },
+    organization::entities::ids::OrganizationId,
+    rank::{
+        ports::RankService,
+        services::{check_neighbours, rank_between, spread_ranks},
+    },
+    transaction::ports::{Transaction, Transactional, UnitOfWork},
+};
 
 use super::{
     entities::{
@@ -13,93 +34,451 @@ use super::{
 };
 
 #[derive(Clone)]
-pub struct EstimatorServiceImpl<ER> {
+pub struct EstimatorServiceImpl<ER, RS, CR, AR, UW> {
     repo: ER,
+    rank_service: RS,
+    customer_repo: CR,
+    audit_repo: AR,
+    unit_of_work: UW,
+}
+
+impl<ER, RS, CR, AR, UW> EstimatorServiceImpl<ER, RS, CR, AR, UW> {
+    pub fn new(
+        repo: ER,
+        rank_service: RS,
+        customer_repo: CR,
+        audit_repo: AR,
+        unit_of_work: UW,
+    ) -> Self {
+        Self {
+            repo,
+            rank_service,
+            customer_repo,
+            audit_repo,
+            unit_of_work,
+        }
+    }
+}
+
+/// The repositories of an `EstimatorServiceImpl` that write, bound to one transaction.
+struct Repositories<ER, AR> {
+    estimators: ER,
+    audit: AR,
+}
+
+impl<ER, RS, CR, AR, UW> EstimatorServiceImpl<ER, RS, CR, AR, UW>
+where
+    ER: Transactional<UW::Transaction>,
+    AR: Transactional<UW::Transaction>,
+    UW: UnitOfWork,
+{
+    /// Open a transaction and the repositories writing through it.
+    ///
+    /// A change and its audit entry are committed together, or not at all.
+    async fn begin(&self) -> Result<(UW::Transaction, Repositories<ER, AR>), DomainError> {
+        let transaction = self.unit_of_work.begin().await?;
+        let repositories = Repositories {
+            estimators: self.repo.in_transaction(&transaction),
+            audit: self.audit_repo.in_transaction(&transaction),
+        };
+        Ok((transaction, repositories))
+    }
 }
 
-impl<ER> EstimatorServiceImpl<ER> {
-    pub fn new(repo: ER) -> Self {
-        Self { repo }
+impl<ER, RS, CR, AR, UW> EstimatorServiceImpl<ER, RS, CR, AR, UW>
+where
+    ER: EstimatorRepository + Transactional<UW::Transaction> + Send + Sync,
+    RS: RankService + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
+{
+    /// The rank of a variable of `estimator` placed between `after` and `before`.
+    ///
+    /// When there is no room left between them, the variables of the estimator
+    /// are rebalanced first.
+    async fn variable_rank(
+        &self,
+        repos: &Repositories<ER, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        estimator: &Estimator,
+        after: Option<&EstimatorVariable>,
+        before: Option<&EstimatorVariable>,
+    ) -> Result<String, DomainError> {
+        let (after_rank, before_rank) = (
+            after.map(|v| v.rank.as_str()),
+            before.map(|v| v.rank.as_str()),
+        );
+        if let Ok(rank) = rank_between(&self.rank_service, after_rank, before_rank) {
+            return Ok(rank);
+        }
+        let ranks = self
+            .rebalance_variables(repos, organization, actor, estimator)
+            .await?;
+        let rank_of = |variable: Option<&EstimatorVariable>| {
+            variable.and_then(|v| ranks.get(&v.id)).map(String::as_str)
+        };
+        rank_between(&self.rank_service, rank_of(after), rank_of(before))
+    }
+
+    /// Rewrite the ranks of the variables of `estimator` to short, evenly spaced
+    /// values in their current order, and return the new rank of each variable.
+    async fn rebalance_variables(
+        &self,
+        repos: &Repositories<ER, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        estimator: &Estimator,
+    ) -> Result<HashMap<EstimatorVariableId, String>, DomainError> {
+        let ids: Vec<EstimatorVariableId> = estimator.variables.iter().map(|v| v.id).collect();
+        let ranks = spread_ranks(&self.rank_service, &ids)?;
+        repos
+            .estimators
+            .update_variable_ranks(organization, &ranks)
+            .await?;
+
+        for (variable, (_, rank)) in estimator.variables.iter().zip(&ranks) {
+            let reranked = EstimatorVariable {
+                rank: rank.clone(),
+                ..variable.clone()
+            };
+            record(
+                &repos.audit,
+                organization,
+                actor,
+                AuditEntity::EstimatorVariable,
+                variable.id,
+                Some(variable),
+                Some(&reranked),
+            )
+            .await?;
+        }
+        Ok(ranks.into_iter().collect())
     }
 }
 
-impl<ER> EstimatorService for EstimatorServiceImpl<ER>
+impl<ER, RS, CR, AR, UW> EstimatorService for EstimatorServiceImpl<ER, RS, CR, AR, UW>
 where
-    ER: EstimatorRepository + Send + Sync,
+    ER: EstimatorRepository + Transactional<UW::Transaction> + Send + Sync,
+    RS: RankService + Send + Sync,
+    CR: CustomerRepository + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
 {
     async fn create_estimator(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         flow_id: FlowId,
         name: String,
     ) -> Result<Estimator, DomainError> {
-        let estimator = Estimator::new(flow_id, name);
-        self.repo.create_estimator(estimator).await
-    }
-
-    async fn get_estimator(&self, id: EstimatorId) -> Result<Estimator, DomainError> {
-        self.repo.get_estimator(id).await
+        let (transaction, repos) = self.begin().await?;
+        let estimator = Estimator::new(organization.clone(), flow_id, name);
+        let estimator = repos.estimators.create_estimator(estimator).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Estimator,
+            estimator.id,
+            None,
+            Some(&estimator),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(estimator)
+    }
+
+    async fn get_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+    ) -> Result<Estimator, DomainError> {
+        self.repo.get_estimator(organization, id).await
     }
 
     async fn list_estimators_for_flow(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
-    ) -> Result<Vec<Estimator>, DomainError> {
-        self.repo.list_estimators_for_flow(flow_id).await
+        query: ListQuery<EstimatorId>,
+    ) -> Result<Page<Estimator, EstimatorId>, DomainError> {
+        query.validate()?;
+        let limit = query.limit;
+        let probe = ListQuery {
+            limit: limit + 1,
+            ..query
+        };
+        let estimators = self
+            .repo
+            .list_estimators_for_flow(organization, flow_id, &probe)
+            .await?;
+
+        Ok(Page::from_items(estimators, limit, |estimator| {
+            probe.cursor(estimator.id, &estimator.name)
+        }))
     }
 
     async fn update_estimator(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorId,
         name: Option<String>,
+        version: Option<u32>,
     ) -> Result<Estimator, DomainError> {
-        self.repo.update_estimator(id, name).await
-    }
-
-    async fn delete_estimator(&self, id: EstimatorId) -> Result<(), DomainError> {
-        self.repo.delete_estimator(id).await
+        let (transaction, repos) = self.begin().await?;
+        let before = repos.estimators.get_estimator(organization, id).await?;
+        let estimator = repos
+            .estimators
+            .update_estimator(organization, id, name, version)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Estimator,
+            id,
+            Some(&before),
+            Some(&estimator),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(estimator)
+    }
+
+    async fn delete_estimator(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: EstimatorId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let before = repos.estimators.get_estimator(organization, id).await?;
+        repos
+            .estimators
+            .delete_estimator(organization, id, version)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Estimator,
+            id,
+            Some(&before),
+            None,
+        )
+        .await?;
+        transaction.commit().await
     }
 
     async fn add_variable(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         estimator_id: EstimatorId,
         name: String,
         expression: String,
         description: String,
     ) -> Result<EstimatorVariable, DomainError> {
-        let variable = EstimatorVariable::new(name, expression, description);
-        self.repo.add_variable(estimator_id, variable).await
+        let (transaction, repos) = self.begin().await?;
+        let estimator = repos
+            .estimators
+            .get_estimator(organization, estimator_id)
+            .await?;
+
+        let next_rank = self
+            .variable_rank(
+                &repos,
+                organization,
+                actor,
+                &estimator,
+                estimator.variables.last(),
+                None,
+            )
+            .await?;
+
+        let variable = EstimatorVariable::new(name, expression, description, next_rank);
+        let variable = repos
+            .estimators
+            .add_variable(organization, estimator_id, variable)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::EstimatorVariable,
+            variable.id,
+            None,
+            Some(&variable),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(variable)
     }
 
     async fn update_variable(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: EstimatorVariableId,
         name: Option<String>,
         expression: Option<String>,
         description: Option<String>,
+        version: Option<u32>,
     ) -> Result<EstimatorVariable, DomainError> {
-        self.repo.update_variable(id, name, expression, description).await
-    }
+        let (transaction, repos) = self.begin().await?;
+        let estimator = repos
+            .estimators
+            .get_estimator_for_variable(organization, id)
+            .await?;
+        let variable = repos
+            .estimators
+            .update_variable(
+                organization,
+                id,
+                name,
+                expression,
+                description,
+                None,
+                version,
+            )
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::EstimatorVariable,
+            id,
+            estimator.get_variable(&id),
+            Some(&variable),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(variable)
+    }
+
+    async fn remove_variable(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: EstimatorVariableId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let estimator = repos
+            .estimators
+            .get_estimator_for_variable(organization, id)
+            .await?;
+        repos
+            .estimators
+            .remove_variable(organization, id, version)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::EstimatorVariable,
+            id,
+            estimator.get_variable(&id),
+            None,
+        )
+        .await?;
+        transaction.commit().await
+    }
+
+    async fn reorder_variable(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: EstimatorVariableId,
+        after_id: Option<EstimatorVariableId>,
+        before_id: Option<EstimatorVariableId>,
+        version: Option<u32>,
+    ) -> Result<Estimator, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let estimator = repos
+            .estimators
+            .get_estimator_for_variable(organization, id)
+            .await?;
+
+        let siblings: Vec<EstimatorVariableId> = estimator.variables.iter().map(|v| v.id).collect();
+        let parent = format!("estimator {}", estimator.id);
+        check_neighbours(
+            "EstimatorVariable",
+            id,
+            &siblings,
+            &parent,
+            after_id,
+            before_id,
+        )?;
+        let after = after_id.and_then(|id| estimator.get_variable(&id));
+        let before_variable = before_id.and_then(|id| estimator.get_variable(&id));
+
+        // Without a neighbour, the variable goes to the end of the estimator.
+        let after = match (after, before_variable) {
+            (None, None) => estimator.variables.iter().rev().find(|v| v.id != id),
+            _ => after,
+        };
 
-    async fn remove_variable(&self, id: EstimatorVariableId) -> Result<(), DomainError> {
-        self.repo.remove_variable(id).await
+        let new_rank = self
+            .variable_rank(
+                &repos,
+                organization,
+                actor,
+                &estimator,
+                after,
+                before_variable,
+            )
+            .await?;
+
+        let variable = repos
+            .estimators
+            .update_variable(organization, id, None, None, None, Some(new_rank), version)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::EstimatorVariable,
+            id,
+            estimator.get_variable(&id),
+            Some(&variable),
+        )
+        .await?;
+
+        let estimator = repos
+            .estimators
+            .get_estimator(organization, estimator.id)
+            .await?;
+        transaction.commit().await?;
+        Ok(estimator)
     }
 
     async fn evaluate(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         field_values: HashMap<String, f64>,
     ) -> Result<HashMap<String, f64>, DomainError> {
-        let estimator = self.repo.get_estimator(estimator_id).await?;
+        let estimator = self.repo.get_estimator(organization, estimator_id).await?;
         evaluate_estimator(&estimator, &field_values)
     }
 
     async fn evaluate_submission(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         data: SubmissionData,
+        customer_id: Option<CustomerId>,
     ) -> Result<HashMap<String, f64>, DomainError> {
-        let estimator = self.repo.get_estimator(estimator_id).await?;
-        evaluate_estimator_with_submission(&estimator, &data)
+        let estimator = self.repo.get_estimator(organization, estimator_id).await?;
+        let customer = match customer_id {
+            Some(id) => Some(self.customer_repo.get_customer(organization, id).await?),
+            None => None,
+        };
+        evaluate_estimator_for_customer(&estimator, &data, customer.as_ref())
     }
 }
 
@@ -151,6 +530,19 @@ pub fn evaluate_estimator(
 pub fn evaluate_estimator_with_submission(
     estimator: &Estimator,
     data: &SubmissionData,
+) -> Result<HashMap<String, f64>, DomainError> {
+    evaluate_estimator_for_customer(estimator, data, None)
+}
+
+/// Evaluate a submission on behalf of a customer.
+///
+/// The customer's attributes are available to expressions as `@customer_*`
+/// variables (see `Customer::expression_variables`). They are set after the
+/// submitted answers, so a submission cannot override them.
+pub fn evaluate_estimator_for_customer(
+    estimator: &Estimator,
+    data: &SubmissionData,
+    customer: Option<&Customer>,
 ) -> Result<HashMap<String, f64>, DomainError> {
     let order = topological_sort(&estimator.variables)?;
 
@@ -162,6 +554,13 @@ pub fn evaluate_estimator_with_submission(
             .map_err(|e| DomainError::internal(e.to_string()))?;
     }
 
+    if let Some(customer) = customer {
+        for (key, value) in customer.expression_variables() {
+            ctx.set_value(key, evalexpr::Value::Float(value))
+                .map_err(|e| DomainError::internal(e.to_string()))?;
+        }
+    }
+
     let var_by_id: HashMap<EstimatorVariableId, &EstimatorVariable> =
         estimator.variables.iter().map(|v| (v.id, v)).collect();
 
@@ -374,14 +773,27 @@ fn topological_sort(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::flows::entities::ids::FlowId;
+    use crate::domain::{
+        flows::entities::ids::FlowId, organization::entities::ids::OrganizationId,
+    };
 
     fn make_var(name: &str, expr: &str) -> EstimatorVariable {
-        EstimatorVariable::new(name.to_string(), expr.to_string(), String::new())
+        EstimatorVariable::new(
+            name.to_string(),
+            expr.to_string(),
+            String::new(),
+            String::new(),
+        )
     }
 
     fn make_estimator(vars: Vec<EstimatorVariable>) -> Estimator {
-        Estimator::with_variables(EstimatorId::new(), FlowId::new(), "test".to_string(), vars)
+        Estimator::with_variables(
+            EstimatorId::new(),
+            OrganizationId::new("acme"),
+            FlowId::new(),
+            "test".to_string(),
+            vars,
+        )
     }
 
     #[test]
@@ -543,4 +955,32 @@ mod tests {
         let result = evaluate_estimator_with_submission(&estimator, &data);
         assert!(matches!(result, Err(DomainError::ValidationError { .. })));
     }
+
+    #[test]
+    fn test_customer_attributes_are_available_to_expressions() {
+        use crate::domain::customer::entities::customer::CustomerDetails;
+
+        let estimator = make_estimator(vec![make_var(
+            "total",
+            "@surface * 10.0 * (1.0 - @customer_discount_rate)",
+        )]);
+        let customer = Customer::new(
+            OrganizationId::new("acme"),
+            CustomerDetails {
+                discount_rate: 0.2,
+                ..Default::default()
+            },
+        );
+        let data = SubmissionData {
+            field_values: HashMap::from([
+                ("surface".to_string(), 50.0),
+                // A submitted answer must not override the negotiated rate.
+                ("customer_discount_rate".to_string(), 0.9),
+            ]),
+            ..Default::default()
+        };
+
+        let result = evaluate_estimator_for_customer(&estimator, &data, Some(&customer)).unwrap();
+        assert!((result["total"] - 400.0).abs() < 1e-9);
+    }
 }
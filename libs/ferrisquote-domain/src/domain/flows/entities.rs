@@ -1,4 +1,6 @@
 pub mod field;
 pub mod flow;
 pub mod ids;
+pub mod layout;
+pub mod query;
 pub mod step;
@@ -10,6 +10,9 @@ pub struct Field {
     pub description: String,
     pub rank: String,
     pub config: FieldConfig,
+    /// Bumped whenever the field changes. Not serialized, like `Flow::version`.
+    #[serde(skip)]
+    pub version: u32,
 }
 
 impl Field {
@@ -27,6 +30,7 @@ impl Field {
             description,
             rank,
             config,
+            version: 1,
         }
     }
 
@@ -45,6 +49,7 @@ impl Field {
             description,
             rank,
             config,
+            version: 1,
         }
     }
 }
@@ -1,40 +1,84 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::organization::entities::ids::OrganizationId;
+
 use super::{ids::FlowId, step::Step};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Flow {
     pub id: FlowId,
+    pub organization_id: OrganizationId,
     pub name: String,
     pub description: String,
     pub steps: Vec<Step>,
+    /// Optimistic concurrency version, bumped on every change. Left out of
+    /// serialized snapshots so audit diffs only show content changes.
+    #[serde(skip)]
+    pub version: u32,
+}
+
+/// A flow without its steps and fields, for listings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowSummary {
+    pub id: FlowId,
+    pub name: String,
+    pub description: String,
+    pub step_count: usize,
+    pub version: u32,
 }
 
 impl Flow {
-    pub fn new(name: String, description: String) -> Self {
+    pub fn new(organization_id: OrganizationId, name: String, description: String) -> Self {
         Flow {
             id: FlowId::new(),
+            organization_id,
             name,
             description,
             steps: Vec::new(),
+            version: 1,
         }
     }
 
-    pub fn with_id(id: FlowId, name: String, description: String) -> Self {
+    pub fn with_id(
+        id: FlowId,
+        organization_id: OrganizationId,
+        name: String,
+        description: String,
+    ) -> Self {
         Flow {
             id,
+            organization_id,
             name,
             description,
             steps: Vec::new(),
+            version: 1,
         }
     }
 
-    pub fn with_steps(id: FlowId, name: String, description: String, steps: Vec<Step>) -> Self {
+    pub fn with_steps(
+        id: FlowId,
+        organization_id: OrganizationId,
+        name: String,
+        description: String,
+        steps: Vec<Step>,
+    ) -> Self {
         Flow {
             id,
+            organization_id,
             name,
             description,
             steps,
+            version: 1,
+        }
+    }
+
+    pub fn summary(&self) -> FlowSummary {
+        FlowSummary {
+            id: self.id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            step_count: self.steps.len(),
+            version: self.version,
         }
     }
 
@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use crate::domain::error::DomainError;
+
+use super::{
+    flow::Flow,
+    ids::{FieldId, StepId},
+};
+
+/// The order of the steps of a flow, and of the fields within each step.
+///
+/// A field listed under another step than its current one is moved there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowLayout {
+    pub steps: Vec<StepLayout>,
+}
+
+/// A step of a `FlowLayout` with the ids of its fields, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepLayout {
+    pub step_id: StepId,
+    pub field_ids: Vec<FieldId>,
+}
+
+impl FlowLayout {
+    /// Check that the layout lists every step and field of `flow` exactly once,
+    /// and nothing else.
+    pub fn validate(&self, flow: &Flow) -> Result<(), DomainError> {
+        let mut steps = HashSet::new();
+        let mut fields = HashSet::new();
+        for step in &self.steps {
+            if flow.get_step(&step.step_id).is_none() {
+                return Err(DomainError::validation(format!(
+                    "Step {} does not belong to flow {}",
+                    step.step_id, flow.id
+                )));
+            }
+            if !steps.insert(step.step_id) {
+                return Err(DomainError::validation(format!(
+                    "Step {} is listed more than once",
+                    step.step_id
+                )));
+            }
+            for field_id in &step.field_ids {
+                if !flow.steps.iter().any(|s| s.get_field(field_id).is_some()) {
+                    return Err(DomainError::validation(format!(
+                        "Field {field_id} does not belong to flow {}",
+                        flow.id
+                    )));
+                }
+                if !fields.insert(*field_id) {
+                    return Err(DomainError::validation(format!(
+                        "Field {field_id} is listed more than once"
+                    )));
+                }
+            }
+        }
+
+        if let Some(step) = flow.steps.iter().find(|s| !steps.contains(&s.id)) {
+            return Err(DomainError::validation(format!(
+                "Step {} is missing from the layout",
+                step.id
+            )));
+        }
+        if let Some(field) = flow
+            .steps
+            .iter()
+            .flat_map(|s| &s.fields)
+            .find(|f| !fields.contains(&f.id))
+        {
+            return Err(DomainError::validation(format!(
+                "Field {} is missing from the layout",
+                field.id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        flows::entities::{
+            field::{Field, FieldConfig},
+            step::Step,
+        },
+        organization::entities::ids::OrganizationId,
+    };
+
+    /// A flow with two steps of two fields each.
+    fn flow() -> Flow {
+        let mut flow = Flow::new(OrganizationId::new("acme"), "Kitchen".into(), String::new());
+        for (title, rank) in [("Size", "0|n"), ("Finish", "0|o")] {
+            let mut step = Step::new(title.into(), String::new(), rank.into());
+            for (key, rank) in [("a", "0|n"), ("b", "0|o")] {
+                step.add_field(Field::new(
+                    key.into(),
+                    key.into(),
+                    String::new(),
+                    rank.into(),
+                    FieldConfig::new_text(32),
+                ));
+            }
+            flow.add_step(step);
+        }
+        flow
+    }
+
+    fn layout_of(flow: &Flow) -> FlowLayout {
+        FlowLayout {
+            steps: flow
+                .steps
+                .iter()
+                .map(|s| StepLayout {
+                    step_id: s.id,
+                    field_ids: s.fields.iter().map(|f| f.id).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn assert_rejected(layout: &FlowLayout, flow: &Flow, message: &str) {
+        match layout.validate(flow) {
+            Err(DomainError::ValidationError { message: actual }) => assert_eq!(actual, message),
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reordered_and_moved_entries_are_valid() {
+        let flow = flow();
+        let mut layout = layout_of(&flow);
+        layout.steps.reverse();
+        let moved = layout.steps[0].field_ids.pop().unwrap();
+        layout.steps[1].field_ids.insert(0, moved);
+
+        assert!(layout.validate(&flow).is_ok());
+    }
+
+    #[test]
+    fn test_every_step_must_be_listed_once() {
+        let flow = flow();
+
+        let mut missing = layout_of(&flow);
+        let dropped = missing.steps.pop().unwrap();
+        missing.steps[0].field_ids.extend(dropped.field_ids);
+        assert_rejected(
+            &missing,
+            &flow,
+            &format!("Step {} is missing from the layout", dropped.step_id),
+        );
+
+        let mut twice = layout_of(&flow);
+        let repeated = StepLayout {
+            step_id: twice.steps[0].step_id,
+            field_ids: Vec::new(),
+        };
+        twice.steps.push(repeated);
+        assert_rejected(
+            &twice,
+            &flow,
+            &format!("Step {} is listed more than once", flow.steps[0].id),
+        );
+
+        let mut unknown = layout_of(&flow);
+        let stranger = StepId::new();
+        unknown.steps[0].step_id = stranger;
+        assert_rejected(
+            &unknown,
+            &flow,
+            &format!("Step {stranger} does not belong to flow {}", flow.id),
+        );
+    }
+
+    #[test]
+    fn test_every_field_must_be_listed_once() {
+        let flow = flow();
+
+        let mut missing = layout_of(&flow);
+        let dropped = missing.steps[1].field_ids.pop().unwrap();
+        assert_rejected(
+            &missing,
+            &flow,
+            &format!("Field {dropped} is missing from the layout"),
+        );
+
+        let mut twice = layout_of(&flow);
+        let repeated = twice.steps[0].field_ids[0];
+        twice.steps[1].field_ids.push(repeated);
+        assert_rejected(
+            &twice,
+            &flow,
+            &format!("Field {repeated} is listed more than once"),
+        );
+
+        let mut unknown = layout_of(&flow);
+        let stranger = FieldId::new();
+        unknown.steps[0].field_ids.push(stranger);
+        assert_rejected(
+            &unknown,
+            &flow,
+            &format!("Field {stranger} does not belong to flow {}", flow.id),
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+/// Page size used when the caller does not ask for one.
+pub const DEFAULT_LIMIT: u32 = 20;
+/// Largest page a listing returns.
+pub const MAX_LIMIT: u32 = 100;
+
+/// What a listing is sorted on. Ties are broken on id, so pages never overlap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListSort {
+    /// Creation order. Ids are UUIDv7, so this is the order of the ids.
+    #[default]
+    Created,
+    /// Name, ignoring case.
+    Name,
+}
+
+impl ListSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ListSort::Created => "created",
+            ListSort::Name => "name",
+        }
+    }
+
+    /// Newest first when sorting on creation, A to Z when sorting on name.
+    pub fn default_order(&self) -> SortOrder {
+        match self {
+            ListSort::Created => SortOrder::Desc,
+            ListSort::Name => SortOrder::Asc,
+        }
+    }
+}
+
+impl std::fmt::Display for ListSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ListSort {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(ListSort::Created),
+            "name" => Ok(ListSort::Name),
+            other => Err(DomainError::validation(format!("Unknown sort '{other}'"))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortOrder::Asc),
+            "desc" => Ok(SortOrder::Desc),
+            other => Err(DomainError::validation(format!(
+                "Unknown sort order '{other}'"
+            ))),
+        }
+    }
+}
+
+/// Position in a listing: the sort key of the last item of a page, which the
+/// next page starts after.
+///
+/// The cursor holds the key itself rather than pointing at the item, so the
+/// next page is the same whether that item was deleted or renamed meanwhile.
+/// Its string form is opaque to clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor<C> {
+    pub id: C,
+    /// Name of the item, set when the listing is sorted by name.
+    pub name: Option<String>,
+}
+
+impl<C: std::fmt::Display> std::fmt::Display for Cursor<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match &self.name {
+            Some(name) => format!("{}:{name}", self.id),
+            None => self.id.to_string(),
+        };
+        key.bytes().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl<C: std::str::FromStr> std::str::FromStr for Cursor<C> {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DomainError::validation(format!("Invalid cursor '{s}'"));
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| {
+                s.get(i..i + 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        let key = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (id, name) = match key.split_once(':') {
+            Some((id, name)) => (id, Some(name.to_string())),
+            None => (key.as_str(), None),
+        };
+        Ok(Self {
+            id: id.parse().map_err(|_| invalid())?,
+            name,
+        })
+    }
+}
+
+/// Which slice of a listing to return.
+///
+/// Pages are addressed by cursor rather than by offset, so items created,
+/// deleted or renamed meanwhile do not shift the following pages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListQuery<C> {
+    /// Only items whose name contains this, ignoring case.
+    pub search: Option<String>,
+    pub sort: ListSort,
+    pub order: SortOrder,
+    /// Cursor of the previous page; `None` starts at the first item.
+    pub after: Option<Cursor<C>>,
+    pub limit: u32,
+}
+
+impl<C> Default for ListQuery<C> {
+    fn default() -> Self {
+        Self {
+            search: None,
+            sort: ListSort::default(),
+            order: ListSort::default().default_order(),
+            after: None,
+            limit: DEFAULT_LIMIT,
+        }
+    }
+}
+
+impl<C> ListQuery<C> {
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if self.limit == 0 || self.limit > MAX_LIMIT {
+            return Err(DomainError::validation(format!(
+                "Page size must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+        if self
+            .after
+            .as_ref()
+            .is_some_and(|after| after.name.is_some() != (self.sort == ListSort::Name))
+        {
+            return Err(DomainError::validation(format!(
+                "Cursor does not belong to a listing sorted by {}",
+                self.sort
+            )));
+        }
+        Ok(())
+    }
+
+    /// The cursor of the page ending with the item `id` named `name`.
+    pub fn cursor(&self, id: C, name: &str) -> Cursor<C> {
+        Cursor {
+            id,
+            name: (self.sort == ListSort::Name).then(|| name.to_string()),
+        }
+    }
+
+    /// Whether `name` passes the `search` filter.
+    pub fn matches(&self, name: &str) -> bool {
+        self.search
+            .as_deref()
+            .is_none_or(|search| name.to_lowercase().contains(&search.to_lowercase()))
+    }
+}
+
+impl<C: Copy + Ord> ListQuery<C> {
+    /// Run the query over items held in memory, as the in-memory repositories
+    /// do. `key` gives the id and the name of an item.
+    pub fn select<T>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        key: impl Fn(&T) -> (C, String),
+    ) -> Vec<T> {
+        // Sort keys: the lowercase name when sorting by name, then the id.
+        let sort_key = |id: C, name: &str| match self.sort {
+            ListSort::Created => (String::new(), id),
+            ListSort::Name => (name.to_lowercase(), id),
+        };
+        let cursor = self
+            .after
+            .as_ref()
+            .map(|after| sort_key(after.id, after.name.as_deref().unwrap_or_default()));
+        let mut keyed: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                let (id, name) = key(&item);
+                (sort_key(id, &name), self.matches(&name), item)
+            })
+            .collect();
+
+        keyed.sort_by(|(a, _, _), (b, _, _)| match self.order {
+            SortOrder::Asc => a.cmp(b),
+            SortOrder::Desc => b.cmp(a),
+        });
+        keyed
+            .into_iter()
+            .filter(|(key, matches, _)| {
+                *matches
+                    && cursor.as_ref().is_none_or(|cursor| match self.order {
+                        SortOrder::Asc => key > cursor,
+                        SortOrder::Desc => key < cursor,
+                    })
+            })
+            .take(self.limit as usize)
+            .map(|(_, _, item)| item)
+            .collect()
+    }
+}
+
+/// One page of a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    /// Cursor of the next page, `None` on the last one.
+    pub next: Option<Cursor<C>>,
+}
+
+impl<T, C> Page<T, C> {
+    /// Build a page of at most `limit` items out of up to `limit + 1`: the extra
+    /// one only tells that another page follows, starting after the last item
+    /// kept.
+    pub fn from_items(mut items: Vec<T>, limit: u32, cursor: impl Fn(&T) -> Cursor<C>) -> Self {
+        let limit = limit as usize;
+        let next = if items.len() > limit {
+            items.truncate(limit);
+            items.last().map(cursor)
+        } else {
+            None
+        };
+        Self { items, next }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_and_order_round_trip_through_strings() {
+        for sort in [ListSort::Created, ListSort::Name] {
+            assert_eq!(sort.as_str().parse::<ListSort>().unwrap(), sort);
+        }
+        for order in [SortOrder::Asc, SortOrder::Desc] {
+            assert_eq!(order.as_str().parse::<SortOrder>().unwrap(), order);
+        }
+        assert!("rank".parse::<ListSort>().is_err());
+        assert!("up".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn test_limit_must_be_within_bounds() {
+        let query = |limit| ListQuery::<u32> {
+            limit,
+            ..ListQuery::default()
+        };
+
+        assert!(query(1).validate().is_ok());
+        assert!(query(MAX_LIMIT).validate().is_ok());
+        assert!(query(0).validate().is_err());
+        assert!(query(MAX_LIMIT + 1).validate().is_err());
+    }
+
+    #[test]
+    fn test_search_ignores_case() {
+        let query = ListQuery::<u32> {
+            search: Some("KITCH".to_string()),
+            ..ListQuery::default()
+        };
+
+        assert!(query.matches("Small kitchen"));
+        assert!(!query.matches("Bathroom"));
+        assert!(ListQuery::<u32>::default().matches("Bathroom"));
+    }
+
+    /// Items of `select` tests: an id, which is also the creation order, and a name.
+    fn items() -> Vec<(u32, &'static str)> {
+        vec![
+            (1, "kitchen"),
+            (2, "Bathroom"),
+            (3, "attic"),
+            (4, "Kitchenette"),
+        ]
+    }
+
+    fn select(query: ListQuery<u32>) -> Vec<u32> {
+        query
+            .select(items(), |(id, name)| (*id, name.to_string()))
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    fn after(id: u32, name: Option<&str>) -> Option<Cursor<u32>> {
+        Some(Cursor {
+            id,
+            name: name.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn test_select_sorts_filters_and_pages() {
+        let newest_first = ListQuery::default();
+        let by_name = ListQuery {
+            sort: ListSort::Name,
+            order: SortOrder::Asc,
+            ..ListQuery::default()
+        };
+
+        assert_eq!(select(newest_first.clone()), vec![4, 3, 2, 1]);
+        assert_eq!(select(by_name.clone()), vec![3, 2, 1, 4]);
+        assert_eq!(
+            select(ListQuery {
+                search: Some("kitchen".to_string()),
+                ..by_name.clone()
+            }),
+            vec![1, 4]
+        );
+        assert_eq!(
+            select(ListQuery {
+                after: after(2, Some("Bathroom")),
+                limit: 1,
+                ..by_name
+            }),
+            vec![1]
+        );
+        assert_eq!(
+            select(ListQuery {
+                after: after(3, None),
+                ..newest_first
+            }),
+            vec![2, 1]
+        );
+    }
+
+    #[test]
+    fn test_select_resumes_after_deleted_or_renamed_items() {
+        let by_name = ListQuery {
+            sort: ListSort::Name,
+            order: SortOrder::Asc,
+            ..ListQuery::default()
+        };
+
+        // Neither 9 nor "basement" is among the items: the page starts where
+        // they would sort.
+        assert_eq!(
+            select(ListQuery {
+                after: after(9, Some("basement")),
+                ..by_name.clone()
+            }),
+            vec![2, 1, 4]
+        );
+        // Item 3 was named "Bathroom" when the cursor was issued: renamed to
+        // "attic" since, it does not show up again.
+        assert_eq!(
+            select(ListQuery {
+                after: after(3, Some("Bathroom")),
+                ..by_name
+            }),
+            vec![1, 4]
+        );
+    }
+
+    #[test]
+    fn test_cursor_must_match_the_sort() {
+        let by_name = ListQuery::<u32> {
+            sort: ListSort::Name,
+            after: after(1, None),
+            ..ListQuery::default()
+        };
+        let by_creation = ListQuery::<u32> {
+            after: after(1, Some("kitchen")),
+            ..ListQuery::default()
+        };
+
+        assert!(matches!(
+            by_name.validate(),
+            Err(DomainError::ValidationError { .. })
+        ));
+        assert!(matches!(
+            by_creation.validate(),
+            Err(DomainError::ValidationError { .. })
+        ));
+        assert_eq!(
+            by_name.cursor(1, "Kitchen"),
+            after(1, Some("Kitchen")).unwrap()
+        );
+        assert_eq!(by_creation.cursor(1, "Kitchen"), after(1, None).unwrap());
+    }
+
+    #[test]
+    fn test_cursor_round_trips_through_an_opaque_string() {
+        for cursor in [after(7, None), after(7, Some("Cuisine: équipée"))] {
+            let cursor = cursor.unwrap();
+            let encoded = cursor.to_string();
+
+            assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+            assert_eq!(encoded.parse::<Cursor<u32>>().unwrap(), cursor);
+        }
+        for invalid in ["7", "zz", "ff", "6b"] {
+            assert!(matches!(
+                invalid.parse::<Cursor<u32>>(),
+                Err(DomainError::ValidationError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_extra_item_gives_the_next_cursor() {
+        let cursor = |i: &u32| Cursor { id: *i, name: None };
+        let full = Page::from_items(vec![1, 2, 3], 2, cursor);
+        assert_eq!(full.items, vec![1, 2]);
+        assert_eq!(full.next, after(2, None));
+
+        let last = Page::from_items(vec![1, 2], 2, cursor);
+        assert_eq!(last.items, vec![1, 2]);
+        assert_eq!(last.next, None);
+    }
+}
@@ -13,6 +13,9 @@ pub struct Step {
     pub min_repeats: u32,
     pub max_repeats: Option<u32>,
     pub fields: Vec<Field>,
+    /// Bumped whenever the step itself changes. Not serialized, like `Flow::version`.
+    #[serde(skip)]
+    pub version: u32,
 }
 
 impl Step {
@@ -27,6 +30,7 @@ impl Step {
             min_repeats: 1,
             max_repeats: None,
             fields: Vec::new(),
+            version: 1,
         }
     }
 
@@ -41,9 +45,11 @@ impl Step {
             min_repeats: 1,
             max_repeats: None,
             fields: Vec::new(),
+            version: 1,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn with_fields(
         id: StepId,
         title: String,
@@ -65,6 +71,7 @@ impl Step {
             min_repeats,
             max_repeats,
             fields,
+            version: 1,
         }
     }
 
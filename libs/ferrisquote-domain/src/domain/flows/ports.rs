@@ -1,22 +1,41 @@
 use std::future::Future;
 
-use crate::domain::error::DomainError;
+use crate::domain::{
+    audit::entities::actor::Actor, error::DomainError, organization::entities::ids::OrganizationId,
+};
 
 use super::entities::{
     field::{Field, FieldConfig},
-    flow::Flow,
+    flow::{Flow, FlowSummary},
     ids::{FieldId, FlowId, StepId},
+    layout::FlowLayout,
+    query::{ListQuery, Page},
     step::Step,
 };
 
 /// Repository trait for Flow entity.
+///
+/// Every lookup is scoped to an organization: a flow owned by another organization
+/// must be reported as `DomainError::NotFound`, exactly like a missing one.
 pub trait FlowRepository: Send + Sync {
-    /// Create a new flow.
+    /// Create a new flow in `flow.organization_id`.
     fn create_flow(&self, flow: Flow) -> impl Future<Output = Result<Flow, DomainError>> + Send;
     /// Retrieve a flow by id.
-    fn get_flow(&self, id: FlowId) -> impl Future<Output = Result<Flow, DomainError>> + Send;
-    /// List all flows.
-    fn list_flows(&self) -> impl Future<Output = Result<Vec<Flow>, DomainError>> + Send;
+    fn get_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
+    /// Summaries of the flows of an organization matching `query`, without
+    /// loading their steps.
+    ///
+    /// Returns at most `query.limit` flows, in the requested order, following
+    /// the `query.after` cursor.
+    fn list_flows(
+        &self,
+        organization: &OrganizationId,
+        query: &ListQuery<FlowId>,
+    ) -> impl Future<Output = Result<Vec<FlowSummary>, DomainError>> + Send;
     /// Update an existing flow.
     ///
     /// The repository should update only the fields that are provided (`Some(_)`).
@@ -25,12 +44,27 @@ pub trait FlowRepository: Send + Sync {
     /// partial updates atomically where supported.
     fn update_flow(
         &self,
+        organization: &OrganizationId,
         id: FlowId,
         name: Option<String>,
         description: Option<String>,
     ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
     /// Delete a flow by id.
-    fn delete_flow(&self, id: FlowId) -> impl Future<Output = Result<(), DomainError>> + Send;
+    fn delete_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Increment the version of a flow and return the new one.
+    ///
+    /// With `expected`, the flow must still be at that version: otherwise this
+    /// fails with `DomainError::Conflict` and leaves it unchanged.
+    fn bump_flow_version(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+        expected: Option<u32>,
+    ) -> impl Future<Output = Result<u32, DomainError>> + Send;
 }
 
 /// Repository trait for Step entity.
@@ -45,12 +79,31 @@ pub trait StepRepository: Send + Sync {
     /// Retrieve a step by id.
     fn get_step(&self, id: StepId) -> impl Future<Output = Result<Step, DomainError>> + Send;
 
+    /// Retrieve a step of `organization` together with the id of the flow owning it.
+    ///
+    /// Steps of another organization are reported as `DomainError::NotFound`.
+    fn get_step_with_flow(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> impl Future<Output = Result<(FlowId, Step), DomainError>> + Send;
+
+    /// Id of the flow owning a step.
+    ///
+    /// Steps of another organization are reported as `DomainError::NotFound`.
+    fn find_flow_id_for_step(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> impl Future<Output = Result<FlowId, DomainError>> + Send;
+
     /// Update an existing step (partial update).
     ///
     /// The repository should update only the fields provided as `Some(...)`.
     /// Fields set to `None` must remain unchanged in storage. This avoids a
     /// full select-and-rewrite on the caller side and lets repositories apply
     /// partial updates atomically where supported.
+    #[allow(clippy::too_many_arguments)]
     fn update_step(
         &self,
         id: StepId,
@@ -62,8 +115,20 @@ pub trait StepRepository: Send + Sync {
         min_repeats: Option<u32>,
         max_repeats: Option<Option<u32>>,
     ) -> impl Future<Output = Result<Step, DomainError>> + Send;
+    /// Set the rank of each step in `ranks`, all or none of them.
+    fn update_step_ranks(
+        &self,
+        ranks: &[(StepId, String)],
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
     /// Delete a step by id.
     fn delete_step(&self, id: StepId) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Increment the version of a step and return the new one, failing with
+    /// `DomainError::Conflict` when it is no longer at `expected`.
+    fn bump_step_version(
+        &self,
+        id: StepId,
+        expected: Option<u32>,
+    ) -> impl Future<Output = Result<u32, DomainError>> + Send;
 }
 
 /// Repository trait for Field entity.
@@ -89,6 +154,35 @@ pub trait FieldRepository: Send + Sync {
     ) -> impl Future<Output = Result<Field, DomainError>> + Send;
     /// Delete a field by id.
     fn delete_field(&self, id: FieldId) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Increment the version of a field and return the new one, failing with
+    /// `DomainError::Conflict` when it is no longer at `expected`.
+    fn bump_field_version(
+        &self,
+        id: FieldId,
+        expected: Option<u32>,
+    ) -> impl Future<Output = Result<u32, DomainError>> + Send;
+    /// Move a field to `step_id` at `rank`.
+    ///
+    /// The field keeps its id, creation time and everything attached to it.
+    fn move_field(
+        &self,
+        field_id: FieldId,
+        step_id: StepId,
+        rank: String,
+    ) -> impl Future<Output = Result<Field, DomainError>> + Send;
+    /// Set the rank of each field in `ranks`, all or none of them.
+    fn update_field_ranks(
+        &self,
+        ranks: &[(FieldId, String)],
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Id of the flow owning a field.
+    ///
+    /// Fields of another organization are reported as `DomainError::NotFound`.
+    fn find_flow_id_for_field(
+        &self,
+        organization: &OrganizationId,
+        field_id: FieldId,
+    ) -> impl Future<Output = Result<FlowId, DomainError>> + Send;
     /// Get all fields for a flow.
     fn get_flow_fields(
         &self,
@@ -98,45 +192,117 @@ pub trait FieldRepository: Send + Sync {
 }
 
 /// Service trait for Flow domain logic.
+///
+/// Every operation acts on behalf of `organization` and only sees its flows.
+/// Operations that change a flow record the change under `actor` in the audit log.
+///
+/// Changes taking a `version` fail with `DomainError::Conflict` when the flow,
+/// step or field they target is no longer at that version; `None` skips the
+/// check. Either way the version is incremented.
 pub trait FlowService: Send + Sync {
     /// Create a flow with a given name.
-    fn create_flow(&self, name: String) -> impl Future<Output = Result<Flow, DomainError>> + Send;
+    fn create_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        name: String,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
     /// Get a flow by id.
-    fn get_flow(&self, id: FlowId) -> impl Future<Output = Result<Flow, DomainError>> + Send;
-    /// List all flows.
-    fn list_flows(&self) -> impl Future<Output = Result<Vec<Flow>, DomainError>> + Send;
+    fn get_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
+    /// One page of the flows of the organization, as summaries.
+    fn list_flows(
+        &self,
+        organization: &OrganizationId,
+        query: ListQuery<FlowId>,
+    ) -> impl Future<Output = Result<Page<FlowSummary, FlowId>, DomainError>> + Send;
     /// Update flow metadata.
     fn update_flow_metadata(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: FlowId,
         name: Option<String>,
         description: Option<String>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
     /// Delete a flow by id.
-    fn delete_flow(&self, id: FlowId) -> impl Future<Output = Result<(), DomainError>> + Send;
+    fn delete_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        version: Option<u32>,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Put the steps and fields of a flow in the order of `layout`, moving
+    /// fields across steps where needed, all in one change.
+    ///
+    /// The layout must list every step and field of the flow exactly once.
+    fn reorder_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        layout: FlowLayout,
+        version: Option<u32>,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
+    /// Rewrite the ranks of every step and field of a flow to short, evenly
+    /// spaced values, keeping their order.
+    ///
+    /// Steps and fields are also rebalanced automatically when a new rank
+    /// cannot be placed between its neighbours.
+    fn rebalance_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        version: Option<u32>,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
 }
 
 /// Service trait for Step domain logic.
+///
+/// Steps are only reachable through a flow of `organization`. Changes are
+/// recorded under `actor` in the audit log and check `version` like
+/// [`FlowService`] does.
 pub trait StepService: Send + Sync {
     /// Add a step to a flow.
     fn add_step(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         flow_id: FlowId,
         title: String,
     ) -> impl Future<Output = Result<Step, DomainError>> + Send;
     /// Remove a step by id.
-    fn remove_step(&self, step_id: StepId) -> impl Future<Output = Result<(), DomainError>> + Send;
-    /// Reorder a step within its flow.
+    fn remove_step(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        step_id: StepId,
+        version: Option<u32>,
+    ) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Reorder a step within its flow. Without a neighbour, the step goes to
+    /// the end of the flow.
     fn reorder_step(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         after_id: Option<StepId>,
         before_id: Option<StepId>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
 
     /// Update a step's metadata.
+    #[allow(clippy::too_many_arguments)]
     fn update_step_metadata(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         title: Option<String>,
         description: Option<String>,
@@ -144,14 +310,21 @@ pub trait StepService: Send + Sync {
         repeat_label: Option<Option<String>>,
         min_repeats: Option<u32>,
         max_repeats: Option<Option<u32>>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Step, DomainError>> + Send;
 }
 
 /// Service trait for Field domain logic.
+///
+/// Fields are only reachable through a flow of `organization`. Changes are
+/// recorded under `actor` in the audit log and check `version` like
+/// [`FlowService`] does.
 pub trait FieldService: Send + Sync {
     /// Add a field to a step.
     fn add_field(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         label: String,
         key: String,
@@ -163,28 +336,39 @@ pub trait FieldService: Send + Sync {
     /// changed by the repository. Fields set to `None` are left untouched.
     fn update_field_config(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         field_id: FieldId,
         label: Option<String>,
         config: Option<FieldConfig>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Field, DomainError>> + Send;
     /// Remove a field by id.
     fn remove_field(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         field_id: FieldId,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<(), DomainError>> + Send;
 
     /// Move a field into a step or change its order.
+    #[allow(clippy::too_many_arguments)]
     fn move_field(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         field_id: FieldId,
         target_step_id: Option<StepId>,
         after_id: Option<FieldId>,
         before_id: Option<FieldId>,
+        version: Option<u32>,
     ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
 
     /// Search for fields within a flow.
     fn search_flow_fields(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
         _query: Option<String>,
     ) -> impl Future<Output = Result<Vec<Field>, DomainError>> + Send;
@@ -1,13 +1,27 @@
+use std::collections::HashMap;
+
 use crate::domain::{
+    audit::{
+        entities::{actor::Actor, entry::AuditEntity},
+        ports::AuditRepository,
+        services::record,
+    },
     error::DomainError,
-    rank::{entities::Rank, ports::RankService},
+    organization::entities::ids::OrganizationId,
+    rank::{
+        ports::RankService,
+        services::{check_neighbours, rank_between, spread_ranks},
+    },
+    transaction::ports::{Transaction, Transactional, UnitOfWork},
 };
 
 use super::{
     entities::{
         field::{Field, FieldConfig},
-        flow::Flow,
+        flow::{Flow, FlowSummary},
         ids::{FieldId, FlowId, StepId},
+        layout::FlowLayout,
+        query::{ListQuery, Page},
         step::Step,
     },
     ports::{
@@ -21,34 +35,42 @@ use super::{
 /// The `FlowServiceImpl` acts as an orchestrator: it implements the `FlowService`,
 /// `StepService` and `FieldService` traits and delegates persistence to the
 /// provided repository implementations. It also uses a `RankService` to compute
-/// ordering ranks for steps and fields.
+/// ordering ranks for steps and fields, and records every change it makes to
+/// the audit log in the same transaction as the change.
 ///
 /// Type parameters:
 /// - `FR`: type implementing `FlowRepository` (storage for flows)
 /// - `SR`: type implementing `StepRepository` (storage for steps)
 /// - `FDR`: type implementing `FieldRepository` (storage for fields)
 /// - `RS`: type implementing `RankService` (rank generation)
+/// - `AR`: type implementing `AuditRepository` (audit log)
+/// - `UW`: type implementing `UnitOfWork` (transactions around each change)
 ///
 /// Example:
-/// ```no_run
+/// ```ignore
 /// # use ferrisquote::domain::flows::ports::*;
 /// # use ferrisquote::domain::rank::ports::RankService;
-/// # struct MyFlowRepo; struct MyStepRepo; struct MyFieldRepo; struct MyRankSvc;
+/// # use ferrisquote::domain::transaction::ports::UnitOfWork;
+/// # struct MyFlowRepo; struct MyStepRepo; struct MyFieldRepo; struct MyRankSvc; struct MyAuditRepo; struct MyUnitOfWork;
 /// # impl FlowRepository for MyFlowRepo { /* ... */ }
 /// # impl StepRepository for MyStepRepo { /* ... */ }
 /// # impl FieldRepository for MyFieldRepo { /* ... */ }
 /// # impl RankService for MyRankSvc { /* ... */ }
-/// let svc = FlowServiceImpl::new(MyFlowRepo, MyStepRepo, MyFieldRepo, MyRankSvc);
+/// # impl AuditRepository for MyAuditRepo { /* ... */ }
+/// # impl UnitOfWork for MyUnitOfWork { /* ... */ }
+/// let svc = FlowServiceImpl::new(MyFlowRepo, MyStepRepo, MyFieldRepo, MyRankSvc, MyAuditRepo, MyUnitOfWork);
 /// ```
 #[derive(Clone)]
-pub struct FlowServiceImpl<FR, SR, FDR, RS> {
+pub struct FlowServiceImpl<FR, SR, FDR, RS, AR, UW> {
     flow_repo: FR,
     step_repo: SR,
     field_repo: FDR,
     rank_service: RS,
+    audit_repo: AR,
+    unit_of_work: UW,
 }
 
-impl<FR, SR, FDR, RS> FlowServiceImpl<FR, SR, FDR, RS> {
+impl<FR, SR, FDR, RS, AR, UW> FlowServiceImpl<FR, SR, FDR, RS, AR, UW> {
     /// Construct a new `FlowServiceImpl`.
     ///
     /// Parameters:
@@ -56,115 +78,576 @@ impl<FR, SR, FDR, RS> FlowServiceImpl<FR, SR, FDR, RS> {
     /// - `step_repo`: repository handling `Step` persistence.
     /// - `field_repo`: repository handling `Field` persistence.
     /// - `rank_service`: service used to compute lexicographic ranks.
+    /// - `audit_repo`: repository the changes are recorded to.
+    /// - `unit_of_work`: opens the transaction each change runs in.
     ///
     /// The returned value implements `FlowService`, `StepService` and `FieldService`
     /// as long as the repository/service types implement the corresponding traits.
-    pub fn new(flow_repo: FR, step_repo: SR, field_repo: FDR, rank_service: RS) -> Self {
+    pub fn new(
+        flow_repo: FR,
+        step_repo: SR,
+        field_repo: FDR,
+        rank_service: RS,
+        audit_repo: AR,
+        unit_of_work: UW,
+    ) -> Self {
         Self {
             flow_repo,
             step_repo,
             field_repo,
             rank_service,
+            audit_repo,
+            unit_of_work,
         }
     }
 }
 
-impl<FR, SR, FDR, RS> FlowService for FlowServiceImpl<FR, SR, FDR, RS>
+/// The repositories of a `FlowServiceImpl`, bound to one transaction.
+struct Repositories<FR, SR, FDR, AR> {
+    flows: FR,
+    steps: SR,
+    fields: FDR,
+    audit: AR,
+}
+
+impl<FR, SR, FDR, RS, AR, UW> FlowServiceImpl<FR, SR, FDR, RS, AR, UW>
+where
+    FR: Transactional<UW::Transaction>,
+    SR: Transactional<UW::Transaction>,
+    FDR: Transactional<UW::Transaction>,
+    AR: Transactional<UW::Transaction>,
+    UW: UnitOfWork,
+{
+    /// Open a transaction and the repositories writing through it.
+    ///
+    /// A change and its audit entry are committed together, or not at all.
+    async fn begin(&self) -> Result<(UW::Transaction, Repositories<FR, SR, FDR, AR>), DomainError> {
+        let transaction = self.unit_of_work.begin().await?;
+        let repositories = Repositories {
+            flows: self.flow_repo.in_transaction(&transaction),
+            steps: self.step_repo.in_transaction(&transaction),
+            fields: self.field_repo.in_transaction(&transaction),
+            audit: self.audit_repo.in_transaction(&transaction),
+        };
+        Ok((transaction, repositories))
+    }
+}
+
+impl<FR, SR, FDR, RS, AR, UW> FlowServiceImpl<FR, SR, FDR, RS, AR, UW>
 where
-    FR: FlowRepository + Send + Sync,
-    SR: StepRepository + Send + Sync,
-    FDR: FieldRepository + Send + Sync,
+    FR: FlowRepository + Transactional<UW::Transaction> + Send + Sync,
+    SR: StepRepository + Transactional<UW::Transaction> + Send + Sync,
+    FDR: FieldRepository + Transactional<UW::Transaction> + Send + Sync,
     RS: RankService + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
 {
-    async fn create_flow(&self, name: String) -> Result<Flow, DomainError> {
-        let flow = Flow::new(name, String::new());
-        self.flow_repo.create_flow(flow).await
+    /// The rank of a step of `flow_id` placed between `after` and `before`.
+    ///
+    /// When there is no room left between them, the steps of the flow are
+    /// rebalanced first.
+    #[allow(clippy::too_many_arguments)]
+    async fn step_rank(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        flow_id: FlowId,
+        after: Option<&Step>,
+        before: Option<&Step>,
+    ) -> Result<String, DomainError> {
+        if let Ok(rank) = rank_between(
+            &self.rank_service,
+            after.map(|s| s.rank.as_str()),
+            before.map(|s| s.rank.as_str()),
+        ) {
+            return Ok(rank);
+        }
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+        let ranks = self
+            .rebalance_steps(repos, organization, actor, &flow)
+            .await?;
+        let rank_of = |step: Option<&Step>| step.and_then(|s| ranks.get(&s.id)).map(String::as_str);
+        rank_between(&self.rank_service, rank_of(after), rank_of(before))
+    }
+
+    /// The rank of a field of `step_id` placed between `after` and `before`.
+    ///
+    /// When there is no room left between them, the fields of the step are
+    /// rebalanced first.
+    #[allow(clippy::too_many_arguments)]
+    async fn field_rank(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        step_id: StepId,
+        after: Option<&Field>,
+        before: Option<&Field>,
+    ) -> Result<String, DomainError> {
+        if let Ok(rank) = rank_between(
+            &self.rank_service,
+            after.map(|f| f.rank.as_str()),
+            before.map(|f| f.rank.as_str()),
+        ) {
+            return Ok(rank);
+        }
+        let step = repos.steps.get_step(step_id).await?;
+        let ranks = self
+            .rebalance_fields(repos, organization, actor, &step)
+            .await?;
+        let rank_of =
+            |field: Option<&Field>| field.and_then(|f| ranks.get(&f.id)).map(String::as_str);
+        rank_between(&self.rank_service, rank_of(after), rank_of(before))
     }
 
-    async fn get_flow(&self, id: FlowId) -> Result<Flow, DomainError> {
-        self.flow_repo.get_flow(id).await
+    /// Rewrite the ranks of the steps of `flow` to short, evenly spaced values
+    /// in their current order, and return the new rank of each step.
+    async fn rebalance_steps(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        flow: &Flow,
+    ) -> Result<HashMap<StepId, String>, DomainError> {
+        let ids: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+        let ranks = spread_ranks(&self.rank_service, &ids)?;
+        self.write_step_ranks(repos, organization, actor, &flow.steps, &ranks)
+            .await?;
+        Ok(ranks.into_iter().collect())
     }
 
-    async fn list_flows(&self) -> Result<Vec<Flow>, DomainError> {
-        self.flow_repo.list_flows().await
+    /// Rewrite the ranks of the fields of `step` to short, evenly spaced values
+    /// in their current order, and return the new rank of each field.
+    async fn rebalance_fields(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        step: &Step,
+    ) -> Result<HashMap<FieldId, String>, DomainError> {
+        let ids: Vec<FieldId> = step.fields.iter().map(|f| f.id).collect();
+        let ranks = spread_ranks(&self.rank_service, &ids)?;
+        self.write_field_ranks(repos, organization, actor, &step.fields, &ranks)
+            .await?;
+        Ok(ranks.into_iter().collect())
+    }
+
+    /// Set the rank of each step in `ranks`, and record the change of each of
+    /// the `steps` it applies to.
+    async fn write_step_ranks(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        steps: &[Step],
+        ranks: &[(StepId, String)],
+    ) -> Result<(), DomainError> {
+        repos.steps.update_step_ranks(ranks).await?;
+
+        for (id, rank) in ranks {
+            if let Some(step) = steps.iter().find(|s| s.id == *id) {
+                let reranked = Step {
+                    rank: rank.clone(),
+                    ..step.clone()
+                };
+                record(
+                    &repos.audit,
+                    organization,
+                    actor,
+                    AuditEntity::Step,
+                    step.id,
+                    Some(step),
+                    Some(&reranked),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the rank of each field in `ranks`, and record the change of each of
+    /// the `fields` it applies to.
+    async fn write_field_ranks(
+        &self,
+        repos: &Repositories<FR, SR, FDR, AR>,
+        organization: &OrganizationId,
+        actor: &Actor,
+        fields: &[Field],
+        ranks: &[(FieldId, String)],
+    ) -> Result<(), DomainError> {
+        repos.fields.update_field_ranks(ranks).await?;
+
+        for (id, rank) in ranks {
+            if let Some(field) = fields.iter().find(|f| f.id == *id) {
+                let reranked = Field {
+                    rank: rank.clone(),
+                    ..field.clone()
+                };
+                record(
+                    &repos.audit,
+                    organization,
+                    actor,
+                    AuditEntity::Field,
+                    field.id,
+                    Some(field),
+                    Some(&reranked),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn flow_field(flow: &Flow, field_id: &FieldId) -> Option<Field> {
+    flow.steps
+        .iter()
+        .find_map(|s| s.get_field(field_id))
+        .cloned()
+}
+
+impl<FR, SR, FDR, RS, AR, UW> FlowService for FlowServiceImpl<FR, SR, FDR, RS, AR, UW>
+where
+    FR: FlowRepository + Transactional<UW::Transaction> + Send + Sync,
+    SR: StepRepository + Transactional<UW::Transaction> + Send + Sync,
+    FDR: FieldRepository + Transactional<UW::Transaction> + Send + Sync,
+    RS: RankService + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
+{
+    async fn create_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        name: String,
+    ) -> Result<Flow, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let flow = Flow::new(organization.clone(), name, String::new());
+        let flow = repos.flows.create_flow(flow).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Flow,
+            flow.id,
+            None,
+            Some(&flow),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(flow)
+    }
+
+    async fn get_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> Result<Flow, DomainError> {
+        self.flow_repo.get_flow(organization, id).await
+    }
+
+    async fn list_flows(
+        &self,
+        organization: &OrganizationId,
+        query: ListQuery<FlowId>,
+    ) -> Result<Page<FlowSummary, FlowId>, DomainError> {
+        query.validate()?;
+        let limit = query.limit;
+        // One flow more than asked tells whether another page follows.
+        let probe = ListQuery {
+            limit: limit + 1,
+            ..query
+        };
+        let flows = self.flow_repo.list_flows(organization, &probe).await?;
+
+        Ok(Page::from_items(flows, limit, |flow| {
+            probe.cursor(flow.id, &flow.name)
+        }))
     }
 
     async fn update_flow_metadata(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         id: FlowId,
         name: Option<String>,
         description: Option<String>,
+        version: Option<u32>,
+    ) -> Result<Flow, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let before = repos.flows.get_flow(organization, id).await?;
+        repos
+            .flows
+            .bump_flow_version(organization, id, version)
+            .await?;
+        let flow = repos
+            .flows
+            .update_flow(organization, id, name, description)
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Flow,
+            id,
+            Some(&before),
+            Some(&flow),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(flow)
+    }
+
+    async fn delete_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let before = repos.flows.get_flow(organization, id).await?;
+        repos
+            .flows
+            .bump_flow_version(organization, id, version)
+            .await?;
+        repos.flows.delete_flow(organization, id).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Flow,
+            id,
+            Some(&before),
+            None,
+        )
+        .await?;
+        transaction.commit().await
+    }
+
+    async fn reorder_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        layout: FlowLayout,
+        version: Option<u32>,
     ) -> Result<Flow, DomainError> {
-        self.flow_repo.update_flow(id, name, description).await
+        let (transaction, repos) = self.begin().await?;
+        let flow = repos.flows.get_flow(organization, id).await?;
+        repos
+            .flows
+            .bump_flow_version(organization, id, version)
+            .await?;
+        layout.validate(&flow)?;
+
+        let step_ranks: Vec<(StepId, String)> = layout
+            .steps
+            .iter()
+            .zip(self.rank_service.spread(layout.steps.len())?)
+            .map(|(step, rank)| (step.step_id, rank.into()))
+            .collect();
+        self.write_step_ranks(&repos, organization, actor, &flow.steps, &step_ranks)
+            .await?;
+
+        let fields: Vec<Field> = flow.steps.iter().flat_map(|s| s.fields.clone()).collect();
+        let mut field_ranks = Vec::new();
+        for step in &layout.steps {
+            let ranks = self.rank_service.spread(step.field_ids.len())?;
+            for (field_id, rank) in step.field_ids.iter().zip(ranks) {
+                let stays = flow
+                    .get_step(&step.step_id)
+                    .is_some_and(|s| s.get_field(field_id).is_some());
+                if stays {
+                    field_ranks.push((*field_id, rank.into()));
+                } else if let Some(field) = flow_field(&flow, field_id) {
+                    let moved = repos
+                        .fields
+                        .move_field(*field_id, step.step_id, rank.into())
+                        .await?;
+                    record(
+                        &repos.audit,
+                        organization,
+                        actor,
+                        AuditEntity::Field,
+                        *field_id,
+                        Some(&field),
+                        Some(&moved),
+                    )
+                    .await?;
+                }
+            }
+        }
+        self.write_field_ranks(&repos, organization, actor, &fields, &field_ranks)
+            .await?;
+
+        let flow = repos.flows.get_flow(organization, id).await?;
+        transaction.commit().await?;
+        Ok(flow)
     }
 
-    async fn delete_flow(&self, id: FlowId) -> Result<(), DomainError> {
-        self.flow_repo.delete_flow(id).await
+    async fn rebalance_flow(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: FlowId,
+        version: Option<u32>,
+    ) -> Result<Flow, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let flow = repos.flows.get_flow(organization, id).await?;
+        repos
+            .flows
+            .bump_flow_version(organization, id, version)
+            .await?;
+        self.rebalance_steps(&repos, organization, actor, &flow)
+            .await?;
+        for step in &flow.steps {
+            self.rebalance_fields(&repos, organization, actor, step)
+                .await?;
+        }
+        let flow = repos.flows.get_flow(organization, id).await?;
+        transaction.commit().await?;
+        Ok(flow)
     }
 }
 
-impl<FR, SR, FDR, RS> StepService for FlowServiceImpl<FR, SR, FDR, RS>
+impl<FR, SR, FDR, RS, AR, UW> StepService for FlowServiceImpl<FR, SR, FDR, RS, AR, UW>
 where
-    FR: FlowRepository + Send + Sync,
-    SR: StepRepository + Send + Sync,
-    FDR: FieldRepository + Send + Sync,
+    FR: FlowRepository + Transactional<UW::Transaction> + Send + Sync,
+    SR: StepRepository + Transactional<UW::Transaction> + Send + Sync,
+    FDR: FieldRepository + Transactional<UW::Transaction> + Send + Sync,
     RS: RankService + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
 {
-    async fn add_step(&self, flow_id: FlowId, title: String) -> Result<Step, DomainError> {
-        let flow = self.flow_repo.get_flow(flow_id).await?;
-
-        let next_rank = match flow.steps.get(flow.steps.len().saturating_sub(1)) {
-            Some(last_step) => self.rank_service.after(&Rank::from_string(last_step.rank.clone())),
-            None => self.rank_service.initial(),
-        };
+    async fn add_step(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        flow_id: FlowId,
+        title: String,
+    ) -> Result<Step, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+
+        let next_rank = self
+            .step_rank(
+                &repos,
+                organization,
+                actor,
+                flow_id,
+                flow.steps.last(),
+                None,
+            )
+            .await?;
 
-        let step = Step::new(title, String::new(), next_rank.as_str().to_string());
-        self.step_repo.create_step(flow_id, step).await
+        let step = Step::new(title, String::new(), next_rank);
+        let step = repos.steps.create_step(flow_id, step).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Step,
+            step.id,
+            None,
+            Some(&step),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(step)
     }
 
-    async fn remove_step(&self, step_id: StepId) -> Result<(), DomainError> {
-        self.step_repo.delete_step(step_id).await
+    async fn remove_step(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        step_id: StepId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let (_, step) = repos
+            .steps
+            .get_step_with_flow(organization, step_id)
+            .await?;
+        repos.steps.bump_step_version(step_id, version).await?;
+        repos.steps.delete_step(step_id).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Step,
+            step_id,
+            Some(&step),
+            None,
+        )
+        .await?;
+        transaction.commit().await
     }
 
     async fn reorder_step(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         after_id: Option<StepId>,
         before_id: Option<StepId>,
+        version: Option<u32>,
     ) -> Result<Flow, DomainError> {
-        let after_rank = if let Some(id) = after_id {
-            Some(self.step_repo.get_step(id).await?.rank)
-        } else {
-            None
-        };
-        let before_rank = if let Some(id) = before_id {
-            Some(self.step_repo.get_step(id).await?.rank)
-        } else {
-            None
+        let (transaction, repos) = self.begin().await?;
+        let (flow_id, before) = repos
+            .steps
+            .get_step_with_flow(organization, step_id)
+            .await?;
+        repos.steps.bump_step_version(step_id, version).await?;
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+
+        let siblings: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+        check_neighbours(
+            "Step",
+            step_id,
+            &siblings,
+            &format!("flow {flow_id}"),
+            after_id,
+            before_id,
+        )?;
+        let after = after_id.and_then(|id| flow.get_step(&id));
+        let before_step = before_id.and_then(|id| flow.get_step(&id));
+
+        // Without a neighbour, the step goes to the end of the flow.
+        let after = match (after, before_step) {
+            (None, None) => flow.steps.iter().rev().find(|s| s.id != step_id),
+            _ => after,
         };
 
-        let new_rank = match (after_rank, before_rank) {
-            (Some(a), Some(b)) => {
-                self.rank_service.between(&Rank::from_string(a), &Rank::from_string(b))
-            }
-            (Some(a), None) => self.rank_service.after(&Rank::from_string(a)),
-            (None, Some(b)) => self.rank_service.before(&Rank::from_string(b)),
-            (None, None) => self.rank_service.initial(),
-        };
+        let new_rank = self
+            .step_rank(&repos, organization, actor, flow_id, after, before_step)
+            .await?;
 
-        self.step_repo
-            .update_step(step_id, None, None, Some(new_rank.as_str().to_string()), None, None, None, None)
+        repos
+            .steps
+            .update_step(step_id, None, None, Some(new_rank), None, None, None, None)
             .await?;
 
-        let flows = self.flow_repo.list_flows().await?;
-        flows
-            .into_iter()
-            .find(|f| f.get_step(&step_id).is_some())
-            .ok_or_else(|| DomainError::not_found("Flow containing step", format!("{:?}", step_id)))
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Step,
+            step_id,
+            Some(&before),
+            flow.get_step(&step_id),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(flow)
     }
 
     async fn update_step_metadata(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         title: Option<String>,
         description: Option<String>,
@@ -172,138 +655,243 @@ where
         repeat_label: Option<Option<String>>,
         min_repeats: Option<u32>,
         max_repeats: Option<Option<u32>>,
+        version: Option<u32>,
     ) -> Result<Step, DomainError> {
-        self.step_repo
+        let (transaction, repos) = self.begin().await?;
+        let (_, before) = repos
+            .steps
+            .get_step_with_flow(organization, step_id)
+            .await?;
+        repos.steps.bump_step_version(step_id, version).await?;
+        let step = repos
+            .steps
             .update_step(step_id, title, description, None, is_repeatable, repeat_label, min_repeats, max_repeats)
-            .await
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Step,
+            step_id,
+            Some(&before),
+            Some(&step),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(step)
     }
 }
 
-impl<FR, SR, FDR, RS> FieldService for FlowServiceImpl<FR, SR, FDR, RS>
+impl<FR, SR, FDR, RS, AR, UW> FieldService for FlowServiceImpl<FR, SR, FDR, RS, AR, UW>
 where
-    FR: FlowRepository + Send + Sync,
-    SR: StepRepository + Send + Sync,
-    FDR: FieldRepository + Send + Sync,
+    FR: FlowRepository + Transactional<UW::Transaction> + Send + Sync,
+    SR: StepRepository + Transactional<UW::Transaction> + Send + Sync,
+    FDR: FieldRepository + Transactional<UW::Transaction> + Send + Sync,
     RS: RankService + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
 {
     async fn add_field(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         step_id: StepId,
         label: String,
         key: String,
         config: FieldConfig,
     ) -> Result<Field, DomainError> {
-        let step = self.step_repo.get_step(step_id).await?;
+        let (transaction, repos) = self.begin().await?;
+        let (_, step) = repos
+            .steps
+            .get_step_with_flow(organization, step_id)
+            .await?;
 
-        let next_rank = match step.fields.get(step.fields.len().saturating_sub(1)) {
-            Some(last_field) => self.rank_service.after(&Rank::from_string(last_field.rank.clone())),
-            None => self.rank_service.initial(),
-        };
+        let next_rank = self
+            .field_rank(
+                &repos,
+                organization,
+                actor,
+                step_id,
+                step.fields.last(),
+                None,
+            )
+            .await?;
 
-        let field = Field::new(
-            key,
-            label,
-            String::new(),
-            next_rank.as_str().to_string(),
-            config,
-        );
+        let field = Field::new(key, label, String::new(), next_rank, config);
 
-        self.field_repo.create_field(step_id, field).await
+        let field = repos.fields.create_field(step_id, field).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Field,
+            field.id,
+            None,
+            Some(&field),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(field)
     }
 
     async fn update_field_config(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         field_id: FieldId,
         label: Option<String>,
         config: Option<FieldConfig>,
+        version: Option<u32>,
     ) -> Result<Field, DomainError> {
-        self.field_repo
+        let (transaction, repos) = self.begin().await?;
+        let flow_id = repos
+            .fields
+            .find_flow_id_for_field(organization, field_id)
+            .await?;
+        repos.fields.bump_field_version(field_id, version).await?;
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+        let field = repos
+            .fields
             .update_field(field_id, None, label, None, config)
-            .await
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Field,
+            field_id,
+            flow_field(&flow, &field_id).as_ref(),
+            Some(&field),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(field)
     }
 
-    async fn remove_field(&self, field_id: FieldId) -> Result<(), DomainError> {
-        self.field_repo.delete_field(field_id).await
+    async fn remove_field(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        field_id: FieldId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let flow_id = repos
+            .fields
+            .find_flow_id_for_field(organization, field_id)
+            .await?;
+        repos.fields.bump_field_version(field_id, version).await?;
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+        repos.fields.delete_field(field_id).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Field,
+            field_id,
+            flow_field(&flow, &field_id).as_ref(),
+            None,
+        )
+        .await?;
+        transaction.commit().await
     }
 
     async fn move_field(
         &self,
+        organization: &OrganizationId,
+        actor: &Actor,
         field_id: FieldId,
         target_step_id: Option<StepId>,
         after_id: Option<FieldId>,
         before_id: Option<FieldId>,
+        version: Option<u32>,
     ) -> Result<Flow, DomainError> {
-        let flows = self.flow_repo.list_flows().await?;
-        let mut found: Option<(Flow, StepId, Field)> = None;
-        for f in flows.into_iter() {
-            for s in f.steps.iter() {
-                if let Some(field) = s.get_field(&field_id) {
-                    found = Some((f.clone(), s.id, field.clone()));
-                    break;
-                }
-            }
-            if found.is_some() {
-                break;
-            }
+        let (transaction, repos) = self.begin().await?;
+        let flow_id = repos
+            .fields
+            .find_flow_id_for_field(organization, field_id)
+            .await?;
+        repos.fields.bump_field_version(field_id, version).await?;
+        if let Some(target) = target_step_id
+            && repos
+                .steps
+                .find_flow_id_for_step(organization, target)
+                .await?
+                != flow_id
+        {
+            return Err(DomainError::validation(format!(
+                "Step {target} does not belong to flow {flow_id}"
+            )));
         }
 
-        let (flow, source_step_id, field) =
-            found.ok_or_else(|| DomainError::not_found("Field", format!("{:?}", field_id)))?;
+        let flow = repos.flows.get_flow(organization, flow_id).await?;
+        let (source_step_id, field) = flow
+            .steps
+            .iter()
+            .find_map(|s| s.get_field(&field_id).map(|f| (s.id, f.clone())))
+            .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
 
         let target_step = target_step_id.unwrap_or(source_step_id);
-
-        let find_field_rank = |fid: FieldId| -> Option<String> {
-            for s in flow.steps.iter() {
-                if let Some(fld) = s.get_field(&fid) {
-                    return Some(fld.rank.clone());
-                }
-            }
-            None
+        let target = flow
+            .get_step(&target_step)
+            .ok_or_else(|| DomainError::not_found("Step", target_step.to_string()))?;
+
+        let siblings: Vec<FieldId> = target.fields.iter().map(|f| f.id).collect();
+        check_neighbours(
+            "Field",
+            field_id,
+            &siblings,
+            &format!("step {target_step}"),
+            after_id,
+            before_id,
+        )?;
+        let after = after_id.and_then(|id| target.get_field(&id));
+        let before_field = before_id.and_then(|id| target.get_field(&id));
+
+        // Without a neighbour, the field goes to the end of the target step.
+        let after = match (after, before_field) {
+            (None, None) => target.fields.iter().rev().find(|f| f.id != field_id),
+            _ => after,
         };
 
-        let after_rank = after_id.and_then(find_field_rank);
-        let before_rank = before_id.and_then(find_field_rank);
-
-        let new_rank = match (after_rank, before_rank) {
-            (Some(a), Some(b)) => {
-                self.rank_service.between(&Rank::from_string(a), &Rank::from_string(b))
-            }
-            (Some(a), None) => self.rank_service.after(&Rank::from_string(a)),
-            (None, Some(b)) => self.rank_service.before(&Rank::from_string(b)),
-            (None, None) => {
-                if let Ok(ts) = self.step_repo.get_step(target_step).await {
-                    if let Some(last) = ts.fields.get(ts.fields.len().saturating_sub(1)) {
-                        self.rank_service.after(&Rank::from_string(last.rank.clone()))
-                    } else {
-                        self.rank_service.initial()
-                    }
-                } else {
-                    self.rank_service.initial()
-                }
-            }
-        };
-
-        self.field_repo.delete_field(field_id).await?;
-
-        let new_field = Field::with_id(
-            field.id,
-            field.key,
-            field.label,
-            field.description,
-            new_rank.as_str().to_string(),
-            field.config,
-        );
+        let new_rank = self
+            .field_rank(
+                &repos,
+                organization,
+                actor,
+                target_step,
+                after,
+                before_field,
+            )
+            .await?;
 
-        self.field_repo.create_field(target_step, new_field).await?;
+        let moved_field = repos
+            .fields
+            .move_field(field_id, target_step, new_rank)
+            .await?;
 
-        self.flow_repo.get_flow(flow.id).await
+        let moved = repos.flows.get_flow(organization, flow.id).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Field,
+            field_id,
+            Some(&field),
+            Some(&moved_field),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(moved)
     }
 
     async fn search_flow_fields(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
         query: Option<String>,
     ) -> Result<Vec<Field>, DomainError> {
+        self.flow_repo.get_flow(organization, flow_id).await?;
         self.field_repo.get_flow_fields(flow_id, query).await
     }
 }
@@ -1,5 +1,13 @@
+pub mod api_key;
+pub mod audit;
+pub mod authorization;
+pub mod customer;
 pub mod error;
 pub mod estimator;
 pub mod flows;
+pub mod organization;
+pub mod quote;
 pub mod rank;
+pub mod runner;
+pub mod transaction;
 pub use error::DomainError;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Identifier of an organization (tenant).
+///
+/// Organizations are managed by the identity provider: the id is read from a claim of
+/// the caller's token, never chosen by the client. Flows, estimators, quotes and share
+/// links all belong to exactly one organization and are invisible to the others.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OrganizationId(String);
+
+impl OrganizationId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl std::fmt::Display for OrganizationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod diff;
+pub mod ids;
+pub mod quote;
+pub mod status;
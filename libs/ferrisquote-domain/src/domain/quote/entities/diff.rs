@@ -0,0 +1,181 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ids::QuoteId, quote::Quote};
+
+/// A keyed value that differs between two revisions.
+///
+/// `before` is `None` when the key was added, `after` is `None` when it was
+/// removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange<T> {
+    pub key: String,
+    pub before: Option<T>,
+    pub after: Option<T>,
+}
+
+/// Structured difference between two revisions of the same quote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteDiff {
+    pub from_id: QuoteId,
+    pub to_id: QuoteId,
+    pub from_revision: u32,
+    pub to_revision: u32,
+    /// Scalar answers (`field_values`) that changed.
+    pub changed_answers: Vec<ValueChange<f64>>,
+    /// Repeatable-step answers (`iteration_values`) that changed.
+    pub changed_iteration_answers: Vec<ValueChange<Vec<f64>>>,
+    /// Evaluated estimator variables that changed.
+    pub changed_line_items: Vec<ValueChange<f64>>,
+    pub total_before: f64,
+    pub total_after: f64,
+    pub total_delta: f64,
+}
+
+impl QuoteDiff {
+    /// Compare `from` to `to`. Changes are sorted by key.
+    pub fn between(from: &Quote, to: &Quote) -> Self {
+        Self {
+            from_id: from.id,
+            to_id: to.id,
+            from_revision: from.revision,
+            to_revision: to.revision,
+            changed_answers: diff_maps(&from.submission.field_values, &to.submission.field_values),
+            changed_iteration_answers: diff_maps(
+                &from.submission.iteration_values,
+                &to.submission.iteration_values,
+            ),
+            changed_line_items: diff_maps(&from.results, &to.results),
+            total_before: from.total,
+            total_after: to.total,
+            total_delta: to.total - from.total,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed_answers.is_empty()
+            && self.changed_iteration_answers.is_empty()
+            && self.changed_line_items.is_empty()
+            && self.total_delta == 0.0
+    }
+}
+
+fn diff_maps<T: Clone + PartialEq>(
+    before: &HashMap<String, T>,
+    after: &HashMap<String, T>,
+) -> Vec<ValueChange<T>> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let b = before.get(key);
+            let a = after.get(key);
+            (b != a).then(|| ValueChange {
+                key: key.clone(),
+                before: b.cloned(),
+                after: a.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+        flows::entities::ids::FlowId,
+        organization::entities::ids::OrganizationId,
+    };
+
+    fn make_quote(field_values: &[(&str, f64)], results: &[(&str, f64)], total: f64) -> Quote {
+        Quote::new(
+            OrganizationId::new("acme"),
+            FlowId::new(),
+            EstimatorId::new(),
+            None,
+            SubmissionData {
+                field_values: field_values
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), *v))
+                    .collect(),
+                ..Default::default()
+            },
+            results.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            total,
+            30,
+        )
+    }
+
+    #[test]
+    fn test_identical_revisions_have_empty_diff() {
+        let from = make_quote(&[("surface", 50.0)], &[("total", 500.0)], 500.0);
+        let to = from.new_revision(2);
+
+        let diff = QuoteDiff::between(&from, &to);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.from_revision, 1);
+        assert_eq!(diff.to_revision, 2);
+    }
+
+    #[test]
+    fn test_diff_reports_changed_added_and_removed_keys() {
+        let from = make_quote(
+            &[("surface", 50.0), ("floors", 2.0)],
+            &[("ht", 500.0), ("total", 600.0)],
+            600.0,
+        );
+        let to = make_quote(
+            &[("surface", 60.0), ("rooms", 4.0)],
+            &[("ht", 600.0), ("total", 720.0)],
+            720.0,
+        );
+
+        let diff = QuoteDiff::between(&from, &to);
+
+        assert_eq!(
+            diff.changed_answers,
+            vec![
+                ValueChange {
+                    key: "floors".into(),
+                    before: Some(2.0),
+                    after: None
+                },
+                ValueChange {
+                    key: "rooms".into(),
+                    before: None,
+                    after: Some(4.0)
+                },
+                ValueChange {
+                    key: "surface".into(),
+                    before: Some(50.0),
+                    after: Some(60.0)
+                },
+            ]
+        );
+        assert_eq!(diff.changed_line_items.len(), 2);
+        assert_eq!(diff.total_delta, 120.0);
+    }
+
+    #[test]
+    fn test_diff_compares_iteration_answers() {
+        let mut from = make_quote(&[], &[], 0.0);
+        from.submission
+            .iteration_values
+            .insert("room_surface".into(), vec![10.0, 12.0]);
+        let mut to = from.new_revision(2);
+        to.submission
+            .iteration_values
+            .insert("room_surface".into(), vec![10.0, 12.0, 8.0]);
+
+        let diff = QuoteDiff::between(&from, &to);
+
+        assert_eq!(diff.changed_iteration_answers.len(), 1);
+        assert_eq!(
+            diff.changed_iteration_answers[0].after,
+            Some(vec![10.0, 12.0, 8.0])
+        );
+    }
+}
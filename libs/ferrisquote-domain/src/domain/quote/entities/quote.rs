@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    customer::entities::ids::CustomerId,
+    error::DomainError,
+    estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+};
+
+use super::{
+    ids::QuoteId,
+    status::{QuoteStatus, QuoteStatusChange},
+};
+
+/// Number of days a sent quote stays valid when no explicit period is given.
+pub const DEFAULT_VALIDITY_DAYS: u32 = 30;
+
+/// A Quote is the priced outcome of a submission evaluated by an Estimator.
+///
+/// The submitted answers and the evaluated results are frozen on the quote so
+/// later edits to the flow or the estimator do not alter what was issued. The
+/// validity period only starts once the quote is sent.
+///
+/// Reissuing a quote creates a new revision sharing the same `root_id` (the id
+/// of the first revision), with `revision` numbered from 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quote {
+    pub id: QuoteId,
+    pub root_id: QuoteId,
+    pub revision: u32,
+    pub organization_id: OrganizationId,
+    pub flow_id: FlowId,
+    pub estimator_id: EstimatorId,
+    pub customer_id: Option<CustomerId>,
+    pub status: QuoteStatus,
+    pub submission: SubmissionData,
+    pub results: HashMap<String, f64>,
+    /// Value of the estimator's last variable (by rank) at evaluation time.
+    pub total: f64,
+    pub validity_days: u32,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Quote {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        organization_id: OrganizationId,
+        flow_id: FlowId,
+        estimator_id: EstimatorId,
+        customer_id: Option<CustomerId>,
+        submission: SubmissionData,
+        results: HashMap<String, f64>,
+        total: f64,
+        validity_days: u32,
+    ) -> Self {
+        let id = QuoteId::new();
+        let now = Utc::now();
+        Self {
+            id,
+            root_id: id,
+            revision: 1,
+            organization_id,
+            flow_id,
+            estimator_id,
+            customer_id,
+            status: QuoteStatus::Draft,
+            submission,
+            results,
+            total,
+            validity_days,
+            valid_until: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Copy this quote into a new draft revision numbered `revision`.
+    ///
+    /// The submission and results are carried over so the copy can be edited
+    /// and re-evaluated without touching the quote it was made from.
+    pub fn new_revision(&self, revision: u32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: QuoteId::new(),
+            root_id: self.root_id,
+            revision,
+            organization_id: self.organization_id.clone(),
+            flow_id: self.flow_id,
+            estimator_id: self.estimator_id,
+            customer_id: self.customer_id,
+            status: QuoteStatus::Draft,
+            submission: self.submission.clone(),
+            results: self.results.clone(),
+            total: self.total,
+            validity_days: self.validity_days,
+            valid_until: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Replace the submission and its evaluated results.
+    ///
+    /// Only drafts can be edited: once sent, a quote is changed by issuing a
+    /// new revision instead.
+    pub fn revise(
+        &mut self,
+        submission: SubmissionData,
+        results: HashMap<String, f64>,
+        total: f64,
+        at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        if self.status != QuoteStatus::Draft {
+            return Err(DomainError::conflict(format!(
+                "Quote {} is {} and can no longer be edited",
+                self.id, self.status
+            )));
+        }
+
+        self.submission = submission;
+        self.results = results;
+        self.total = total;
+        self.updated_at = at;
+
+        Ok(())
+    }
+
+    /// Move the quote to `next`, enforcing the status state machine.
+    ///
+    /// Sending the quote starts its validity period, and an overdue quote can
+    /// no longer be accepted or rejected: it can only expire. Returns the
+    /// history entry describing the change so it can be persisted alongside
+    /// the quote.
+    pub fn transition_to(
+        &mut self,
+        next: QuoteStatus,
+        at: DateTime<Utc>,
+        reason: Option<String>,
+    ) -> Result<QuoteStatusChange, DomainError> {
+        if !self.status.can_transition_to(next) {
+            return Err(DomainError::invalid_transition(
+                "Quote",
+                self.status.as_str(),
+                next.as_str(),
+            ));
+        }
+        if matches!(next, QuoteStatus::Accepted | QuoteStatus::Rejected) && self.is_overdue(at) {
+            return Err(DomainError::conflict(format!(
+                "Quote {} is past its validity period and can no longer be {next}",
+                self.id
+            )));
+        }
+
+        let change = QuoteStatusChange {
+            quote_id: self.id,
+            from: self.status,
+            to: next,
+            reason,
+            changed_at: at,
+        };
+
+        if next == QuoteStatus::Sent {
+            self.valid_until = Some(at + Duration::days(i64::from(self.validity_days)));
+        }
+        self.status = next;
+        self.updated_at = at;
+
+        Ok(change)
+    }
+
+    /// A sent quote is overdue once its validity period has elapsed.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.status == QuoteStatus::Sent && self.valid_until.is_some_and(|until| until <= now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_quote(validity_days: u32) -> Quote {
+        Quote::new(
+            OrganizationId::new("acme"),
+            FlowId::new(),
+            EstimatorId::new(),
+            None,
+            SubmissionData::default(),
+            HashMap::from([("total".to_string(), 100.0)]),
+            100.0,
+            validity_days,
+        )
+    }
+
+    #[test]
+    fn test_new_quote_is_draft_without_expiry() {
+        let quote = make_quote(DEFAULT_VALIDITY_DAYS);
+        assert_eq!(quote.status, QuoteStatus::Draft);
+        assert!(quote.valid_until.is_none());
+        assert!(!quote.is_overdue(Utc::now() + Duration::days(365)));
+    }
+
+    #[test]
+    fn test_sending_starts_validity_period() {
+        let mut quote = make_quote(15);
+        let sent_at = Utc::now();
+
+        let change = quote
+            .transition_to(QuoteStatus::Sent, sent_at, None)
+            .unwrap();
+
+        assert_eq!(change.from, QuoteStatus::Draft);
+        assert_eq!(change.to, QuoteStatus::Sent);
+        assert_eq!(quote.status, QuoteStatus::Sent);
+        assert_eq!(quote.valid_until, Some(sent_at + Duration::days(15)));
+        assert!(!quote.is_overdue(sent_at + Duration::days(14)));
+        assert!(quote.is_overdue(sent_at + Duration::days(15)));
+    }
+
+    #[test]
+    fn test_sent_quote_can_be_accepted_rejected_or_expired() {
+        for next in [
+            QuoteStatus::Accepted,
+            QuoteStatus::Rejected,
+            QuoteStatus::Expired,
+            QuoteStatus::Superseded,
+        ] {
+            let mut quote = make_quote(DEFAULT_VALIDITY_DAYS);
+            quote
+                .transition_to(QuoteStatus::Sent, Utc::now(), None)
+                .unwrap();
+            assert!(quote.transition_to(next, Utc::now(), None).is_ok());
+            assert!(quote.status.is_terminal());
+            assert!(!quote.is_overdue(Utc::now() + Duration::days(365)));
+        }
+    }
+
+    #[test]
+    fn test_overdue_quote_can_only_expire() {
+        for next in [QuoteStatus::Accepted, QuoteStatus::Rejected] {
+            let mut quote = make_quote(15);
+            let sent_at = Utc::now();
+            quote
+                .transition_to(QuoteStatus::Sent, sent_at, None)
+                .unwrap();
+
+            let result = quote.transition_to(next, sent_at + Duration::days(15), None);
+            assert!(matches!(result, Err(DomainError::Conflict { .. })));
+            assert_eq!(quote.status, QuoteStatus::Sent);
+
+            assert!(
+                quote
+                    .transition_to(next, sent_at + Duration::days(14), None)
+                    .is_ok()
+            );
+        }
+
+        let mut quote = make_quote(15);
+        let sent_at = Utc::now();
+        quote
+            .transition_to(QuoteStatus::Sent, sent_at, None)
+            .unwrap();
+        assert!(
+            quote
+                .transition_to(QuoteStatus::Expired, sent_at + Duration::days(15), None)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_draft_cannot_skip_sending() {
+        for next in [
+            QuoteStatus::Accepted,
+            QuoteStatus::Rejected,
+            QuoteStatus::Expired,
+        ] {
+            let mut quote = make_quote(DEFAULT_VALIDITY_DAYS);
+            let result = quote.transition_to(next, Utc::now(), None);
+            assert!(matches!(result, Err(DomainError::InvalidTransition { .. })));
+            assert_eq!(quote.status, QuoteStatus::Draft);
+        }
+    }
+
+    #[test]
+    fn test_terminal_statuses_are_final() {
+        let mut quote = make_quote(DEFAULT_VALIDITY_DAYS);
+        quote
+            .transition_to(QuoteStatus::Sent, Utc::now(), None)
+            .unwrap();
+        quote
+            .transition_to(QuoteStatus::Accepted, Utc::now(), None)
+            .unwrap();
+
+        for next in [
+            QuoteStatus::Draft,
+            QuoteStatus::Sent,
+            QuoteStatus::Rejected,
+            QuoteStatus::Expired,
+        ] {
+            let result = quote.transition_to(next, Utc::now(), None);
+            assert!(matches!(result, Err(DomainError::InvalidTransition { .. })));
+        }
+        assert_eq!(quote.status, QuoteStatus::Accepted);
+    }
+
+    #[test]
+    fn test_status_round_trips_through_str() {
+        for status in [
+            QuoteStatus::Draft,
+            QuoteStatus::Sent,
+            QuoteStatus::Accepted,
+            QuoteStatus::Rejected,
+            QuoteStatus::Expired,
+            QuoteStatus::Superseded,
+        ] {
+            assert_eq!(status.as_str().parse::<QuoteStatus>().unwrap(), status);
+        }
+        assert!("unknown".parse::<QuoteStatus>().is_err());
+    }
+
+    #[test]
+    fn test_new_revision_copies_quote_as_draft() {
+        let mut quote = make_quote(15);
+        quote
+            .transition_to(QuoteStatus::Sent, Utc::now(), None)
+            .unwrap();
+
+        let revision = quote.new_revision(2);
+
+        assert_ne!(revision.id, quote.id);
+        assert_eq!(revision.root_id, quote.id);
+        assert_eq!(revision.revision, 2);
+        assert_eq!(revision.status, QuoteStatus::Draft);
+        assert!(revision.valid_until.is_none());
+        assert_eq!(revision.results, quote.results);
+        assert_eq!(revision.total, quote.total);
+        assert_eq!(revision.validity_days, 15);
+    }
+
+    #[test]
+    fn test_only_drafts_can_be_revised() {
+        let mut quote = make_quote(DEFAULT_VALIDITY_DAYS);
+        let results = HashMap::from([("total".to_string(), 120.0)]);
+        quote
+            .revise(
+                SubmissionData::default(),
+                results.clone(),
+                120.0,
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!(quote.total, 120.0);
+
+        quote
+            .transition_to(QuoteStatus::Sent, Utc::now(), None)
+            .unwrap();
+        let result = quote.revise(SubmissionData::default(), results, 130.0, Utc::now());
+        assert!(matches!(result, Err(DomainError::Conflict { .. })));
+        assert_eq!(quote.total, 120.0);
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+use super::ids::QuoteId;
+
+/// Lifecycle state of a quote.
+///
+/// ```text
+/// Draft ──► Sent ──┬──► Accepted
+///                  ├──► Rejected
+///                  ├──► Expired
+///                  └──► Superseded
+/// ```
+///
+/// `Accepted`, `Rejected`, `Expired` and `Superseded` are terminal. A sent
+/// quote is superseded when a newer revision of it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    Draft,
+    Sent,
+    Accepted,
+    Rejected,
+    Expired,
+    Superseded,
+}
+
+impl QuoteStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteStatus::Draft => "draft",
+            QuoteStatus::Sent => "sent",
+            QuoteStatus::Accepted => "accepted",
+            QuoteStatus::Rejected => "rejected",
+            QuoteStatus::Expired => "expired",
+            QuoteStatus::Superseded => "superseded",
+        }
+    }
+
+    /// Whether the state machine allows moving from `self` to `next`.
+    pub fn can_transition_to(&self, next: QuoteStatus) -> bool {
+        use QuoteStatus::*;
+
+        matches!(
+            (self, next),
+            (Draft, Sent)
+                | (Sent, Accepted)
+                | (Sent, Rejected)
+                | (Sent, Expired)
+                | (Sent, Superseded)
+        )
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            QuoteStatus::Accepted
+                | QuoteStatus::Rejected
+                | QuoteStatus::Expired
+                | QuoteStatus::Superseded
+        )
+    }
+}
+
+impl std::fmt::Display for QuoteStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for QuoteStatus {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(QuoteStatus::Draft),
+            "sent" => Ok(QuoteStatus::Sent),
+            "accepted" => Ok(QuoteStatus::Accepted),
+            "rejected" => Ok(QuoteStatus::Rejected),
+            "expired" => Ok(QuoteStatus::Expired),
+            "superseded" => Ok(QuoteStatus::Superseded),
+            other => Err(DomainError::validation(format!(
+                "Unknown quote status '{other}'"
+            ))),
+        }
+    }
+}
+
+/// One entry of a quote's status history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteStatusChange {
+    pub quote_id: QuoteId,
+    pub from: QuoteStatus,
+    pub to: QuoteStatus,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
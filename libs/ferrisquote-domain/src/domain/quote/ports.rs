@@ -0,0 +1,185 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    audit::entities::actor::Actor,
+    customer::entities::ids::CustomerId,
+    error::DomainError,
+    estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+};
+
+use super::entities::{
+    diff::QuoteDiff,
+    ids::QuoteId,
+    quote::Quote,
+    status::{QuoteStatus, QuoteStatusChange},
+};
+
+/// Repository trait for Quote persistence.
+///
+/// Quotes belong to the organization of their estimator. Lookups taking an
+/// `organization` must report quotes of other organizations as `DomainError::NotFound`;
+/// writes are scoped by `quote.organization_id`.
+pub trait QuoteRepository: Send + Sync {
+    fn create_quote(&self, quote: Quote)
+    -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    fn get_quote(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    fn list_quotes_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    fn list_quotes_for_customer(
+        &self,
+        organization: &OrganizationId,
+        customer_id: CustomerId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    /// Persist an edited draft (submission, results, total, validity period).
+    ///
+    /// Must fail with `DomainError::Conflict` if the stored quote is no longer
+    /// a draft.
+    fn update_quote(&self, quote: Quote)
+    -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    /// All revisions sharing `root_id`, ordered by revision number.
+    fn list_revisions(
+        &self,
+        organization: &OrganizationId,
+        root_id: QuoteId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    /// Persist the status carried by `quote` and append `change` to its history.
+    ///
+    /// The write must only succeed while the stored status still equals
+    /// `change.from`; otherwise a `DomainError::Conflict` is returned so that
+    /// concurrent transitions (e.g. the expiry scheduler racing an acceptance)
+    /// cannot both win.
+    fn apply_status_change(
+        &self,
+        quote: Quote,
+        change: QuoteStatusChange,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    /// Status history of a quote, oldest first.
+    fn list_status_history(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Vec<QuoteStatusChange>, DomainError>> + Send;
+
+    /// Sent quotes whose validity period ended at or before `now`.
+    fn list_overdue_quotes(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+}
+
+/// Service trait for Quote domain logic.
+///
+/// Every operation but the expiry sweep acts on behalf of `organization`.
+/// Changes are recorded under `actor` in the audit log; the expiry sweep
+/// records them under the system actor.
+pub trait QuoteService: Send + Sync {
+    /// Evaluate a submission with an estimator and store the result as a draft quote.
+    ///
+    /// When `customer_id` is set the quote is linked to that customer and the
+    /// customer's attributes are available to the estimator expressions.
+    fn create_quote(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        estimator_id: EstimatorId,
+        submission: SubmissionData,
+        customer_id: Option<CustomerId>,
+        validity_days: Option<u32>,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    fn get_quote(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    fn list_quotes_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    fn list_quotes_for_customer(
+        &self,
+        organization: &OrganizationId,
+        customer_id: CustomerId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    /// Replace the answers of a draft quote and re-evaluate it.
+    fn update_quote_submission(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+        submission: SubmissionData,
+        validity_days: Option<u32>,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    /// Copy a quote into a new draft revision of the same quote.
+    fn create_revision(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    /// All revisions of the quote `id` belongs to, oldest first.
+    fn list_revisions(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+
+    /// Structured difference from revision `from_id` to revision `to_id`.
+    fn diff_revisions(
+        &self,
+        organization: &OrganizationId,
+        from_id: QuoteId,
+        to_id: QuoteId,
+    ) -> impl Future<Output = Result<QuoteDiff, DomainError>> + Send;
+
+    /// Move a quote to another status, enforcing the state machine.
+    ///
+    /// Sending a revision supersedes any previously sent revision of the same
+    /// quote. A sent quote past its validity period can no longer be accepted
+    /// or rejected (`DomainError::Conflict`); it is left for the expiry sweep.
+    fn transition_quote(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+        status: QuoteStatus,
+    ) -> impl Future<Output = Result<Quote, DomainError>> + Send;
+
+    fn get_quote_history(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> impl Future<Output = Result<Vec<QuoteStatusChange>, DomainError>> + Send;
+
+    /// Expire every sent quote whose validity period has elapsed, in every organization.
+    ///
+    /// Returns the quotes that were expired by this call.
+    fn expire_overdue_quotes(
+        &self,
+        now: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<Quote>, DomainError>> + Send;
+}
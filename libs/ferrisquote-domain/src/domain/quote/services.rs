@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    audit::{
+        entities::{actor::Actor, entry::AuditEntity},
+        ports::AuditRepository,
+        services::record,
+    },
+    customer::{
+        entities::{customer::Customer, ids::CustomerId},
+        ports::CustomerRepository,
+    },
+    error::DomainError,
+    estimator::{
+        entities::{estimator::Estimator, ids::EstimatorId, submission::SubmissionData},
+        ports::EstimatorRepository,
+        services::evaluate_estimator_for_customer,
+    },
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+    transaction::ports::{Transaction, Transactional, UnitOfWork},
+};
+
+use super::{
+    entities::{
+        diff::QuoteDiff,
+        ids::QuoteId,
+        quote::{DEFAULT_VALIDITY_DAYS, Quote},
+        status::{QuoteStatus, QuoteStatusChange},
+    },
+    ports::{QuoteRepository, QuoteService},
+};
+
+/// Name of the system actor the expiry sweep records its changes under.
+pub const EXPIRY_ACTOR: &str = "quote-expiry";
+
+#[derive(Clone)]
+pub struct QuoteServiceImpl<QR, ER, CR, AR, UW> {
+    quote_repo: QR,
+    estimator_repo: ER,
+    customer_repo: CR,
+    audit_repo: AR,
+    unit_of_work: UW,
+}
+
+impl<QR, ER, CR, AR, UW> QuoteServiceImpl<QR, ER, CR, AR, UW> {
+    pub fn new(
+        quote_repo: QR,
+        estimator_repo: ER,
+        customer_repo: CR,
+        audit_repo: AR,
+        unit_of_work: UW,
+    ) -> Self {
+        Self {
+            quote_repo,
+            estimator_repo,
+            customer_repo,
+            audit_repo,
+            unit_of_work,
+        }
+    }
+}
+
+/// The repositories of a `QuoteServiceImpl` that write, bound to one transaction.
+struct Repositories<QR, AR> {
+    quotes: QR,
+    audit: AR,
+}
+
+impl<QR, ER, CR, AR, UW> QuoteServiceImpl<QR, ER, CR, AR, UW>
+where
+    QR: Transactional<UW::Transaction>,
+    AR: Transactional<UW::Transaction>,
+    UW: UnitOfWork,
+{
+    /// Open a transaction and the repositories writing through it.
+    ///
+    /// A change and its audit entry are committed together, or not at all. So
+    /// are the revisions superseded by sending a newer one.
+    async fn begin(&self) -> Result<(UW::Transaction, Repositories<QR, AR>), DomainError> {
+        let transaction = self.unit_of_work.begin().await?;
+        let repositories = Repositories {
+            quotes: self.quote_repo.in_transaction(&transaction),
+            audit: self.audit_repo.in_transaction(&transaction),
+        };
+        Ok((transaction, repositories))
+    }
+}
+
+impl<QR, ER, CR, AR, UW> QuoteServiceImpl<QR, ER, CR, AR, UW>
+where
+    ER: EstimatorRepository + Send + Sync,
+    CR: CustomerRepository + Send + Sync,
+{
+    /// Evaluate `submission` with the estimator, returning the results and the total.
+    ///
+    /// The customer must belong to `organization`.
+    async fn evaluate(
+        &self,
+        organization: &OrganizationId,
+        estimator: &Estimator,
+        submission: &SubmissionData,
+        customer_id: Option<CustomerId>,
+    ) -> Result<(HashMap<String, f64>, f64), DomainError> {
+        let customer: Option<Customer> = match customer_id {
+            Some(id) => Some(self.customer_repo.get_customer(organization, id).await?),
+            None => None,
+        };
+        let results = evaluate_estimator_for_customer(estimator, submission, customer.as_ref())?;
+        let total = estimator.total(&results);
+        Ok((results, total))
+    }
+}
+
+impl<QR, ER, CR, AR, UW> QuoteService for QuoteServiceImpl<QR, ER, CR, AR, UW>
+where
+    QR: QuoteRepository + Transactional<UW::Transaction> + Send + Sync,
+    ER: EstimatorRepository + Send + Sync,
+    CR: CustomerRepository + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
+{
+    async fn create_quote(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        estimator_id: EstimatorId,
+        submission: SubmissionData,
+        customer_id: Option<CustomerId>,
+        validity_days: Option<u32>,
+    ) -> Result<Quote, DomainError> {
+        let estimator = self
+            .estimator_repo
+            .get_estimator(organization, estimator_id)
+            .await?;
+        let (results, total) = self
+            .evaluate(organization, &estimator, &submission, customer_id)
+            .await?;
+
+        let (transaction, repos) = self.begin().await?;
+
+        let quote = Quote::new(
+            estimator.organization_id.clone(),
+            estimator.flow_id,
+            estimator.id,
+            customer_id,
+            submission,
+            results,
+            total,
+            validity_days.unwrap_or(DEFAULT_VALIDITY_DAYS),
+        );
+        let quote = repos.quotes.create_quote(quote).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Quote,
+            quote.id,
+            None,
+            Some(&quote),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(quote)
+    }
+
+    async fn get_quote(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Quote, DomainError> {
+        self.quote_repo.get_quote(organization, id).await
+    }
+
+    async fn list_quotes_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        self.quote_repo
+            .list_quotes_for_flow(organization, flow_id)
+            .await
+    }
+
+    async fn list_quotes_for_customer(
+        &self,
+        organization: &OrganizationId,
+        customer_id: CustomerId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        self.quote_repo
+            .list_quotes_for_customer(organization, customer_id)
+            .await
+    }
+
+    async fn update_quote_submission(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+        submission: SubmissionData,
+        validity_days: Option<u32>,
+    ) -> Result<Quote, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let mut quote = repos.quotes.get_quote(organization, id).await?;
+        let before = quote.clone();
+        let estimator = self
+            .estimator_repo
+            .get_estimator(organization, quote.estimator_id)
+            .await?;
+        let (results, total) = self
+            .evaluate(organization, &estimator, &submission, quote.customer_id)
+            .await?;
+
+        quote.revise(submission, results, total, Utc::now())?;
+        if let Some(days) = validity_days {
+            quote.validity_days = days;
+        }
+        let quote = repos.quotes.update_quote(quote).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Quote,
+            id,
+            Some(&before),
+            Some(&quote),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(quote)
+    }
+
+    async fn create_revision(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+    ) -> Result<Quote, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let quote = repos.quotes.get_quote(organization, id).await?;
+        let revisions = repos
+            .quotes
+            .list_revisions(organization, quote.root_id)
+            .await?;
+
+        if let Some(draft) = revisions.iter().find(|q| q.status == QuoteStatus::Draft) {
+            return Err(DomainError::conflict(format!(
+                "Revision {} of quote {} is still a draft",
+                draft.revision, quote.root_id
+            )));
+        }
+
+        let next = revisions
+            .iter()
+            .map(|q| q.revision)
+            .max()
+            .unwrap_or(quote.revision)
+            + 1;
+        let revision = repos.quotes.create_quote(quote.new_revision(next)).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Quote,
+            revision.id,
+            None,
+            Some(&revision),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(revision)
+    }
+
+    async fn list_revisions(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let quote = self.quote_repo.get_quote(organization, id).await?;
+        self.quote_repo
+            .list_revisions(organization, quote.root_id)
+            .await
+    }
+
+    async fn diff_revisions(
+        &self,
+        organization: &OrganizationId,
+        from_id: QuoteId,
+        to_id: QuoteId,
+    ) -> Result<QuoteDiff, DomainError> {
+        let from = self.quote_repo.get_quote(organization, from_id).await?;
+        let to = self.quote_repo.get_quote(organization, to_id).await?;
+
+        if from.root_id != to.root_id {
+            return Err(DomainError::validation(format!(
+                "Quotes {} and {} are not revisions of the same quote",
+                from.id, to.id
+            )));
+        }
+
+        Ok(QuoteDiff::between(&from, &to))
+    }
+
+    async fn transition_quote(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: QuoteId,
+        status: QuoteStatus,
+    ) -> Result<Quote, DomainError> {
+        let (transaction, repos) = self.begin().await?;
+        let mut quote = repos.quotes.get_quote(organization, id).await?;
+        let before = quote.clone();
+        let now = Utc::now();
+        let change = quote.transition_to(status, now, None)?;
+        let quote = repos.quotes.apply_status_change(quote, change).await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::Quote,
+            id,
+            Some(&before),
+            Some(&quote),
+        )
+        .await?;
+
+        if quote.status == QuoteStatus::Sent {
+            let reason = format!("superseded by revision {}", quote.revision);
+            for mut previous in repos
+                .quotes
+                .list_revisions(organization, quote.root_id)
+                .await?
+            {
+                if previous.id == quote.id || previous.status != QuoteStatus::Sent {
+                    continue;
+                }
+                let sent = previous.clone();
+                let change =
+                    previous.transition_to(QuoteStatus::Superseded, now, Some(reason.clone()))?;
+                match repos.quotes.apply_status_change(previous, change).await {
+                    Ok(superseded) => {
+                        record(
+                            &repos.audit,
+                            organization,
+                            actor,
+                            AuditEntity::Quote,
+                            superseded.id,
+                            Some(&sent),
+                            Some(&superseded),
+                        )
+                        .await?
+                    }
+                    Err(DomainError::Conflict { .. }) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(quote)
+    }
+
+    async fn get_quote_history(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Vec<QuoteStatusChange>, DomainError> {
+        self.quote_repo.list_status_history(organization, id).await
+    }
+
+    async fn expire_overdue_quotes(&self, now: DateTime<Utc>) -> Result<Vec<Quote>, DomainError> {
+        let overdue = self.quote_repo.list_overdue_quotes(now).await?;
+        let actor = Actor::system(EXPIRY_ACTOR);
+
+        let mut expired = Vec::with_capacity(overdue.len());
+        for mut quote in overdue {
+            if !quote.is_overdue(now) {
+                continue;
+            }
+            let sent = quote.clone();
+            let change = quote.transition_to(
+                QuoteStatus::Expired,
+                now,
+                Some("validity period elapsed".to_string()),
+            )?;
+            let (transaction, repos) = self.begin().await?;
+            match repos.quotes.apply_status_change(quote, change).await {
+                Ok(quote) => {
+                    record(
+                        &repos.audit,
+                        &quote.organization_id,
+                        &actor,
+                        AuditEntity::Quote,
+                        quote.id,
+                        Some(&sent),
+                        Some(&quote),
+                    )
+                    .await?;
+                    transaction.commit().await?;
+                    expired.push(quote);
+                }
+                // Another transition (e.g. an acceptance) won the race: nothing to expire.
+                Err(DomainError::Conflict { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(expired)
+    }
+}
@@ -19,18 +19,18 @@ impl From<String> for Rank {
     }
 }
 
-impl Into<String> for Rank {
-    fn into(self) -> String {
-        self.0
+impl From<Rank> for String {
+    fn from(rank: Rank) -> Self {
+        rank.0
     }
 }
 
 /// Allow converting a reference to `Rank` into `&str`.
-/// We implement `Into<&str>` for `&Rank` (not for `Rank`) because converting
+/// We implement `From<&Rank>` (not `From<Rank>`) because converting
 /// a moved `Rank` into a borrowed `&str` would produce a dangling reference.
 /// Implementing for `&Rank` lets callers do `let s: &str = (&rank).into();`.
-impl<'a> Into<&'a str> for &'a Rank {
-    fn into(self) -> &'a str {
-        self.as_str()
+impl<'a> From<&'a Rank> for &'a str {
+    fn from(rank: &'a Rank) -> Self {
+        rank.as_str()
     }
 }
@@ -1,8 +1,14 @@
-use crate::domain::rank::entities::Rank;
+use crate::domain::{error::DomainError, rank::entities::Rank};
 
 pub trait RankService: Send + Sync {
-    fn initial(&self) -> Rank;
-    fn between(&self, before: &Rank, after: &Rank) -> Rank;
-    fn after(&self, rank: &Rank) -> Rank;
-    fn before(&self, rank: &Rank) -> Rank;
+    fn initial(&self) -> Result<Rank, DomainError>;
+    /// A rank strictly between `before` and `after`, which must differ.
+    fn between(&self, before: &Rank, after: &Rank) -> Result<Rank, DomainError>;
+    fn after(&self, rank: &Rank) -> Result<Rank, DomainError>;
+    fn before(&self, rank: &Rank) -> Result<Rank, DomainError>;
+    /// Whether `rank` has grown long enough that its siblings should be rebalanced.
+    fn needs_rebalance(&self, rank: &Rank) -> bool;
+    /// `count` short, evenly spaced ranks in ascending order, used to rebalance
+    /// a list of siblings while keeping their order.
+    fn spread(&self, count: usize) -> Result<Vec<Rank>, DomainError>;
 }
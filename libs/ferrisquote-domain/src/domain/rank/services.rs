@@ -1,38 +1,261 @@
+use crate::domain::error::DomainError;
 use crate::domain::rank::entities::Rank;
 use crate::domain::rank::ports::RankService;
 
+/// Ranks longer than this are rebalanced. Every insertion between two adjacent
+/// ranks can add a character, and the rank columns hold at most 255.
+const REBALANCE_LENGTH: usize = 64;
+
+/// The bucket every rank is generated in.
+const BUCKET: u8 = 0;
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
 #[derive(Clone)]
 pub struct LexoRankProvider;
 
+fn parse(rank: &Rank) -> Result<lexorank::LexoRank, DomainError> {
+    // `LexoRank::from_string` panics on a value without a bucket separator.
+    if !rank.as_str().contains('|') {
+        return Err(DomainError::validation(format!(
+            "Invalid rank '{}': missing bucket",
+            rank.as_str()
+        )));
+    }
+    lexorank::LexoRank::from_string(rank.as_str())
+        .map_err(|e| DomainError::validation(format!("Invalid rank '{}': {e}", rank.as_str())))
+}
+
+fn bucket() -> Result<lexorank::Bucket, DomainError> {
+    lexorank::Bucket::new(BUCKET).map_err(|e| DomainError::internal(e.to_string()))
+}
+
+/// `value` in base 36, left-padded to `width` digits, without trailing zeros.
+fn encode(mut value: u128, width: usize) -> String {
+    let mut digits = vec![b'0'; width];
+    for digit in digits.iter_mut().rev() {
+        *digit = DIGITS[(value % 36) as usize];
+        value /= 36;
+    }
+    let end = digits.iter().rposition(|&d| d != b'0').map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&digits[..end]).into_owned()
+}
+
 impl RankService for LexoRankProvider {
-    fn initial(&self) -> Rank {
-        let bucket = lexorank::Bucket::new(0).expect("Invalid bucket");
-        let rank = lexorank::Rank::new("n").expect("Invalid initial rank value");
-        let lexo = lexorank::LexoRank::new(bucket, rank);
+    fn initial(&self) -> Result<Rank, DomainError> {
+        let rank = lexorank::Rank::new("n").map_err(|e| DomainError::internal(e.to_string()))?;
+        let lexo = lexorank::LexoRank::new(bucket()?, rank);
 
-        Rank::from_string(lexo.to_string())
+        Ok(Rank::from_string(lexo.to_string()))
     }
 
-    fn between(&self, before: &Rank, after: &Rank) -> Rank {
-        let before_lexo =
-            lexorank::LexoRank::from_string(before.as_str()).expect("Invalid `before` rank");
-        let after_lexo =
-            lexorank::LexoRank::from_string(after.as_str()).expect("Invalid `after` rank");
+    fn between(&self, before: &Rank, after: &Rank) -> Result<Rank, DomainError> {
+        let before_lexo = parse(before)?;
+        let after_lexo = parse(after)?;
+
+        let between = before_lexo.between(&after_lexo).ok_or_else(|| {
+            DomainError::conflict(format!(
+                "Cannot compute a rank between '{}' and '{}'",
+                before.as_str(),
+                after.as_str()
+            ))
+        })?;
 
-        let between = before_lexo
-            .between(&after_lexo)
-            .expect("Cannot calculate rank between these two ranks");
+        Ok(Rank::from_string(between.to_string()))
+    }
 
-        Rank::from_string(between.to_string())
+    fn after(&self, rank: &Rank) -> Result<Rank, DomainError> {
+        Ok(Rank::from_string(parse(rank)?.next().to_string()))
     }
 
-    fn after(&self, rank: &Rank) -> Rank {
-        let lexo = lexorank::LexoRank::from_string(rank.as_str()).expect("Invalid rank");
-        Rank::from_string(lexo.next().to_string())
+    fn before(&self, rank: &Rank) -> Result<Rank, DomainError> {
+        Ok(Rank::from_string(parse(rank)?.prev().to_string()))
     }
 
-    fn before(&self, rank: &Rank) -> Rank {
-        let lexo = lexorank::LexoRank::from_string(rank.as_str()).expect("Invalid rank");
-        Rank::from_string(lexo.prev().to_string())
+    fn needs_rebalance(&self, rank: &Rank) -> bool {
+        rank.as_str().len() > REBALANCE_LENGTH
+    }
+
+    fn spread(&self, count: usize) -> Result<Vec<Rank>, DomainError> {
+        // One digit more than needed to tell the ranks apart, so that each gap
+        // leaves room for 35 insertions before ranks start to grow.
+        let slots = count as u128 + 1;
+        let mut width = 1;
+        let mut space: u128 = 36;
+        while space < slots * 36 {
+            width += 1;
+            space = space
+                .checked_mul(36)
+                .ok_or_else(|| DomainError::internal(format!("Cannot spread {count} ranks")))?;
+        }
+
+        (1..slots)
+            .map(|i| {
+                let rank = lexorank::Rank::new(&encode(i * space / slots, width))
+                    .map_err(|e| DomainError::internal(e.to_string()))?;
+                Ok(Rank::from_string(
+                    lexorank::LexoRank::new(bucket()?, rank).to_string(),
+                ))
+            })
+            .collect()
+    }
+}
+
+/// The rank between `after` and `before`, where `None` stands for the start
+/// or the end of the list, and the list is empty when both are.
+///
+/// Fails when there is no room left between the two ranks, or when the new
+/// rank is long enough that its siblings should be rebalanced.
+pub(crate) fn rank_between<RS: RankService>(
+    rank_service: &RS,
+    after: Option<&str>,
+    before: Option<&str>,
+) -> Result<String, DomainError> {
+    let rank = |value: &str| Rank::from_string(value.to_string());
+    let new_rank = match (after, before) {
+        (Some(a), Some(b)) => rank_service.between(&rank(a), &rank(b)),
+        (Some(a), None) => rank_service.after(&rank(a)),
+        (None, Some(b)) => rank_service.before(&rank(b)),
+        (None, None) => rank_service.initial(),
+    }?;
+    if rank_service.needs_rebalance(&new_rank) {
+        return Err(DomainError::conflict(format!(
+            "Rank '{}' is too long",
+            new_rank.as_str()
+        )));
+    }
+    Ok(new_rank.into())
+}
+
+/// Short, evenly spaced ranks for the siblings `ids`, keeping their order.
+pub(crate) fn spread_ranks<RS: RankService, Id: Copy>(
+    rank_service: &RS,
+    ids: &[Id],
+) -> Result<Vec<(Id, String)>, DomainError> {
+    Ok(ids
+        .iter()
+        .zip(rank_service.spread(ids.len())?)
+        .map(|(id, rank)| (*id, rank.into()))
+        .collect())
+}
+
+/// Check that the neighbours `after` and `before` of `item` are among the
+/// `siblings` of `parent`, ordered by rank, and that `after` comes first.
+pub(crate) fn check_neighbours<Id: Copy + PartialEq + std::fmt::Display>(
+    entity: &str,
+    item: Id,
+    siblings: &[Id],
+    parent: &str,
+    after: Option<Id>,
+    before: Option<Id>,
+) -> Result<(), DomainError> {
+    let position = |id: Id| {
+        if id == item {
+            return Err(DomainError::validation(format!(
+                "{entity} {id} cannot be placed next to itself"
+            )));
+        }
+        siblings
+            .iter()
+            .position(|sibling| *sibling == id)
+            .ok_or_else(|| {
+                DomainError::validation(format!("{entity} {id} does not belong to {parent}"))
+            })
+    };
+    let after_position = after.map(position).transpose()?;
+    let before_position = before.map(position).transpose()?;
+
+    if let (Some(a), Some(b), Some(after), Some(before)) =
+        (after_position, before_position, after, before)
+        && a >= b
+    {
+        return Err(DomainError::validation(format!(
+            "{entity} {after} must come before {entity} {before}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(value: &str) -> Rank {
+        Rank::from_string(value.to_string())
+    }
+
+    #[test]
+    fn test_between_orders_the_new_rank() {
+        let provider = LexoRankProvider;
+        let (a, b) = (rank("0|n"), rank("0|o"));
+
+        let between = provider.between(&a, &b).unwrap();
+
+        assert!(a < between && between < b);
+    }
+
+    #[test]
+    fn test_between_equal_ranks_is_a_conflict() {
+        let result = LexoRankProvider.between(&rank("0|n"), &rank("0|n"));
+
+        assert!(matches!(result, Err(DomainError::Conflict { .. })));
+    }
+
+    #[test]
+    fn test_malformed_ranks_are_rejected_instead_of_panicking() {
+        for value in ["n", "0|", "x|n", "0|n0"] {
+            assert!(
+                matches!(
+                    LexoRankProvider.after(&rank(value)),
+                    Err(DomainError::ValidationError { .. })
+                ),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeated_insertions_end_up_needing_a_rebalance() {
+        let provider = LexoRankProvider;
+        let first = provider.initial().unwrap();
+        let mut last = provider.after(&first).unwrap();
+
+        let mut insertions = 0;
+        while !provider.needs_rebalance(&last) {
+            last = provider.between(&first, &last).unwrap();
+            insertions += 1;
+        }
+
+        assert!(insertions > 10);
+        assert!(last.as_str().len() < 255);
+    }
+
+    #[test]
+    fn test_spread_returns_short_ascending_ranks() {
+        let provider = LexoRankProvider;
+
+        for count in [0, 1, 2, 35, 36, 1000] {
+            let ranks = provider.spread(count).unwrap();
+
+            assert_eq!(ranks.len(), count);
+            assert!(ranks.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(
+                ranks
+                    .iter()
+                    .all(|r| parse(r).is_ok() && !provider.needs_rebalance(r))
+            );
+        }
+    }
+
+    #[test]
+    fn test_spread_leaves_room_between_neighbours() {
+        let provider = LexoRankProvider;
+        let ranks = provider.spread(10).unwrap();
+
+        for pair in ranks.windows(2) {
+            let between = provider.between(&pair[0], &pair[1]).unwrap();
+            assert!(pair[0] < between && between < pair[1]);
+            assert!(between.as_str().len() <= pair[1].as_str().len());
+        }
     }
 }
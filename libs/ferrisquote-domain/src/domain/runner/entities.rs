@@ -0,0 +1,3 @@
+pub mod ids;
+pub mod session;
+pub mod share_link;
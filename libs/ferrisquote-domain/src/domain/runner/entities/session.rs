@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    error::DomainError,
+    estimator::entities::submission::SubmissionData,
+    flows::entities::{
+        field::{Field, FieldConfig},
+        flow::Flow,
+        ids::StepId,
+        step::Step,
+    },
+};
+
+use super::ids::{RunnerSessionId, ShareLinkId};
+
+/// Answers for one iteration of a step, keyed by field key.
+pub type StepAnswers = HashMap<String, f64>;
+
+/// An anonymous visitor filling a shared flow, one step at a time.
+///
+/// Answers accumulate in a `SubmissionData` so they can be evaluated exactly
+/// like an admin submission. Only numeric answers are recorded: number fields
+/// take their value, boolean fields `0`/`1` and select fields the index of the
+/// chosen option. Text and date fields do not feed estimators and are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunnerSession {
+    pub id: RunnerSessionId,
+    pub share_link_id: ShareLinkId,
+    pub submission: SubmissionData,
+    pub completed_steps: Vec<StepId>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RunnerSession {
+    pub fn new(share_link_id: ShareLinkId) -> Self {
+        let now = Utc::now();
+        Self {
+            id: RunnerSessionId::new(),
+            share_link_id,
+            submission: SubmissionData::default(),
+            completed_steps: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Validate and store the answers of `step`, replacing earlier answers.
+    ///
+    /// A regular step takes exactly one iteration; a repeatable step takes
+    /// between `min_repeats` and `max_repeats`. Repeatable answers are stored
+    /// in `iteration_values`, with `iteration_counts` set for each field key
+    /// so `COUNT_ITER(@field)` works.
+    pub fn record_step(
+        &mut self,
+        step: &Step,
+        iterations: Vec<StepAnswers>,
+        at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        let count = iterations.len();
+        if step.is_repeatable {
+            if count < step.min_repeats as usize
+                || step.max_repeats.is_some_and(|max| count > max as usize)
+            {
+                return Err(DomainError::validation(format!(
+                    "Step '{}' takes between {} and {} iterations, got {count}",
+                    step.title,
+                    step.min_repeats,
+                    step.max_repeats
+                        .map_or_else(|| "unlimited".to_string(), |max| max.to_string()),
+                )));
+            }
+        } else if count != 1 {
+            return Err(DomainError::validation(format!(
+                "Step '{}' takes exactly one set of answers, got {count}",
+                step.title
+            )));
+        }
+
+        let numeric_fields: Vec<&Field> = step
+            .fields
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.config,
+                    FieldConfig::Number(_) | FieldConfig::Boolean(_) | FieldConfig::Select(_)
+                )
+            })
+            .collect();
+
+        let mut columns: HashMap<String, Vec<f64>> = numeric_fields
+            .iter()
+            .map(|f| (f.key.clone(), Vec::with_capacity(count)))
+            .collect();
+
+        for answers in &iterations {
+            if let Some(unknown) = answers.keys().find(|k| !columns.contains_key(*k)) {
+                return Err(DomainError::validation(format!(
+                    "Step '{}' has no numeric field '{unknown}'",
+                    step.title
+                )));
+            }
+            for field in &numeric_fields {
+                let value = resolve_answer(field, answers.get(&field.key).copied())?;
+                columns
+                    .get_mut(&field.key)
+                    .expect("column exists for every numeric field")
+                    .push(value);
+            }
+        }
+
+        for (key, values) in columns {
+            if step.is_repeatable {
+                self.submission
+                    .iteration_counts
+                    .insert(key.clone(), values.len());
+                self.submission.iteration_values.insert(key, values);
+            } else {
+                self.submission.field_values.insert(key, values[0]);
+            }
+        }
+
+        if !self.completed_steps.contains(&step.id) {
+            self.completed_steps.push(step.id);
+        }
+        self.updated_at = at;
+
+        Ok(())
+    }
+
+    /// Steps of `flow` that still have to be answered.
+    pub fn missing_steps<'a>(&self, flow: &'a Flow) -> Vec<&'a Step> {
+        flow.steps
+            .iter()
+            .filter(|s| !self.completed_steps.contains(&s.id))
+            .collect()
+    }
+}
+
+/// Check an answer against its field configuration, applying defaults.
+fn resolve_answer(field: &Field, value: Option<f64>) -> Result<f64, DomainError> {
+    let invalid = |reason: String| {
+        DomainError::validation(format!("Invalid answer for '{}': {reason}", field.key))
+    };
+
+    match (&field.config, value) {
+        (FieldConfig::Boolean(b), None) => Ok(if b.default { 1.0 } else { 0.0 }),
+        (_, None) => Err(invalid("an answer is required".to_string())),
+        (_, Some(v)) if !v.is_finite() => Err(invalid("must be a finite number".to_string())),
+        (FieldConfig::Number(n), Some(v)) => {
+            if n.min.is_some_and(|min| v < min) || n.max.is_some_and(|max| v > max) {
+                Err(invalid(format!(
+                    "must be within [{}, {}]",
+                    n.min.map_or("-inf".to_string(), |m| m.to_string()),
+                    n.max.map_or("+inf".to_string(), |m| m.to_string()),
+                )))
+            } else {
+                Ok(v)
+            }
+        }
+        (FieldConfig::Boolean(_), Some(v)) if v == 0.0 || v == 1.0 => Ok(v),
+        (FieldConfig::Boolean(_), Some(_)) => Err(invalid("must be 0 or 1".to_string())),
+        (FieldConfig::Select(s), Some(v))
+            if v.fract() == 0.0 && v >= 0.0 && (v as usize) < s.options.len() =>
+        {
+            Ok(v)
+        }
+        (FieldConfig::Select(s), Some(_)) => Err(invalid(format!(
+            "must be an option index below {}",
+            s.options.len()
+        ))),
+        (FieldConfig::Text(_) | FieldConfig::Date(_), Some(_)) => {
+            Err(invalid("field does not take a numeric answer".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::flows::entities::field::FieldConfig;
+
+    fn make_step(repeatable: bool) -> Step {
+        let mut step = Step::new("Rooms".into(), String::new(), "0|a".into());
+        step.is_repeatable = repeatable;
+        step.min_repeats = 1;
+        step.max_repeats = Some(3);
+        step.fields = vec![
+            Field::new(
+                "surface".into(),
+                "Surface".into(),
+                String::new(),
+                "0|a".into(),
+                FieldConfig::new_number(Some(0.0), Some(500.0)),
+            ),
+            Field::new(
+                "painted".into(),
+                "Painted".into(),
+                String::new(),
+                "0|b".into(),
+                FieldConfig::new_boolean(false),
+            ),
+            Field::new(
+                "notes".into(),
+                "Notes".into(),
+                String::new(),
+                "0|c".into(),
+                FieldConfig::new_text(255),
+            ),
+        ];
+        step
+    }
+
+    fn answers(surface: f64) -> StepAnswers {
+        HashMap::from([("surface".to_string(), surface)])
+    }
+
+    #[test]
+    fn test_regular_step_fills_field_values_with_defaults() {
+        let step = make_step(false);
+        let mut session = RunnerSession::new(ShareLinkId::new());
+
+        session
+            .record_step(&step, vec![answers(42.0)], Utc::now())
+            .unwrap();
+
+        assert_eq!(session.submission.field_values["surface"], 42.0);
+        assert_eq!(session.submission.field_values["painted"], 0.0);
+        assert!(!session.submission.field_values.contains_key("notes"));
+        assert_eq!(session.completed_steps, vec![step.id]);
+    }
+
+    #[test]
+    fn test_repeatable_step_fills_iterations() {
+        let step = make_step(true);
+        let mut session = RunnerSession::new(ShareLinkId::new());
+
+        session
+            .record_step(&step, vec![answers(10.0), answers(20.0)], Utc::now())
+            .unwrap();
+
+        assert_eq!(
+            session.submission.iteration_values["surface"],
+            vec![10.0, 20.0]
+        );
+        assert_eq!(session.submission.iteration_counts["surface"], 2);
+    }
+
+    #[test]
+    fn test_record_step_rejects_invalid_answers() {
+        let step = make_step(true);
+        let mut session = RunnerSession::new(ShareLinkId::new());
+        let now = Utc::now();
+
+        // Out of range, unknown key, non-numeric field, too many iterations, missing answer.
+        assert!(
+            session
+                .record_step(&step, vec![answers(501.0)], now)
+                .is_err()
+        );
+        assert!(
+            session
+                .record_step(
+                    &step,
+                    vec![HashMap::from([("price".to_string(), 1.0)])],
+                    now
+                )
+                .is_err()
+        );
+        assert!(
+            session
+                .record_step(
+                    &step,
+                    vec![HashMap::from([("notes".to_string(), 1.0)])],
+                    now
+                )
+                .is_err()
+        );
+        assert!(
+            session
+                .record_step(&step, vec![answers(1.0); 4], now)
+                .is_err()
+        );
+        assert!(
+            session
+                .record_step(&step, vec![HashMap::new()], now)
+                .is_err()
+        );
+        assert!(session.completed_steps.is_empty());
+    }
+
+    #[test]
+    fn test_missing_steps() {
+        let first = make_step(false);
+        let second = make_step(false);
+        let flow = Flow::with_steps(
+            crate::domain::flows::entities::ids::FlowId::new(),
+            crate::domain::organization::entities::ids::OrganizationId::new("acme"),
+            "Flow".into(),
+            String::new(),
+            vec![first.clone(), second.clone()],
+        );
+        let mut session = RunnerSession::new(ShareLinkId::new());
+        session
+            .record_step(&first, vec![answers(1.0)], Utc::now())
+            .unwrap();
+
+        let missing: Vec<StepId> = session.missing_steps(&flow).iter().map(|s| s.id).collect();
+        assert_eq!(missing, vec![second.id]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    audit::entities::actor::Actor,
+    error::DomainError,
+    estimator::entities::ids::EstimatorId,
+    flows::entities::{flow::Flow, ids::FlowId, ids::StepId},
+    organization::entities::ids::OrganizationId,
+};
+
+use super::entities::{
+    ids::{RunnerSessionId, ShareLinkId},
+    session::{RunnerSession, StepAnswers},
+    share_link::ShareLink,
+};
+
+/// Repository trait for ShareLink persistence.
+///
+/// Links are looked up by token without any organization (that is how anonymous
+/// visitors reach them); every other lookup is scoped to `organization`.
+pub trait ShareLinkRepository: Send + Sync {
+    fn create_share_link(
+        &self,
+        link: ShareLink,
+    ) -> impl Future<Output = Result<ShareLink, DomainError>> + Send;
+
+    fn get_share_link_by_token(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<ShareLink, DomainError>> + Send;
+
+    fn list_share_links_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> impl Future<Output = Result<Vec<ShareLink>, DomainError>> + Send;
+
+    fn revoke_share_link(
+        &self,
+        organization: &OrganizationId,
+        id: ShareLinkId,
+        at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<ShareLink, DomainError>> + Send;
+}
+
+/// Repository trait for RunnerSession persistence.
+pub trait RunnerSessionRepository: Send + Sync {
+    fn create_session(
+        &self,
+        session: RunnerSession,
+    ) -> impl Future<Output = Result<RunnerSession, DomainError>> + Send;
+
+    fn get_session(
+        &self,
+        id: RunnerSessionId,
+    ) -> impl Future<Output = Result<RunnerSession, DomainError>> + Send;
+
+    fn update_session(
+        &self,
+        session: RunnerSession,
+    ) -> impl Future<Output = Result<RunnerSession, DomainError>> + Send;
+}
+
+/// Admin-side service managing the share links of a flow.
+///
+/// Creations and revocations are recorded under `actor` in the audit log.
+pub trait ShareLinkService: Send + Sync {
+    /// Publish `flow_id` with `estimator_id`, which must belong to the flow.
+    fn create_share_link(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        flow_id: FlowId,
+        estimator_id: EstimatorId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<ShareLink, DomainError>> + Send;
+
+    fn list_share_links(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> impl Future<Output = Result<Vec<ShareLink>, DomainError>> + Send;
+
+    fn revoke_share_link(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: ShareLinkId,
+    ) -> impl Future<Output = Result<ShareLink, DomainError>> + Send;
+}
+
+/// Public service used by anonymous visitors holding a share link token.
+///
+/// Unknown, revoked and expired tokens are all reported as `NotFound`.
+pub trait RunnerService: Send + Sync {
+    /// The flow schema behind a share link.
+    fn get_shared_flow(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<Flow, DomainError>> + Send;
+
+    fn start_session(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<RunnerSession, DomainError>> + Send;
+
+    /// Record the answers of one step.
+    fn submit_step(
+        &self,
+        token: &str,
+        session_id: RunnerSessionId,
+        step_id: StepId,
+        iterations: Vec<StepAnswers>,
+    ) -> impl Future<Output = Result<RunnerSession, DomainError>> + Send;
+
+    /// Evaluate a completed session and return the estimated total.
+    fn estimate(
+        &self,
+        token: &str,
+        session_id: RunnerSessionId,
+    ) -> impl Future<Output = Result<f64, DomainError>> + Send;
+}
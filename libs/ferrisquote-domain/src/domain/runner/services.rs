@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    audit::{
+        entities::{actor::Actor, entry::AuditEntity},
+        ports::AuditRepository,
+        services::record,
+    },
+    error::DomainError,
+    estimator::{
+        entities::ids::EstimatorId, ports::EstimatorRepository,
+        services::evaluate_estimator_with_submission,
+    },
+    flows::{
+        entities::{
+            flow::Flow,
+            ids::{FlowId, StepId},
+        },
+        ports::FlowRepository,
+    },
+    organization::entities::ids::OrganizationId,
+    transaction::ports::{Transaction, Transactional, UnitOfWork},
+};
+
+use super::{
+    entities::{
+        ids::{RunnerSessionId, ShareLinkId},
+        session::{RunnerSession, StepAnswers},
+        share_link::ShareLink,
+    },
+    ports::{RunnerService, RunnerSessionRepository, ShareLinkRepository, ShareLinkService},
+};
+
+/// Implements both the admin `ShareLinkService` and the public `RunnerService`.
+///
+/// Type parameters:
+/// - `LR`: type implementing `ShareLinkRepository`
+/// - `SR`: type implementing `RunnerSessionRepository`
+/// - `FR`: type implementing `FlowRepository`
+/// - `ER`: type implementing `EstimatorRepository`
+/// - `AR`: type implementing `AuditRepository`
+/// - `UW`: type implementing `UnitOfWork` (transactions around each audited change)
+#[derive(Clone)]
+pub struct RunnerServiceImpl<LR, SR, FR, ER, AR, UW> {
+    link_repo: LR,
+    session_repo: SR,
+    flow_repo: FR,
+    estimator_repo: ER,
+    audit_repo: AR,
+    unit_of_work: UW,
+}
+
+impl<LR, SR, FR, ER, AR, UW> RunnerServiceImpl<LR, SR, FR, ER, AR, UW> {
+    pub fn new(
+        link_repo: LR,
+        session_repo: SR,
+        flow_repo: FR,
+        estimator_repo: ER,
+        audit_repo: AR,
+        unit_of_work: UW,
+    ) -> Self {
+        Self {
+            link_repo,
+            session_repo,
+            flow_repo,
+            estimator_repo,
+            audit_repo,
+            unit_of_work,
+        }
+    }
+}
+
+/// The repositories of a `RunnerServiceImpl` that write audited changes, bound to one transaction.
+struct Repositories<LR, AR> {
+    links: LR,
+    audit: AR,
+}
+
+impl<LR, SR, FR, ER, AR, UW> RunnerServiceImpl<LR, SR, FR, ER, AR, UW>
+where
+    LR: Transactional<UW::Transaction>,
+    AR: Transactional<UW::Transaction>,
+    UW: UnitOfWork,
+{
+    /// Open a transaction and the repositories writing through it.
+    ///
+    /// A share link change and its audit entry are committed together, or not at all.
+    async fn begin(&self) -> Result<(UW::Transaction, Repositories<LR, AR>), DomainError> {
+        let transaction = self.unit_of_work.begin().await?;
+        let repositories = Repositories {
+            links: self.link_repo.in_transaction(&transaction),
+            audit: self.audit_repo.in_transaction(&transaction),
+        };
+        Ok((transaction, repositories))
+    }
+}
+
+impl<LR, SR, FR, ER, AR, UW> RunnerServiceImpl<LR, SR, FR, ER, AR, UW>
+where
+    LR: ShareLinkRepository + Send + Sync,
+    SR: RunnerSessionRepository + Send + Sync,
+{
+    async fn active_link(&self, token: &str) -> Result<ShareLink, DomainError> {
+        let link = self.link_repo.get_share_link_by_token(token).await?;
+        if !link.is_active(Utc::now()) {
+            return Err(DomainError::not_found("ShareLink", token));
+        }
+        Ok(link)
+    }
+
+    async fn session_for_link(
+        &self,
+        link: &ShareLink,
+        session_id: RunnerSessionId,
+    ) -> Result<RunnerSession, DomainError> {
+        let session = self.session_repo.get_session(session_id).await?;
+        if session.share_link_id != link.id {
+            return Err(DomainError::not_found(
+                "RunnerSession",
+                session_id.to_string(),
+            ));
+        }
+        Ok(session)
+    }
+}
+
+impl<LR, SR, FR, ER, AR, UW> ShareLinkService for RunnerServiceImpl<LR, SR, FR, ER, AR, UW>
+where
+    LR: ShareLinkRepository + Transactional<UW::Transaction> + Send + Sync,
+    SR: RunnerSessionRepository + Send + Sync,
+    FR: FlowRepository + Send + Sync,
+    ER: EstimatorRepository + Send + Sync,
+    AR: AuditRepository + Transactional<UW::Transaction> + Send + Sync,
+    UW: UnitOfWork,
+{
+    async fn create_share_link(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        flow_id: FlowId,
+        estimator_id: EstimatorId,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ShareLink, DomainError> {
+        let estimator = self
+            .estimator_repo
+            .get_estimator(organization, estimator_id)
+            .await?;
+        if estimator.flow_id != flow_id {
+            return Err(DomainError::validation(format!(
+                "Estimator {estimator_id} does not belong to flow {flow_id}"
+            )));
+        }
+
+        let (transaction, repos) = self.begin().await?;
+        let link = repos
+            .links
+            .create_share_link(ShareLink::new(
+                estimator.organization_id,
+                flow_id,
+                estimator_id,
+                expires_at,
+            ))
+            .await?;
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::ShareLink,
+            link.id,
+            None,
+            Some(&link),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(link)
+    }
+
+    async fn list_share_links(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<ShareLink>, DomainError> {
+        self.link_repo
+            .list_share_links_for_flow(organization, flow_id)
+            .await
+    }
+
+    async fn revoke_share_link(
+        &self,
+        organization: &OrganizationId,
+        actor: &Actor,
+        id: ShareLinkId,
+    ) -> Result<ShareLink, DomainError> {
+        let now = Utc::now();
+        let (transaction, repos) = self.begin().await?;
+        let link = repos.links.revoke_share_link(organization, id, now).await?;
+
+        // Revoking an already revoked link keeps it untouched: nothing to record.
+        let mut before = link.clone();
+        if before.revoked_at == Some(now) {
+            before.revoked_at = None;
+        }
+        record(
+            &repos.audit,
+            organization,
+            actor,
+            AuditEntity::ShareLink,
+            id,
+            Some(&before),
+            Some(&link),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(link)
+    }
+}
+
+impl<LR, SR, FR, ER, AR, UW> RunnerService for RunnerServiceImpl<LR, SR, FR, ER, AR, UW>
+where
+    LR: ShareLinkRepository + Send + Sync,
+    SR: RunnerSessionRepository + Send + Sync,
+    FR: FlowRepository + Send + Sync,
+    ER: EstimatorRepository + Send + Sync,
+    AR: AuditRepository + Send + Sync,
+    UW: Send + Sync,
+{
+    async fn get_shared_flow(&self, token: &str) -> Result<Flow, DomainError> {
+        let link = self.active_link(token).await?;
+        self.flow_repo
+            .get_flow(&link.organization_id, link.flow_id)
+            .await
+    }
+
+    async fn start_session(&self, token: &str) -> Result<RunnerSession, DomainError> {
+        let link = self.active_link(token).await?;
+        self.session_repo
+            .create_session(RunnerSession::new(link.id))
+            .await
+    }
+
+    async fn submit_step(
+        &self,
+        token: &str,
+        session_id: RunnerSessionId,
+        step_id: StepId,
+        iterations: Vec<StepAnswers>,
+    ) -> Result<RunnerSession, DomainError> {
+        let link = self.active_link(token).await?;
+        let mut session = self.session_for_link(&link, session_id).await?;
+
+        let flow = self
+            .flow_repo
+            .get_flow(&link.organization_id, link.flow_id)
+            .await?;
+        let step = flow
+            .get_step(&step_id)
+            .ok_or_else(|| DomainError::not_found("Step", step_id.to_string()))?;
+
+        session.record_step(step, iterations, Utc::now())?;
+        self.session_repo.update_session(session).await
+    }
+
+    async fn estimate(&self, token: &str, session_id: RunnerSessionId) -> Result<f64, DomainError> {
+        let link = self.active_link(token).await?;
+        let session = self.session_for_link(&link, session_id).await?;
+
+        let flow = self
+            .flow_repo
+            .get_flow(&link.organization_id, link.flow_id)
+            .await?;
+        let missing = session.missing_steps(&flow);
+        if !missing.is_empty() {
+            let titles: Vec<&str> = missing.iter().map(|s| s.title.as_str()).collect();
+            return Err(DomainError::validation(format!(
+                "Steps not answered yet: {}",
+                titles.join(", ")
+            )));
+        }
+
+        let estimator = self
+            .estimator_repo
+            .get_estimator(&link.organization_id, link.estimator_id)
+            .await?;
+        let results = evaluate_estimator_with_submission(&estimator, &session.submission)?;
+        Ok(estimator.total(&results))
+    }
+}
@@ -0,0 +1,30 @@
+use std::future::Future;
+
+use crate::domain::error::DomainError;
+
+/// Starts transactions spanning several repository calls.
+///
+/// Services use it for operations made of more than one write, so that a failure
+/// half-way leaves storage as it was before the operation started.
+pub trait UnitOfWork: Send + Sync {
+    type Transaction: Transaction;
+
+    /// Open a new transaction.
+    fn begin(&self) -> impl Future<Output = Result<Self::Transaction, DomainError>> + Send;
+}
+
+/// An open transaction.
+///
+/// Dropping a transaction without committing it rolls it back.
+pub trait Transaction: Send + Sync {
+    /// Make every write done through the transaction visible to other callers.
+    fn commit(self) -> impl Future<Output = Result<(), DomainError>> + Send;
+    /// Discard every write done through the transaction.
+    fn rollback(self) -> impl Future<Output = Result<(), DomainError>> + Send;
+}
+
+/// A repository that can run its calls inside a transaction of type `T`.
+pub trait Transactional<T: Transaction> {
+    /// A copy of the repository whose calls, reads included, go through `transaction`.
+    fn in_transaction(&self, transaction: &T) -> Self;
+}
@@ -2,6 +2,25 @@ pub mod domain;
 pub mod infrastructure;
 
 // Re-export commonly used types
+pub use domain::api_key::entities::{
+    api_key::{ApiKey, IssuedApiKey},
+    ids::ApiKeyId,
+};
+pub use domain::audit::entities::{
+    actor::{Actor, ActorKind},
+    entry::{AuditAction, AuditEntity, AuditEntry},
+    ids::AuditEntryId,
+    query::{AuditFilter, AuditPage},
+};
+pub use domain::authorization::entities::{
+    permission::Permission,
+    policy::RolePolicy,
+    principal::{Principal, PrincipalKind},
+};
+pub use domain::customer::entities::{
+    customer::{Address, Customer, CustomerDetails},
+    ids::CustomerId,
+};
 pub use domain::error::DomainError;
 pub use domain::estimator::entities::{
     estimator::Estimator,
@@ -10,7 +29,21 @@ pub use domain::estimator::entities::{
 };
 pub use domain::flows::entities::{
     field::{Field, FieldBoolean, FieldConfig, FieldDate, FieldNumber, FieldSelect, FieldText},
-    flow::Flow,
+    flow::{Flow, FlowSummary},
     ids::{FieldId, FlowId, StepId},
+    layout::{FlowLayout, StepLayout},
+    query::{Cursor, ListQuery, ListSort, Page, SortOrder},
     step::Step,
 };
+pub use domain::organization::entities::ids::OrganizationId;
+pub use domain::quote::entities::{
+    diff::{QuoteDiff, ValueChange},
+    ids::QuoteId,
+    quote::Quote,
+    status::{QuoteStatus, QuoteStatusChange},
+};
+pub use domain::runner::entities::{
+    ids::{RunnerSessionId, ShareLinkId},
+    session::{RunnerSession, StepAnswers},
+    share_link::ShareLink,
+};
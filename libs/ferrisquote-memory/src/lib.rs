@@ -0,0 +1,13 @@
+pub mod repositories;
+mod store;
+pub mod unit_of_work;
+
+pub use repositories::InMemoryApiKeyRepository;
+pub use repositories::InMemoryAuditRepository;
+pub use repositories::InMemoryCustomerRepository;
+pub use repositories::InMemoryEstimatorRepository;
+pub use repositories::InMemoryFlowRepository;
+pub use repositories::InMemoryQuoteRepository;
+pub use repositories::InMemoryRunnerRepository;
+pub use store::InMemoryStore;
+pub use unit_of_work::InMemoryUnitOfWork;
@@ -0,0 +1,102 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    api_key::{
+        entities::{api_key::ApiKey, ids::ApiKeyId},
+        ports::ApiKeyRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+
+use crate::store::InMemoryStore;
+
+/// Stores API keys by the hash of their secret.
+#[derive(Clone, Default)]
+pub struct InMemoryApiKeyRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<ApiKey, DomainError> {
+        let mut tables = self.store.write().await?;
+
+        // Mirrors the unique constraint on `key_hash`.
+        if tables
+            .api_keys
+            .iter()
+            .any(|k| k.id == api_key.id || k.key_hash == api_key.key_hash)
+        {
+            return Err(DomainError::repository("API key already exists"));
+        }
+        tables.api_keys.push(api_key.clone());
+
+        Ok(api_key)
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<ApiKey, DomainError> {
+        self.store
+            .read()?
+            .api_keys
+            .iter()
+            .find(|k| k.key_hash == key_hash)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("ApiKey", "<hash>"))
+    }
+
+    async fn list_api_keys(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<ApiKey>, DomainError> {
+        let mut keys: Vec<ApiKey> = self
+            .store
+            .read()?
+            .api_keys
+            .iter()
+            .filter(|k| &k.organization_id == organization)
+            .cloned()
+            .collect();
+        keys.sort_by_key(|row| Reverse(row.created_at));
+
+        Ok(keys)
+    }
+
+    async fn revoke_api_key(
+        &self,
+        organization: &OrganizationId,
+        id: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<ApiKey, DomainError> {
+        let mut tables = self.store.write().await?;
+        let key = tables
+            .api_keys
+            .iter_mut()
+            .find(|k| k.id == id && &k.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("ApiKey", id.to_string()))?;
+
+        // Revoking twice keeps the original revocation time.
+        key.revoked_at.get_or_insert(at);
+
+        Ok(key.clone())
+    }
+
+    async fn touch_api_key(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if let Some(key) = tables.api_keys.iter_mut().find(|k| k.id == id) {
+            key.last_used_at = Some(at);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{entry::AuditEntry, query::AuditFilter},
+        ports::AuditRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+    transaction::ports::Transactional,
+};
+
+use crate::{store::InMemoryStore, unit_of_work::InMemoryTransaction};
+
+/// Append-only audit log. Entries are never updated or deleted.
+#[derive(Clone, Default)]
+pub struct InMemoryAuditRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryAuditRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Transactional<InMemoryTransaction> for InMemoryAuditRepository {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self::with_store(transaction.store())
+    }
+}
+
+impl AuditRepository for InMemoryAuditRepository {
+    async fn append_entry(&self, entry: AuditEntry) -> Result<AuditEntry, DomainError> {
+        self.store.write().await?.audit_log.push(entry.clone());
+
+        Ok(entry)
+    }
+
+    async fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: &AuditFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<AuditEntry>, u64), DomainError> {
+        let mut entries: Vec<AuditEntry> = self
+            .store
+            .read()?
+            .audit_log
+            .iter()
+            .filter(|e| &e.organization_id == organization)
+            .filter(|e| filter.entity.is_none_or(|entity| e.entity == entity))
+            .filter(|e| {
+                filter
+                    .entity_id
+                    .as_ref()
+                    .is_none_or(|id| &e.entity_id == id)
+            })
+            .cloned()
+            .collect();
+
+        // Newest first, like `ORDER BY at DESC, id DESC`.
+        entries.sort_by(|a, b| {
+            b.at.cmp(&a.at)
+                .then_with(|| b.id.as_uuid().cmp(a.id.as_uuid()))
+        });
+        let total = entries.len() as u64;
+        let page = entries
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect();
+
+        Ok((page, total))
+    }
+}
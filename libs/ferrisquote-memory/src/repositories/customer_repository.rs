@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    customer::{
+        entities::{customer::Customer, ids::CustomerId},
+        ports::CustomerRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+
+use crate::store::InMemoryStore;
+
+#[derive(Clone, Default)]
+pub struct InMemoryCustomerRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryCustomerRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl CustomerRepository for InMemoryCustomerRepository {
+    async fn create_customer(&self, customer: Customer) -> Result<Customer, DomainError> {
+        let mut tables = self.store.write().await?;
+        if tables.customers.iter().any(|c| c.id == customer.id) {
+            return Err(DomainError::repository(format!(
+                "Customer {} already exists",
+                customer.id
+            )));
+        }
+        tables.customers.push(customer.clone());
+
+        Ok(customer)
+    }
+
+    async fn get_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<Customer, DomainError> {
+        self.store
+            .read()?
+            .customers
+            .iter()
+            .find(|c| c.id == id && &c.organization_id == organization)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("Customer", id.to_string()))
+    }
+
+    async fn list_customers(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<Customer>, DomainError> {
+        let mut customers: Vec<Customer> = self
+            .store
+            .read()?
+            .customers
+            .iter()
+            .filter(|c| &c.organization_id == organization)
+            .cloned()
+            .collect();
+        customers.sort_by(|a, b| {
+            a.contact_name
+                .cmp(&b.contact_name)
+                .then_with(|| a.id.as_uuid().cmp(b.id.as_uuid()))
+        });
+
+        Ok(customers)
+    }
+
+    async fn update_customer(
+        &self,
+        organization: &OrganizationId,
+        customer: Customer,
+    ) -> Result<Customer, DomainError> {
+        let mut tables = self.store.write().await?;
+        let stored = tables
+            .customers
+            .iter_mut()
+            .find(|c| c.id == customer.id && &c.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("Customer", customer.id.to_string()))?;
+        let (organization_id, created_at) = (stored.organization_id.clone(), stored.created_at);
+        *stored = Customer {
+            organization_id,
+            created_at,
+            ..customer.clone()
+        };
+
+        Ok(customer)
+    }
+
+    async fn delete_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        let before = tables.customers.len();
+        tables
+            .customers
+            .retain(|c| !(c.id == id && &c.organization_id == organization));
+        if tables.customers.len() == before {
+            return Err(DomainError::not_found("Customer", id.to_string()));
+        }
+
+        // Quotes keep existing without their customer, like `ON DELETE SET NULL`.
+        for quote in tables
+            .quotes
+            .iter_mut()
+            .filter(|q| q.customer_id == Some(id))
+        {
+            quote.customer_id = None;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    estimator::{
+        entities::{
+            estimator::Estimator,
+            ids::{EstimatorId, EstimatorVariableId},
+            variable::EstimatorVariable,
+        },
+        ports::EstimatorRepository,
+    },
+    flows::entities::{ids::FlowId, query::ListQuery},
+    organization::entities::ids::OrganizationId,
+    transaction::ports::Transactional,
+};
+
+use crate::{
+    store::{InMemoryStore, Tables, VariableRow, bump_version},
+    unit_of_work::InMemoryTransaction,
+};
+
+#[derive(Clone, Default)]
+pub struct InMemoryEstimatorRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryEstimatorRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Transactional<InMemoryTransaction> for InMemoryEstimatorRepository {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self::with_store(transaction.store())
+    }
+}
+
+/// Whether `estimator_id` exists and belongs to `organization`.
+fn owns_estimator(
+    tables: &Tables,
+    organization: &OrganizationId,
+    estimator_id: EstimatorId,
+) -> bool {
+    tables
+        .estimators
+        .iter()
+        .any(|e| e.id == estimator_id && &e.organization_id == organization)
+}
+
+/// The estimator owning `variable_id`, if it belongs to `organization`.
+fn variable_owner(
+    tables: &Tables,
+    organization: &OrganizationId,
+    variable_id: EstimatorVariableId,
+) -> Option<EstimatorId> {
+    tables
+        .variables
+        .iter()
+        .find(|row| row.variable.id == variable_id)
+        .map(|row| row.estimator_id)
+        .filter(|id| owns_estimator(tables, organization, *id))
+}
+
+impl EstimatorRepository for InMemoryEstimatorRepository {
+    async fn create_estimator(&self, estimator: Estimator) -> Result<Estimator, DomainError> {
+        let mut tables = self.store.write().await?;
+
+        // The flow must belong to the same organization as the estimator.
+        if !tables
+            .flows
+            .iter()
+            .any(|f| f.id == estimator.flow_id && f.organization_id == estimator.organization_id)
+        {
+            return Err(DomainError::not_found(
+                "Flow",
+                estimator.flow_id.to_string(),
+            ));
+        }
+        let mut row = estimator.clone();
+        row.variables.clear();
+        tables.estimators.push(row);
+
+        Ok(estimator)
+    }
+
+    async fn get_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+    ) -> Result<Estimator, DomainError> {
+        let tables = self.store.read()?;
+        tables
+            .estimators
+            .iter()
+            .find(|e| e.id == id && &e.organization_id == organization)
+            .map(|e| tables.load_estimator(e))
+            .ok_or_else(|| DomainError::not_found("Estimator", id.to_string()))
+    }
+
+    async fn list_estimators_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+        query: &ListQuery<EstimatorId>,
+    ) -> Result<Vec<Estimator>, DomainError> {
+        let tables = self.store.read()?;
+        let estimators = query.select(
+            tables
+                .estimators
+                .iter()
+                .filter(|e| e.flow_id == flow_id && &e.organization_id == organization),
+            |e| (e.id, e.name.clone()),
+        );
+
+        Ok(estimators
+            .into_iter()
+            .map(|e| tables.load_estimator(e))
+            .collect())
+    }
+
+    async fn get_estimator_for_variable(
+        &self,
+        organization: &OrganizationId,
+        variable_id: EstimatorVariableId,
+    ) -> Result<Estimator, DomainError> {
+        let tables = self.store.read()?;
+        let estimator_id = variable_owner(&tables, organization, variable_id)
+            .ok_or_else(|| DomainError::not_found("EstimatorVariable", variable_id.to_string()))?;
+        tables
+            .estimators
+            .iter()
+            .find(|e| e.id == estimator_id)
+            .map(|e| tables.load_estimator(e))
+            .ok_or_else(|| DomainError::not_found("Estimator", estimator_id.to_string()))
+    }
+
+    async fn update_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+        name: Option<String>,
+        version: Option<u32>,
+    ) -> Result<Estimator, DomainError> {
+        let mut tables = self.store.write().await?;
+        let estimator = tables
+            .estimators
+            .iter_mut()
+            .find(|e| e.id == id && &e.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("Estimator", id.to_string()))?;
+        bump_version("Estimator", id, &mut estimator.version, version)?;
+        if let Some(name) = name {
+            estimator.name = name;
+        }
+        let estimator = estimator.clone();
+
+        Ok(tables.load_estimator(&estimator))
+    }
+
+    async fn delete_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        let estimator = tables
+            .estimators
+            .iter_mut()
+            .find(|e| e.id == id && &e.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("Estimator", id.to_string()))?;
+        bump_version("Estimator", id, &mut estimator.version, version)?;
+        tables.cascade_estimator(id);
+
+        Ok(())
+    }
+
+    async fn add_variable(
+        &self,
+        organization: &OrganizationId,
+        estimator_id: EstimatorId,
+        variable: EstimatorVariable,
+    ) -> Result<EstimatorVariable, DomainError> {
+        let mut tables = self.store.write().await?;
+        if !owns_estimator(&tables, organization, estimator_id) {
+            return Err(DomainError::not_found(
+                "Estimator",
+                estimator_id.to_string(),
+            ));
+        }
+        tables.variables.push(VariableRow {
+            estimator_id,
+            variable: variable.clone(),
+        });
+
+        Ok(variable)
+    }
+
+    async fn update_variable(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorVariableId,
+        name: Option<String>,
+        expression: Option<String>,
+        description: Option<String>,
+        rank: Option<String>,
+        version: Option<u32>,
+    ) -> Result<EstimatorVariable, DomainError> {
+        let mut tables = self.store.write().await?;
+        if variable_owner(&tables, organization, id).is_none() {
+            return Err(DomainError::not_found("EstimatorVariable", id.to_string()));
+        }
+        let variable = tables
+            .variables
+            .iter_mut()
+            .map(|row| &mut row.variable)
+            .find(|v| v.id == id)
+            .ok_or_else(|| DomainError::not_found("EstimatorVariable", id.to_string()))?;
+        bump_version("EstimatorVariable", id, &mut variable.version, version)?;
+        if let Some(name) = name {
+            variable.name = name;
+        }
+        if let Some(expression) = expression {
+            variable.expression = expression;
+        }
+        if let Some(description) = description {
+            variable.description = description;
+        }
+        if let Some(rank) = rank {
+            variable.rank = rank;
+        }
+
+        Ok(variable.clone())
+    }
+
+    async fn update_variable_ranks(
+        &self,
+        organization: &OrganizationId,
+        ranks: &[(EstimatorVariableId, String)],
+    ) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if let Some((id, _)) = ranks
+            .iter()
+            .find(|(id, _)| variable_owner(&tables, organization, *id).is_none())
+        {
+            return Err(DomainError::not_found("EstimatorVariable", id.to_string()));
+        }
+        for row in tables.variables.iter_mut() {
+            if let Some((_, rank)) = ranks.iter().find(|(id, _)| *id == row.variable.id) {
+                row.variable.rank = rank.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_variable(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorVariableId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if variable_owner(&tables, organization, id).is_none() {
+            return Err(DomainError::not_found("EstimatorVariable", id.to_string()));
+        }
+        let variable = tables
+            .variables
+            .iter_mut()
+            .map(|row| &mut row.variable)
+            .find(|v| v.id == id)
+            .ok_or_else(|| DomainError::not_found("EstimatorVariable", id.to_string()))?;
+        bump_version("EstimatorVariable", id, &mut variable.version, version)?;
+        tables.variables.retain(|row| row.variable.id != id);
+
+        Ok(())
+    }
+}
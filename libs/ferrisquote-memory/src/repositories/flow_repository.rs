@@ -0,0 +1,453 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    flows::{
+        entities::{
+            field::{Field, FieldConfig},
+            flow::{Flow, FlowSummary},
+            ids::{FieldId, FlowId, StepId},
+            query::ListQuery,
+            step::Step,
+        },
+        ports::{FieldRepository, FlowRepository, StepRepository},
+    },
+    organization::entities::ids::OrganizationId,
+    transaction::ports::Transactional,
+};
+
+use crate::{
+    store::{FieldRow, InMemoryStore, StepRow, bump_version},
+    unit_of_work::InMemoryTransaction,
+};
+
+/// In-memory implementation of FlowRepository, StepRepository and FieldRepository.
+#[derive(Clone, Default)]
+pub struct InMemoryFlowRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryFlowRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Transactional<InMemoryTransaction> for InMemoryFlowRepository {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self::with_store(transaction.store())
+    }
+}
+
+// ============================================================================
+// FlowRepository
+// ============================================================================
+
+impl FlowRepository for InMemoryFlowRepository {
+    async fn create_flow(&self, flow: Flow) -> Result<Flow, DomainError> {
+        let mut tables = self.store.write().await?;
+        if tables.flows.iter().any(|f| f.id == flow.id) {
+            return Err(DomainError::repository(format!(
+                "Flow {} already exists",
+                flow.id
+            )));
+        }
+        let mut row = flow.clone();
+        row.steps.clear();
+        tables.flows.push(row);
+
+        Ok(flow)
+    }
+
+    async fn get_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> Result<Flow, DomainError> {
+        let tables = self.store.read()?;
+        tables
+            .flows
+            .iter()
+            .find(|f| f.id == id && &f.organization_id == organization)
+            .map(|f| tables.load_flow(f))
+            .ok_or_else(|| DomainError::not_found("Flow", id.to_string()))
+    }
+
+    async fn list_flows(
+        &self,
+        organization: &OrganizationId,
+        query: &ListQuery<FlowId>,
+    ) -> Result<Vec<FlowSummary>, DomainError> {
+        let tables = self.store.read()?;
+        let flows = query.select(
+            tables
+                .flows
+                .iter()
+                .filter(|f| &f.organization_id == organization),
+            |f| (f.id, f.name.clone()),
+        );
+
+        // Steps are counted, never loaded, like the `COUNT(*)` of Postgres.
+        Ok(flows
+            .into_iter()
+            .map(|f| FlowSummary {
+                id: f.id,
+                name: f.name.clone(),
+                description: f.description.clone(),
+                step_count: tables.steps.iter().filter(|s| s.flow_id == f.id).count(),
+                version: f.version,
+            })
+            .collect())
+    }
+
+    async fn update_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+        name: Option<String>,
+        description: Option<String>,
+    ) -> Result<Flow, DomainError> {
+        let mut tables = self.store.write().await?;
+        let flow = tables
+            .flows
+            .iter_mut()
+            .find(|f| f.id == id && &f.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("Flow", id.to_string()))?;
+        if let Some(name) = name {
+            flow.name = name;
+        }
+        if let Some(description) = description {
+            flow.description = description;
+        }
+        let flow = flow.clone();
+
+        Ok(tables.load_flow(&flow))
+    }
+
+    async fn delete_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables
+            .flows
+            .iter()
+            .any(|f| f.id == id && &f.organization_id == organization)
+        {
+            return Err(DomainError::not_found("Flow", id.to_string()));
+        }
+        tables.cascade_flow(id);
+
+        Ok(())
+    }
+
+    async fn bump_flow_version(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut tables = self.store.write().await?;
+        let flow = tables
+            .flows
+            .iter_mut()
+            .find(|f| f.id == id && &f.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("Flow", id.to_string()))?;
+        bump_version("Flow", id, &mut flow.version, expected)
+    }
+}
+
+// ============================================================================
+// StepRepository
+// ============================================================================
+
+impl StepRepository for InMemoryFlowRepository {
+    async fn create_step(&self, flow_id: FlowId, step: Step) -> Result<Step, DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables.flows.iter().any(|f| f.id == flow_id) {
+            return Err(DomainError::not_found("Flow", flow_id.to_string()));
+        }
+        let mut row = step.clone();
+        row.fields.clear();
+        tables.steps.push(StepRow { flow_id, step: row });
+
+        Ok(step)
+    }
+
+    async fn get_step(&self, id: StepId) -> Result<Step, DomainError> {
+        let tables = self.store.read()?;
+        tables
+            .steps
+            .iter()
+            .find(|row| row.step.id == id)
+            .map(|row| tables.load_step(&row.step))
+            .ok_or_else(|| DomainError::not_found("Step", id.to_string()))
+    }
+
+    async fn get_step_with_flow(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> Result<(FlowId, Step), DomainError> {
+        let tables = self.store.read()?;
+        tables
+            .owned_step(organization, id)
+            .map(|row| (row.flow_id, tables.load_step(&row.step)))
+            .ok_or_else(|| DomainError::not_found("Step", id.to_string()))
+    }
+
+    async fn find_flow_id_for_step(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> Result<FlowId, DomainError> {
+        self.store
+            .read()?
+            .owned_step(organization, id)
+            .map(|row| row.flow_id)
+            .ok_or_else(|| DomainError::not_found("Step", id.to_string()))
+    }
+
+    async fn update_step(
+        &self,
+        id: StepId,
+        title: Option<String>,
+        description: Option<String>,
+        rank: Option<String>,
+        is_repeatable: Option<bool>,
+        repeat_label: Option<Option<String>>,
+        min_repeats: Option<u32>,
+        max_repeats: Option<Option<u32>>,
+    ) -> Result<Step, DomainError> {
+        let mut tables = self.store.write().await?;
+        let step = tables
+            .steps
+            .iter_mut()
+            .map(|row| &mut row.step)
+            .find(|step| step.id == id)
+            .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
+        if let Some(title) = title {
+            step.title = title;
+        }
+        if let Some(description) = description {
+            step.description = description;
+        }
+        if let Some(rank) = rank {
+            step.rank = rank;
+        }
+        if let Some(is_repeatable) = is_repeatable {
+            step.is_repeatable = is_repeatable;
+        }
+        if let Some(repeat_label) = repeat_label {
+            step.repeat_label = repeat_label;
+        }
+        if let Some(min_repeats) = min_repeats {
+            step.min_repeats = min_repeats;
+        }
+        if let Some(max_repeats) = max_repeats {
+            step.max_repeats = max_repeats;
+        }
+        let step = step.clone();
+
+        Ok(tables.load_step(&step))
+    }
+
+    async fn update_step_ranks(&self, ranks: &[(StepId, String)]) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if let Some((id, _)) = ranks
+            .iter()
+            .find(|(id, _)| !tables.steps.iter().any(|row| row.step.id == *id))
+        {
+            return Err(DomainError::not_found("Step", id.to_string()));
+        }
+        for row in tables.steps.iter_mut() {
+            if let Some((_, rank)) = ranks.iter().find(|(id, _)| *id == row.step.id) {
+                row.step.rank = rank.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_step(&self, id: StepId) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables.steps.iter().any(|row| row.step.id == id) {
+            return Err(DomainError::not_found("Step", id.to_string()));
+        }
+        tables.cascade_step(id);
+
+        Ok(())
+    }
+
+    async fn bump_step_version(
+        &self,
+        id: StepId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut tables = self.store.write().await?;
+        let step = tables
+            .steps
+            .iter_mut()
+            .map(|row| &mut row.step)
+            .find(|step| step.id == id)
+            .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
+        bump_version("Step", id, &mut step.version, expected)
+    }
+}
+
+// ============================================================================
+// FieldRepository
+// ============================================================================
+
+impl FieldRepository for InMemoryFlowRepository {
+    async fn create_field(&self, step_id: StepId, field: Field) -> Result<Field, DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables.steps.iter().any(|row| row.step.id == step_id) {
+            return Err(DomainError::not_found("Step", step_id.to_string()));
+        }
+        tables.fields.push(FieldRow {
+            step_id,
+            field: field.clone(),
+        });
+
+        Ok(field)
+    }
+
+    async fn update_field(
+        &self,
+        field_id: FieldId,
+        key: Option<String>,
+        label: Option<String>,
+        description: Option<String>,
+        config: Option<FieldConfig>,
+    ) -> Result<Field, DomainError> {
+        let mut tables = self.store.write().await?;
+        let field = tables
+            .fields
+            .iter_mut()
+            .map(|row| &mut row.field)
+            .find(|field| field.id == field_id)
+            .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
+        if let Some(key) = key {
+            field.key = key;
+        }
+        if let Some(label) = label {
+            field.label = label;
+        }
+        if let Some(description) = description {
+            field.description = description;
+        }
+        if let Some(config) = config {
+            field.config = config;
+        }
+
+        Ok(field.clone())
+    }
+
+    async fn delete_field(&self, id: FieldId) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        let before = tables.fields.len();
+        tables.fields.retain(|row| row.field.id != id);
+        if tables.fields.len() == before {
+            return Err(DomainError::not_found("Field", id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn bump_field_version(
+        &self,
+        id: FieldId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut tables = self.store.write().await?;
+        let field = tables
+            .fields
+            .iter_mut()
+            .map(|row| &mut row.field)
+            .find(|field| field.id == id)
+            .ok_or_else(|| DomainError::not_found("Field", id.to_string()))?;
+        bump_version("Field", id, &mut field.version, expected)
+    }
+
+    async fn move_field(
+        &self,
+        field_id: FieldId,
+        step_id: StepId,
+        rank: String,
+    ) -> Result<Field, DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables.steps.iter().any(|row| row.step.id == step_id) {
+            return Err(DomainError::repository(format!(
+                "Step {step_id} does not exist"
+            )));
+        }
+        let row = tables
+            .fields
+            .iter_mut()
+            .find(|row| row.field.id == field_id)
+            .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
+        row.step_id = step_id;
+        row.field.rank = rank;
+
+        Ok(row.field.clone())
+    }
+
+    async fn update_field_ranks(&self, ranks: &[(FieldId, String)]) -> Result<(), DomainError> {
+        let mut tables = self.store.write().await?;
+        if let Some((id, _)) = ranks
+            .iter()
+            .find(|(id, _)| !tables.fields.iter().any(|row| row.field.id == *id))
+        {
+            return Err(DomainError::not_found("Field", id.to_string()));
+        }
+        for row in tables.fields.iter_mut() {
+            if let Some((_, rank)) = ranks.iter().find(|(id, _)| *id == row.field.id) {
+                row.field.rank = rank.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn find_flow_id_for_field(
+        &self,
+        organization: &OrganizationId,
+        field_id: FieldId,
+    ) -> Result<FlowId, DomainError> {
+        let tables = self.store.read()?;
+        tables
+            .fields
+            .iter()
+            .find(|row| row.field.id == field_id)
+            .and_then(|field| tables.owned_step(organization, field.step_id))
+            .map(|step| step.flow_id)
+            .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))
+    }
+
+    async fn get_flow_fields(
+        &self,
+        flow_id: FlowId,
+        like: Option<String>,
+    ) -> Result<Vec<Field>, DomainError> {
+        let tables = self.store.read()?;
+        // Case-insensitive substring match, like `label ILIKE '%like%'`.
+        let pattern = like.map(|q| q.to_lowercase());
+
+        Ok(tables
+            .load_steps(flow_id)
+            .into_iter()
+            .flat_map(|step| step.fields)
+            .filter(|field| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|q| field.label.to_lowercase().contains(q))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,15 @@
+pub mod api_key_repository;
+pub mod audit_repository;
+pub mod customer_repository;
+pub mod estimator_repository;
+pub mod flow_repository;
+pub mod quote_repository;
+pub mod runner_repository;
+
+pub use api_key_repository::InMemoryApiKeyRepository;
+pub use audit_repository::InMemoryAuditRepository;
+pub use customer_repository::InMemoryCustomerRepository;
+pub use estimator_repository::InMemoryEstimatorRepository;
+pub use flow_repository::InMemoryFlowRepository;
+pub use quote_repository::InMemoryQuoteRepository;
+pub use runner_repository::InMemoryRunnerRepository;
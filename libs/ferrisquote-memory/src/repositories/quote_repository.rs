@@ -0,0 +1,218 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    customer::entities::ids::CustomerId,
+    error::DomainError,
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+    quote::{
+        entities::{
+            ids::QuoteId,
+            quote::Quote,
+            status::{QuoteStatus, QuoteStatusChange},
+        },
+        ports::QuoteRepository,
+    },
+    transaction::ports::Transactional,
+};
+
+use crate::{store::InMemoryStore, unit_of_work::InMemoryTransaction};
+
+#[derive(Clone, Default)]
+pub struct InMemoryQuoteRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryQuoteRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Transactional<InMemoryTransaction> for InMemoryQuoteRepository {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self::with_store(transaction.store())
+    }
+}
+
+/// Quotes of `organization` matching `filter`, newest first.
+fn newest_first(
+    quotes: &[Quote],
+    organization: &OrganizationId,
+    filter: impl Fn(&Quote) -> bool,
+) -> Vec<Quote> {
+    let mut quotes: Vec<Quote> = quotes
+        .iter()
+        .filter(|q| &q.organization_id == organization && filter(q))
+        .cloned()
+        .collect();
+    quotes.sort_by_key(|row| Reverse(row.created_at));
+    quotes
+}
+
+impl QuoteRepository for InMemoryQuoteRepository {
+    async fn create_quote(&self, quote: Quote) -> Result<Quote, DomainError> {
+        let mut tables = self.store.write().await?;
+
+        // Mirrors the `(root_id, revision)` unique constraint.
+        if tables.quotes.iter().any(|q| {
+            q.id == quote.id || (q.root_id == quote.root_id && q.revision == quote.revision)
+        }) {
+            return Err(DomainError::repository(format!(
+                "Revision {} of quote {} already exists",
+                quote.revision, quote.root_id
+            )));
+        }
+        tables.quotes.push(quote.clone());
+
+        Ok(quote)
+    }
+
+    async fn get_quote(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Quote, DomainError> {
+        self.store
+            .read()?
+            .quotes
+            .iter()
+            .find(|q| q.id == id && &q.organization_id == organization)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("Quote", id.to_string()))
+    }
+
+    async fn list_quotes_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let tables = self.store.read()?;
+        Ok(newest_first(&tables.quotes, organization, |q| {
+            q.flow_id == flow_id
+        }))
+    }
+
+    async fn list_quotes_for_customer(
+        &self,
+        organization: &OrganizationId,
+        customer_id: CustomerId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let tables = self.store.read()?;
+        Ok(newest_first(&tables.quotes, organization, |q| {
+            q.customer_id == Some(customer_id)
+        }))
+    }
+
+    async fn update_quote(&self, quote: Quote) -> Result<Quote, DomainError> {
+        let mut tables = self.store.write().await?;
+        let stored = tables
+            .quotes
+            .iter_mut()
+            .find(|q| {
+                q.id == quote.id
+                    && q.status == QuoteStatus::Draft
+                    && q.organization_id == quote.organization_id
+            })
+            .ok_or_else(|| {
+                DomainError::conflict(format!("Quote {} is no longer a draft", quote.id))
+            })?;
+        stored.submission = quote.submission.clone();
+        stored.results = quote.results.clone();
+        stored.total = quote.total;
+        stored.validity_days = quote.validity_days;
+        stored.updated_at = quote.updated_at;
+
+        Ok(quote)
+    }
+
+    async fn list_revisions(
+        &self,
+        organization: &OrganizationId,
+        root_id: QuoteId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let mut revisions: Vec<Quote> = self
+            .store
+            .read()?
+            .quotes
+            .iter()
+            .filter(|q| q.root_id == root_id && &q.organization_id == organization)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|q| q.revision);
+
+        Ok(revisions)
+    }
+
+    async fn apply_status_change(
+        &self,
+        quote: Quote,
+        change: QuoteStatusChange,
+    ) -> Result<Quote, DomainError> {
+        let mut tables = self.store.write().await?;
+
+        // Guard on the previous status so concurrent transitions cannot both apply.
+        let stored = tables
+            .quotes
+            .iter_mut()
+            .find(|q| {
+                q.id == quote.id
+                    && q.status == change.from
+                    && q.organization_id == quote.organization_id
+            })
+            .ok_or_else(|| {
+                DomainError::conflict(format!("Quote {} is no longer {}", quote.id, change.from))
+            })?;
+        stored.status = quote.status;
+        stored.valid_until = quote.valid_until;
+        stored.updated_at = quote.updated_at;
+        tables.status_history.push(change);
+
+        Ok(quote)
+    }
+
+    async fn list_status_history(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Vec<QuoteStatusChange>, DomainError> {
+        let tables = self.store.read()?;
+        if !tables
+            .quotes
+            .iter()
+            .any(|q| q.id == id && &q.organization_id == organization)
+        {
+            return Ok(Vec::new());
+        }
+        let mut history: Vec<QuoteStatusChange> = tables
+            .status_history
+            .iter()
+            .filter(|h| h.quote_id == id)
+            .cloned()
+            .collect();
+        history.sort_by_key(|h| h.changed_at);
+
+        Ok(history)
+    }
+
+    async fn list_overdue_quotes(&self, now: DateTime<Utc>) -> Result<Vec<Quote>, DomainError> {
+        let mut quotes: Vec<Quote> = self
+            .store
+            .read()?
+            .quotes
+            .iter()
+            .filter(|q| {
+                q.status == QuoteStatus::Sent && q.valid_until.is_some_and(|until| until <= now)
+            })
+            .cloned()
+            .collect();
+        quotes.sort_by_key(|q| q.valid_until);
+
+        Ok(quotes)
+    }
+}
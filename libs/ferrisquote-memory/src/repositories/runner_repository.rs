@@ -0,0 +1,149 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+    runner::{
+        entities::{
+            ids::{RunnerSessionId, ShareLinkId},
+            session::RunnerSession,
+            share_link::ShareLink,
+        },
+        ports::{RunnerSessionRepository, ShareLinkRepository},
+    },
+    transaction::ports::Transactional,
+};
+
+use crate::{store::InMemoryStore, unit_of_work::InMemoryTransaction};
+
+/// In-memory implementation of ShareLinkRepository and RunnerSessionRepository.
+#[derive(Clone, Default)]
+pub struct InMemoryRunnerRepository {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryRunnerRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl Transactional<InMemoryTransaction> for InMemoryRunnerRepository {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self::with_store(transaction.store())
+    }
+}
+
+impl ShareLinkRepository for InMemoryRunnerRepository {
+    async fn create_share_link(&self, link: ShareLink) -> Result<ShareLink, DomainError> {
+        let mut tables = self.store.write().await?;
+
+        // Mirrors the unique constraint on `token`.
+        if tables
+            .share_links
+            .iter()
+            .any(|l| l.id == link.id || l.token == link.token)
+        {
+            return Err(DomainError::repository("Share link token already exists"));
+        }
+        tables.share_links.push(link.clone());
+
+        Ok(link)
+    }
+
+    async fn get_share_link_by_token(&self, token: &str) -> Result<ShareLink, DomainError> {
+        self.store
+            .read()?
+            .share_links
+            .iter()
+            .find(|l| l.token == token)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("ShareLink", token))
+    }
+
+    async fn list_share_links_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<ShareLink>, DomainError> {
+        let mut links: Vec<ShareLink> = self
+            .store
+            .read()?
+            .share_links
+            .iter()
+            .filter(|l| l.flow_id == flow_id && &l.organization_id == organization)
+            .cloned()
+            .collect();
+        links.sort_by_key(|row| Reverse(row.created_at));
+
+        Ok(links)
+    }
+
+    async fn revoke_share_link(
+        &self,
+        organization: &OrganizationId,
+        id: ShareLinkId,
+        at: DateTime<Utc>,
+    ) -> Result<ShareLink, DomainError> {
+        let mut tables = self.store.write().await?;
+        let link = tables
+            .share_links
+            .iter_mut()
+            .find(|l| l.id == id && &l.organization_id == organization)
+            .ok_or_else(|| DomainError::not_found("ShareLink", id.to_string()))?;
+
+        // Revoking twice keeps the original revocation time.
+        link.revoked_at.get_or_insert(at);
+
+        Ok(link.clone())
+    }
+}
+
+impl RunnerSessionRepository for InMemoryRunnerRepository {
+    async fn create_session(&self, session: RunnerSession) -> Result<RunnerSession, DomainError> {
+        let mut tables = self.store.write().await?;
+        if !tables
+            .share_links
+            .iter()
+            .any(|l| l.id == session.share_link_id)
+        {
+            return Err(DomainError::not_found(
+                "ShareLink",
+                session.share_link_id.to_string(),
+            ));
+        }
+        tables.sessions.push(session.clone());
+
+        Ok(session)
+    }
+
+    async fn get_session(&self, id: RunnerSessionId) -> Result<RunnerSession, DomainError> {
+        self.store
+            .read()?
+            .sessions
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("RunnerSession", id.to_string()))
+    }
+
+    async fn update_session(&self, session: RunnerSession) -> Result<RunnerSession, DomainError> {
+        let mut tables = self.store.write().await?;
+        let stored = tables
+            .sessions
+            .iter_mut()
+            .find(|s| s.id == session.id)
+            .ok_or_else(|| DomainError::not_found("RunnerSession", session.id.to_string()))?;
+        stored.submission = session.submission.clone();
+        stored.completed_steps = session.completed_steps.clone();
+        stored.updated_at = session.updated_at;
+
+        Ok(session)
+    }
+}
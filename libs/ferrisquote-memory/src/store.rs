@@ -0,0 +1,261 @@
+use std::{
+    fmt::Display,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use ferrisquote_domain::domain::{
+    api_key::entities::api_key::ApiKey,
+    audit::entities::entry::AuditEntry,
+    customer::entities::customer::Customer,
+    error::DomainError,
+    estimator::entities::{estimator::Estimator, ids::EstimatorId, variable::EstimatorVariable},
+    flows::entities::{
+        field::Field,
+        flow::Flow,
+        ids::{FlowId, StepId},
+        step::Step,
+    },
+    organization::entities::ids::OrganizationId,
+    quote::entities::{quote::Quote, status::QuoteStatusChange},
+    runner::entities::{session::RunnerSession, share_link::ShareLink},
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// A step row: the step without its fields, plus the owning flow.
+#[derive(Clone)]
+pub(crate) struct StepRow {
+    pub flow_id: FlowId,
+    pub step: Step,
+}
+
+/// A field row: the field plus the owning step.
+#[derive(Clone)]
+pub(crate) struct FieldRow {
+    pub step_id: StepId,
+    pub field: Field,
+}
+
+/// A variable row: the variable plus the owning estimator.
+#[derive(Clone)]
+pub(crate) struct VariableRow {
+    pub estimator_id: EstimatorId,
+    pub variable: EstimatorVariable,
+}
+
+/// Rows of every table, in insertion order.
+///
+/// Aggregates are stored flat (flows without steps, steps without fields,
+/// estimators without variables) like their Postgres tables, and reassembled on
+/// read.
+#[derive(Clone, Default)]
+pub(crate) struct Tables {
+    pub flows: Vec<Flow>,
+    pub steps: Vec<StepRow>,
+    pub fields: Vec<FieldRow>,
+    pub estimators: Vec<Estimator>,
+    pub variables: Vec<VariableRow>,
+    pub quotes: Vec<Quote>,
+    pub status_history: Vec<QuoteStatusChange>,
+    pub customers: Vec<Customer>,
+    pub share_links: Vec<ShareLink>,
+    pub sessions: Vec<RunnerSession>,
+    pub api_keys: Vec<ApiKey>,
+    pub audit_log: Vec<AuditEntry>,
+}
+
+impl Tables {
+    /// Steps of a flow with their fields, ordered by rank.
+    pub fn load_steps(&self, flow_id: FlowId) -> Vec<Step> {
+        let mut steps: Vec<Step> = self
+            .steps
+            .iter()
+            .filter(|row| row.flow_id == flow_id)
+            .map(|row| self.load_step(&row.step))
+            .collect();
+        steps.sort_by(|a, b| a.rank.cmp(&b.rank));
+        steps
+    }
+
+    /// A step with its fields, ordered by rank.
+    pub fn load_step(&self, step: &Step) -> Step {
+        let mut step = step.clone();
+        step.fields = self
+            .fields
+            .iter()
+            .filter(|row| row.step_id == step.id)
+            .map(|row| row.field.clone())
+            .collect();
+        step.fields.sort_by(|a, b| a.rank.cmp(&b.rank));
+        step
+    }
+
+    /// The row of a step whose flow belongs to `organization`.
+    pub fn owned_step(&self, organization: &OrganizationId, step_id: StepId) -> Option<&StepRow> {
+        self.steps.iter().find(|row| {
+            row.step.id == step_id
+                && self
+                    .flows
+                    .iter()
+                    .any(|f| f.id == row.flow_id && &f.organization_id == organization)
+        })
+    }
+
+    /// A flow with its steps and fields.
+    pub fn load_flow(&self, flow: &Flow) -> Flow {
+        let mut flow = flow.clone();
+        flow.steps = self.load_steps(flow.id);
+        flow
+    }
+
+    /// An estimator with its variables, ordered by rank.
+    pub fn load_estimator(&self, estimator: &Estimator) -> Estimator {
+        let mut estimator = estimator.clone();
+        estimator.variables = self
+            .variables
+            .iter()
+            .filter(|row| row.estimator_id == estimator.id)
+            .map(|row| row.variable.clone())
+            .collect();
+        estimator.variables.sort_by(|a, b| a.rank.cmp(&b.rank));
+        estimator
+    }
+
+    /// Delete a flow and everything that references it, like `ON DELETE CASCADE`.
+    pub fn cascade_flow(&mut self, flow_id: FlowId) {
+        self.flows.retain(|f| f.id != flow_id);
+        let steps: Vec<StepId> = self
+            .steps
+            .iter()
+            .filter(|row| row.flow_id == flow_id)
+            .map(|row| row.step.id)
+            .collect();
+        for step_id in steps {
+            self.cascade_step(step_id);
+        }
+        let estimators: Vec<EstimatorId> = self
+            .estimators
+            .iter()
+            .filter(|e| e.flow_id == flow_id)
+            .map(|e| e.id)
+            .collect();
+        for estimator_id in estimators {
+            self.cascade_estimator(estimator_id);
+        }
+        self.quotes.retain(|q| q.flow_id != flow_id);
+        self.share_links.retain(|l| l.flow_id != flow_id);
+        self.prune_orphans();
+    }
+
+    /// Delete a step and its fields.
+    pub fn cascade_step(&mut self, step_id: StepId) {
+        self.steps.retain(|row| row.step.id != step_id);
+        self.fields.retain(|row| row.step_id != step_id);
+    }
+
+    /// Delete an estimator and everything that references it.
+    pub fn cascade_estimator(&mut self, estimator_id: EstimatorId) {
+        self.estimators.retain(|e| e.id != estimator_id);
+        self.variables
+            .retain(|row| row.estimator_id != estimator_id);
+        self.quotes.retain(|q| q.estimator_id != estimator_id);
+        self.share_links.retain(|l| l.estimator_id != estimator_id);
+        self.prune_orphans();
+    }
+
+    /// Drop rows whose parent quote or share link no longer exists.
+    fn prune_orphans(&mut self) {
+        let quotes = &self.quotes;
+        self.quotes = quotes
+            .iter()
+            .filter(|q| quotes.iter().any(|root| root.id == q.root_id))
+            .cloned()
+            .collect();
+        let quotes = &self.quotes;
+        self.status_history
+            .retain(|h| quotes.iter().any(|q| q.id == h.quote_id));
+        let links = &self.share_links;
+        self.sessions
+            .retain(|s| links.iter().any(|l| l.id == s.share_link_id));
+    }
+}
+
+/// Increment `version`, unless `expected` is given and differs from it, like
+/// `SET version = version + 1 WHERE version = $expected` does in Postgres.
+pub(crate) fn bump_version(
+    entity: &str,
+    id: impl Display,
+    version: &mut u32,
+    expected: Option<u32>,
+) -> Result<u32, DomainError> {
+    match expected {
+        Some(expected) if expected != *version => {
+            Err(DomainError::stale_version(entity, id, expected, *version))
+        }
+        _ => {
+            *version += 1;
+            Ok(*version)
+        }
+    }
+}
+
+/// Thread-safe in-memory database shared by the in-memory repositories.
+///
+/// Repositories built on the same store see each other's rows, so checks that
+/// span aggregates (an estimator's flow must exist, deleting a flow removes its
+/// estimators) behave like they do against Postgres.
+///
+/// One transaction is open at a time. Writes made outside a transaction wait
+/// for the open one to end, so its commit cannot overwrite them.
+#[derive(Default)]
+pub struct InMemoryStore {
+    tables: RwLock<Tables>,
+    transaction: Arc<Mutex<()>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn read(&self) -> Result<RwLockReadGuard<'_, Tables>, DomainError> {
+        self.tables
+            .read()
+            .map_err(|_| DomainError::repository("in-memory store lock poisoned"))
+    }
+
+    pub(crate) async fn write(&self) -> Result<RwLockWriteGuard<'_, Tables>, DomainError> {
+        // A transaction beginning once the lock is released copies the tables
+        // after this write, since the copy waits for the write guard.
+        let _transaction = self.transaction.lock().await;
+        self.write_tables()
+    }
+
+    fn write_tables(&self) -> Result<RwLockWriteGuard<'_, Tables>, DomainError> {
+        self.tables
+            .write()
+            .map_err(|_| DomainError::repository("in-memory store lock poisoned"))
+    }
+
+    /// Wait for the open transaction to end, then take a copy of every table.
+    ///
+    /// The store stays locked until the returned guard is dropped.
+    pub(crate) async fn begin(&self) -> Result<(OwnedMutexGuard<()>, Self), DomainError> {
+        let guard = self.transaction.clone().lock_owned().await;
+        let working = Self {
+            tables: RwLock::new(self.read()?.clone()),
+            transaction: Arc::default(),
+        };
+        Ok((guard, working))
+    }
+
+    /// Replace every table with the ones of `working`, under the guard returned by `begin`.
+    pub(crate) fn commit(
+        &self,
+        working: &Self,
+        _guard: OwnedMutexGuard<()>,
+    ) -> Result<(), DomainError> {
+        let tables = working.read()?.clone();
+        *self.write_tables()? = tables;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    transaction::ports::{Transaction, UnitOfWork},
+};
+
+use tokio::sync::OwnedMutexGuard;
+
+use crate::store::InMemoryStore;
+
+/// Opens transactions on an `InMemoryStore`.
+#[derive(Clone, Default)]
+pub struct InMemoryUnitOfWork {
+    store: Arc<InMemoryStore>,
+}
+
+impl InMemoryUnitOfWork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_store(store: Arc<InMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl UnitOfWork for InMemoryUnitOfWork {
+    type Transaction = InMemoryTransaction;
+
+    async fn begin(&self) -> Result<InMemoryTransaction, DomainError> {
+        let (guard, working) = self.store.begin().await?;
+        Ok(InMemoryTransaction {
+            store: self.store.clone(),
+            working: Arc::new(working),
+            guard,
+        })
+    }
+}
+
+/// Repositories bound to the transaction work on a copy of the store, which replaces
+/// the store on commit.
+///
+/// The store is locked from `begin` until the transaction is committed, rolled back
+/// or dropped: other transactions and writes made outside a transaction wait for it.
+pub struct InMemoryTransaction {
+    store: Arc<InMemoryStore>,
+    working: Arc<InMemoryStore>,
+    guard: OwnedMutexGuard<()>,
+}
+
+impl InMemoryTransaction {
+    /// The copy of the store the repositories bound to this transaction work on.
+    pub(crate) fn store(&self) -> Arc<InMemoryStore> {
+        self.working.clone()
+    }
+}
+
+impl Transaction for InMemoryTransaction {
+    async fn commit(self) -> Result<(), DomainError> {
+        self.store.commit(&self.working, self.guard)
+    }
+
+    async fn rollback(self) -> Result<(), DomainError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! API key service running against the in-memory adapters.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::acme;
+use ferrisquote_domain::domain::{
+    api_key::{entities::api_key::IssuedApiKey, ports::ApiKeyService, services::ApiKeyServiceImpl},
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+use ferrisquote_memory::InMemoryApiKeyRepository;
+
+type Service = ApiKeyServiceImpl<InMemoryApiKeyRepository>;
+
+fn service() -> Service {
+    ApiKeyServiceImpl::new(InMemoryApiKeyRepository::new())
+}
+
+async fn issue(service: &Service) -> IssuedApiKey {
+    service
+        .create_api_key(
+            &acme(),
+            "erp".to_string(),
+            vec!["estimator:evaluate".to_string()],
+            None,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_authenticate_resolves_issued_key_and_records_use() {
+    let service = service();
+    let issued = issue(&service).await;
+
+    let api_key = service.authenticate(&issued.secret).await.unwrap();
+
+    assert_eq!(api_key.id, issued.api_key.id);
+    assert_eq!(api_key.organization_id, acme());
+    assert!(api_key.last_used_at.is_some());
+    let listed = service.list_api_keys(&acme()).await.unwrap();
+    assert!(listed[0].last_used_at.is_some());
+}
+
+#[tokio::test]
+async fn test_authenticate_rejects_unknown_and_revoked_keys() {
+    let service = service();
+    let issued = issue(&service).await;
+
+    let err = service.authenticate("fq_unknown").await.unwrap_err();
+    assert!(matches!(err, DomainError::Unauthorized { .. }));
+
+    service
+        .revoke_api_key(&acme(), issued.api_key.id)
+        .await
+        .unwrap();
+    let err = service.authenticate(&issued.secret).await.unwrap_err();
+    assert!(matches!(err, DomainError::Unauthorized { .. }));
+}
+
+#[tokio::test]
+async fn test_create_rejects_unknown_scopes_and_past_expiry() {
+    let service = service();
+
+    let err = service
+        .create_api_key(
+            &acme(),
+            "erp".to_string(),
+            vec!["flow:delete".to_string()],
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DomainError::ValidationError { .. }));
+
+    let err = service
+        .create_api_key(
+            &acme(),
+            "erp".to_string(),
+            vec!["flow:read".to_string()],
+            Some(Utc::now() - Duration::minutes(1)),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DomainError::ValidationError { .. }));
+}
+
+#[tokio::test]
+async fn test_keys_are_scoped_to_their_organization() {
+    let service = service();
+    let issued = issue(&service).await;
+    let globex = OrganizationId::new("globex");
+
+    assert!(service.list_api_keys(&globex).await.unwrap().is_empty());
+    let err = service
+        .revoke_api_key(&globex, issued.api_key.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DomainError::NotFound { .. }));
+}
@@ -0,0 +1,105 @@
+//! Audit service, and the entries the flow service writes, against the in-memory adapters.
+
+mod common;
+
+use common::{Services, acme, editor, globex, services};
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{
+            entry::{AuditAction, AuditEntity},
+            query::AuditFilter,
+        },
+        ports::AuditService,
+    },
+    error::DomainError,
+    flows::ports::{FlowService, StepService},
+};
+
+#[tokio::test]
+async fn test_updates_without_changes_are_not_recorded() {
+    let Services { flows, audit, .. } = services();
+    let flow = flows
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+
+    flows
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            Some("Kitchen".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let page = audit
+        .list_entries(&acme(), AuditFilter::default(), 1, 20)
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.entries[0].action, AuditAction::Create);
+    assert_eq!(page.entries[0].actor, editor());
+}
+
+#[tokio::test]
+async fn test_entries_are_paginated_newest_first_per_organization() {
+    let Services { flows, audit, .. } = services();
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        let flow = flows
+            .create_flow(&acme(), &editor(), format!("Flow {i}"))
+            .await
+            .unwrap();
+        ids.push(flow.id.to_string());
+    }
+    flows
+        .create_flow(&globex(), &editor(), "Theirs".to_string())
+        .await
+        .unwrap();
+
+    let page = audit
+        .list_entries(&acme(), AuditFilter::default(), 2, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(page.total, 5);
+    let listed: Vec<&str> = page.entries.iter().map(|e| e.entity_id.as_str()).collect();
+    assert_eq!(listed, vec![ids[2].as_str(), ids[1].as_str()]);
+}
+
+#[tokio::test]
+async fn test_entries_can_be_filtered_by_entity() {
+    let Services { flows, audit, .. } = services();
+    let flow = flows
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = flows
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+
+    let filter = AuditFilter {
+        entity: Some(AuditEntity::Step),
+        entity_id: None,
+    };
+    let page = audit.list_entries(&acme(), filter, 1, 10).await.unwrap();
+
+    assert_eq!(page.total, 1);
+    assert_eq!(page.entries[0].entity_id, step.id.to_string());
+}
+
+#[tokio::test]
+async fn test_page_bounds_are_validated() {
+    let audit = services().audit;
+
+    for (page, per_page) in [(0, 10), (1, 0), (1, 101)] {
+        let result = audit
+            .list_entries(&acme(), AuditFilter::default(), page, per_page)
+            .await;
+        assert!(matches!(result, Err(DomainError::ValidationError { .. })));
+    }
+}
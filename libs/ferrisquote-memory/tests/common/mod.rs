@@ -0,0 +1,186 @@
+//! Fixtures shared by the service tests.
+
+// Each test crate uses only some of the fixtures.
+#![allow(dead_code)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{
+            actor::{Actor, ActorKind},
+            entry::AuditEntry,
+            query::AuditFilter,
+        },
+        ports::AuditRepository,
+        services::AuditServiceImpl,
+    },
+    customer::services::CustomerServiceImpl,
+    error::DomainError,
+    estimator::{entities::submission::SubmissionData, services::EstimatorServiceImpl},
+    flows::services::FlowServiceImpl,
+    organization::entities::ids::OrganizationId,
+    quote::services::QuoteServiceImpl,
+    rank::services::LexoRankProvider,
+    transaction::ports::Transactional,
+};
+use ferrisquote_memory::{
+    InMemoryAuditRepository, InMemoryCustomerRepository, InMemoryEstimatorRepository,
+    InMemoryFlowRepository, InMemoryQuoteRepository, InMemoryStore, InMemoryUnitOfWork,
+    unit_of_work::InMemoryTransaction,
+};
+
+pub type Flows<AR = InMemoryAuditRepository> = FlowServiceImpl<
+    InMemoryFlowRepository,
+    InMemoryFlowRepository,
+    InMemoryFlowRepository,
+    LexoRankProvider,
+    AR,
+    InMemoryUnitOfWork,
+>;
+
+pub type Estimators<AR = InMemoryAuditRepository> = EstimatorServiceImpl<
+    InMemoryEstimatorRepository,
+    LexoRankProvider,
+    InMemoryCustomerRepository,
+    AR,
+    InMemoryUnitOfWork,
+>;
+
+pub type Quotes<AR = InMemoryAuditRepository> = QuoteServiceImpl<
+    InMemoryQuoteRepository,
+    InMemoryEstimatorRepository,
+    InMemoryCustomerRepository,
+    AR,
+    InMemoryUnitOfWork,
+>;
+
+pub type Customers = CustomerServiceImpl<InMemoryCustomerRepository>;
+
+/// Every service on one store, so each sees the rows of the others and writes
+/// to the audit log read by `audit`.
+pub struct Services {
+    pub flows: Flows,
+    pub estimators: Estimators,
+    pub quotes: Quotes,
+    pub customers: Customers,
+    pub audit: AuditServiceImpl<InMemoryAuditRepository>,
+}
+
+pub fn services() -> Services {
+    services_on(Arc::new(InMemoryStore::new()))
+}
+
+pub fn services_on(store: Arc<InMemoryStore>) -> Services {
+    let audit = InMemoryAuditRepository::with_store(store.clone());
+    Services {
+        flows: flows_on(store.clone(), audit.clone()),
+        estimators: estimators_on(store.clone(), audit.clone()),
+        quotes: quotes_on(store.clone(), audit.clone()),
+        customers: CustomerServiceImpl::new(InMemoryCustomerRepository::with_store(store)),
+        audit: AuditServiceImpl::new(audit),
+    }
+}
+
+pub fn flows_on<AR>(store: Arc<InMemoryStore>, audit: AR) -> Flows<AR> {
+    let flows = InMemoryFlowRepository::with_store(store.clone());
+    FlowServiceImpl::new(
+        flows.clone(),
+        flows.clone(),
+        flows,
+        LexoRankProvider,
+        audit,
+        InMemoryUnitOfWork::with_store(store),
+    )
+}
+
+pub fn estimators_on<AR>(store: Arc<InMemoryStore>, audit: AR) -> Estimators<AR> {
+    EstimatorServiceImpl::new(
+        InMemoryEstimatorRepository::with_store(store.clone()),
+        LexoRankProvider,
+        InMemoryCustomerRepository::with_store(store.clone()),
+        audit,
+        InMemoryUnitOfWork::with_store(store),
+    )
+}
+
+pub fn quotes_on<AR>(store: Arc<InMemoryStore>, audit: AR) -> Quotes<AR> {
+    QuoteServiceImpl::new(
+        InMemoryQuoteRepository::with_store(store.clone()),
+        InMemoryEstimatorRepository::with_store(store.clone()),
+        InMemoryCustomerRepository::with_store(store.clone()),
+        audit,
+        InMemoryUnitOfWork::with_store(store),
+    )
+}
+
+/// An audit log that rejects every entry, so no change can be recorded.
+#[derive(Clone)]
+pub struct UnavailableAuditLog;
+
+impl Transactional<InMemoryTransaction> for UnavailableAuditLog {
+    fn in_transaction(&self, _transaction: &InMemoryTransaction) -> Self {
+        self.clone()
+    }
+}
+
+impl AuditRepository for UnavailableAuditLog {
+    async fn append_entry(&self, _entry: AuditEntry) -> Result<AuditEntry, DomainError> {
+        Err(DomainError::repository("audit log unavailable"))
+    }
+
+    async fn list_entries(
+        &self,
+        _organization: &OrganizationId,
+        _filter: &AuditFilter,
+        _offset: u64,
+        _limit: u64,
+    ) -> Result<(Vec<AuditEntry>, u64), DomainError> {
+        Ok((Vec::new(), 0))
+    }
+}
+
+pub fn editor() -> Actor {
+    Actor::new(
+        ActorKind::User,
+        Some("u-1".to_string()),
+        Some("alice".to_string()),
+    )
+}
+
+pub fn acme() -> OrganizationId {
+    OrganizationId::new("acme")
+}
+
+pub fn globex() -> OrganizationId {
+    OrganizationId::new("globex")
+}
+
+/// A submission answering `surface` with `value`.
+pub fn surface(value: f64) -> SubmissionData {
+    SubmissionData {
+        field_values: HashMap::from([("surface".to_string(), value)]),
+        ..Default::default()
+    }
+}
+
+pub fn assert_not_found<T: std::fmt::Debug>(result: Result<T, DomainError>) {
+    assert!(
+        matches!(result, Err(DomainError::NotFound { .. })),
+        "expected NotFound, got {result:?}"
+    );
+}
+
+pub fn assert_conflict<T: std::fmt::Debug>(result: Result<T, DomainError>) {
+    assert!(
+        matches!(result, Err(DomainError::Conflict { .. })),
+        "expected Conflict, got {result:?}"
+    );
+}
+
+pub fn assert_validation<T: std::fmt::Debug>(result: Result<T, DomainError>, message: &str) {
+    match result {
+        Err(DomainError::ValidationError { message: actual }) => assert_eq!(actual, message),
+        other => panic!("expected a validation error, got {other:?}"),
+    }
+}
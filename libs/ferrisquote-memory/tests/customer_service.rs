@@ -0,0 +1,156 @@
+//! Customer service, and the services that use customers, against the in-memory adapters.
+
+mod common;
+
+use common::{Services, acme, assert_not_found, editor, globex, services, surface};
+use ferrisquote_domain::domain::{
+    customer::{
+        entities::customer::{Customer, CustomerDetails},
+        ports::CustomerService,
+    },
+    estimator::{entities::estimator::Estimator, ports::EstimatorService},
+    flows::ports::FlowService,
+    organization::entities::ids::OrganizationId,
+    quote::ports::QuoteService,
+};
+
+fn details(contact_name: &str, discount_rate: f64) -> CustomerDetails {
+    CustomerDetails {
+        contact_name: contact_name.to_string(),
+        email: format!("{}@example.com", contact_name.to_lowercase()),
+        discount_rate,
+        ..Default::default()
+    }
+}
+
+async fn customer_of(services: &Services, organization: &OrganizationId) -> Customer {
+    services
+        .customers
+        .create_customer(organization, details("Jane", 0.5))
+        .await
+        .unwrap()
+}
+
+/// An estimator of `acme` pricing 100 per square metre, less the customer discount.
+async fn pricing(services: &Services) -> Estimator {
+    let flow = services
+        .flows
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let estimator = services
+        .estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    services
+        .estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "total".to_string(),
+            "@surface * 100.0 * (1.0 - @customer_discount_rate)".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+    estimator
+}
+
+#[tokio::test]
+async fn test_customers_are_listed_per_organization() {
+    let services = services();
+    let ours = customer_of(&services, &acme()).await;
+    customer_of(&services, &globex()).await;
+
+    let listed = services.customers.list_customers(&acme()).await.unwrap();
+
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, ours.id);
+    assert_eq!(listed[0].organization_id, acme());
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_read_or_mutate_a_customer() {
+    let services = services();
+    let customer = customer_of(&services, &acme()).await;
+
+    assert_not_found(
+        services
+            .customers
+            .get_customer(&globex(), customer.id)
+            .await,
+    );
+    assert_not_found(
+        services
+            .customers
+            .update_customer(&globex(), customer.id, details("Mallory", 1.0))
+            .await,
+    );
+    assert_not_found(
+        services
+            .customers
+            .delete_customer(&globex(), customer.id)
+            .await,
+    );
+
+    let stored = services
+        .customers
+        .get_customer(&acme(), customer.id)
+        .await
+        .unwrap();
+    assert_eq!(stored.contact_name, "Jane");
+    assert_eq!(stored.discount_rate, 0.5);
+}
+
+#[tokio::test]
+async fn test_customers_of_another_organization_cannot_be_used() {
+    let services = services();
+    let estimator = pricing(&services).await;
+    let ours = customer_of(&services, &acme()).await;
+    let theirs = customer_of(&services, &globex()).await;
+
+    assert_not_found(
+        services
+            .estimators
+            .evaluate_submission(&acme(), estimator.id, surface(10.0), Some(theirs.id))
+            .await,
+    );
+    assert_not_found(
+        services
+            .quotes
+            .create_quote(
+                &acme(),
+                &editor(),
+                estimator.id,
+                surface(10.0),
+                Some(theirs.id),
+                None,
+            )
+            .await,
+    );
+    assert!(
+        services
+            .quotes
+            .list_quotes_for_customer(&acme(), theirs.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let quote = services
+        .quotes
+        .create_quote(
+            &acme(),
+            &editor(),
+            estimator.id,
+            surface(10.0),
+            Some(ours.id),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(quote.customer_id, Some(ours.id));
+    assert_eq!(quote.total, 500.0);
+}
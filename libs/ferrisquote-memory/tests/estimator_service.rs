@@ -0,0 +1,735 @@
+//! Estimator service running against the in-memory adapters.
+
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use common::{
+    Estimators, Flows, Services, UnavailableAuditLog, acme, assert_not_found, assert_validation,
+    editor, estimators_on, globex, services, services_on,
+};
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    estimator::{
+        entities::{
+            estimator::Estimator,
+            ids::{EstimatorId, EstimatorVariableId},
+        },
+        ports::EstimatorService,
+    },
+    flows::{
+        entities::{flow::Flow, query::ListQuery},
+        ports::FlowService,
+    },
+    rank::{entities::Rank, ports::RankService, services::LexoRankProvider},
+};
+use ferrisquote_memory::InMemoryStore;
+
+async fn kitchen(flows: &Flows) -> Flow {
+    flows
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap()
+}
+
+/// An estimator of `acme` with one variable per name, in that order.
+async fn with_variables(
+    flows: &Flows,
+    estimators: &Estimators,
+    names: &[&str],
+) -> (EstimatorId, Vec<EstimatorVariableId>) {
+    let flow = kitchen(flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in names {
+        let variable = estimators
+            .add_variable(
+                &acme(),
+                &editor(),
+                estimator.id,
+                name.to_string(),
+                "1.0".to_string(),
+                String::new(),
+            )
+            .await
+            .unwrap();
+        ids.push(variable.id);
+    }
+    (estimator.id, ids)
+}
+
+fn variable_names(estimator: &Estimator) -> Vec<&str> {
+    estimator
+        .variables
+        .iter()
+        .map(|v| v.name.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_estimator_requires_a_flow_of_the_same_organization() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+
+    assert_not_found(
+        estimators
+            .create_estimator(&globex(), &editor(), flow.id, "Pricing".to_string())
+            .await,
+    );
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+
+    let listed = estimators
+        .list_estimators_for_flow(&acme(), flow.id, ListQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(listed.items.len(), 1);
+    assert_eq!(listed.items[0].id, estimator.id);
+    assert!(
+        estimators
+            .list_estimators_for_flow(&globex(), flow.id, ListQuery::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_update_estimator_keeps_unset_fields() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "vat".to_string(),
+            "0.2".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+
+    let unchanged = estimators
+        .update_estimator(&acme(), &editor(), estimator.id, None, None)
+        .await
+        .unwrap();
+    let renamed = estimators
+        .update_estimator(
+            &acme(),
+            &editor(),
+            estimator.id,
+            Some("Labour".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(unchanged.name, "Pricing");
+    assert_eq!(renamed.name, "Labour");
+    assert_eq!(renamed.variables.len(), 1);
+}
+
+#[tokio::test]
+async fn test_variables_are_ranked_and_reordered() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in ["a", "b", "c"] {
+        let variable = estimators
+            .add_variable(
+                &acme(),
+                &editor(),
+                estimator.id,
+                name.to_string(),
+                "1.0".to_string(),
+                String::new(),
+            )
+            .await
+            .unwrap();
+        ids.push(variable.id);
+    }
+
+    let estimator = estimators
+        .reorder_variable(&acme(), &editor(), ids[2], None, Some(ids[0]), None)
+        .await
+        .unwrap();
+
+    let names: Vec<&str> = estimator
+        .variables
+        .iter()
+        .map(|v| v.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["c", "a", "b"]);
+}
+
+#[tokio::test]
+async fn test_variable_can_be_moved_after_another() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b", "c"]).await;
+
+    let estimator = estimators
+        .reorder_variable(&acme(), &editor(), ids[0], Some(ids[2]), None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(variable_names(&estimator), vec!["b", "c", "a"]);
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(variable_names(&stored), vec!["b", "c", "a"]);
+}
+
+#[tokio::test]
+async fn test_variable_can_be_moved_before_another() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b", "c"]).await;
+
+    estimators
+        .reorder_variable(&acme(), &editor(), ids[2], None, Some(ids[1]), None)
+        .await
+        .unwrap();
+
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(variable_names(&stored), vec!["a", "c", "b"]);
+}
+
+#[tokio::test]
+async fn test_variable_can_be_moved_between_two_others() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b", "c", "d"]).await;
+
+    estimators
+        .reorder_variable(&acme(), &editor(), ids[3], Some(ids[0]), Some(ids[1]), None)
+        .await
+        .unwrap();
+
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(variable_names(&stored), vec!["a", "d", "b", "c"]);
+    assert!(stored.variables[0].rank < stored.variables[1].rank);
+    assert!(stored.variables[1].rank < stored.variables[2].rank);
+}
+
+#[tokio::test]
+async fn test_lone_variable_can_be_reordered_without_neighbours() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a"]).await;
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    let rank = stored.variables[0].rank.clone();
+
+    let estimator = estimators
+        .reorder_variable(&acme(), &editor(), ids[0], None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(variable_names(&estimator), vec!["a"]);
+    assert_eq!(estimator.variables[0].rank, rank);
+}
+
+#[tokio::test]
+async fn test_variables_cannot_be_placed_next_to_variables_of_another_estimator() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b"]).await;
+    let (_, others) = with_variables(&flows, &estimators, &["x"]).await;
+
+    assert_validation(
+        estimators
+            .reorder_variable(&acme(), &editor(), ids[1], Some(others[0]), None, None)
+            .await,
+        &format!(
+            "EstimatorVariable {} does not belong to estimator {id}",
+            others[0]
+        ),
+    );
+
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(variable_names(&stored), vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_variable_neighbours_must_be_ordered() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b", "c"]).await;
+
+    assert_validation(
+        estimators
+            .reorder_variable(&acme(), &editor(), ids[0], Some(ids[2]), Some(ids[1]), None)
+            .await,
+        &format!(
+            "EstimatorVariable {} must come before EstimatorVariable {}",
+            ids[2], ids[1]
+        ),
+    );
+    assert_validation(
+        estimators
+            .reorder_variable(&acme(), &editor(), ids[0], Some(ids[0]), None, None)
+            .await,
+        &format!(
+            "EstimatorVariable {} cannot be placed next to itself",
+            ids[0]
+        ),
+    );
+    assert_validation(
+        estimators
+            .reorder_variable(&acme(), &editor(), ids[0], None, Some(ids[0]), None)
+            .await,
+        &format!(
+            "EstimatorVariable {} cannot be placed next to itself",
+            ids[0]
+        ),
+    );
+
+    let stored = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(variable_names(&stored), vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn test_variable_reordered_without_neighbours_goes_to_the_end() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (_, ids) = with_variables(&flows, &estimators, &["a", "b", "c"]).await;
+
+    let estimator = estimators
+        .reorder_variable(&acme(), &editor(), ids[0], None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(variable_names(&estimator), vec!["b", "c", "a"]);
+    let mut ranks: Vec<&str> = estimator
+        .variables
+        .iter()
+        .map(|v| v.rank.as_str())
+        .collect();
+    ranks.dedup();
+    assert_eq!(ranks.len(), 3);
+}
+
+#[tokio::test]
+async fn test_variables_are_rebalanced_when_their_ranks_grow_too_long() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let (_, ids) = with_variables(&flows, &estimators, &["first", "moved", "next"]).await;
+    let (first, mut moved, mut next) = (ids[0], ids[1], ids[2]);
+
+    // Always drop a variable right behind the first one, halving the same gap.
+    for _ in 0..200 {
+        let estimator = estimators
+            .reorder_variable(&acme(), &editor(), moved, Some(first), Some(next), None)
+            .await
+            .unwrap();
+
+        let order: Vec<EstimatorVariableId> = estimator.variables.iter().map(|v| v.id).collect();
+        assert_eq!(order[..2], [first, moved]);
+        assert!(
+            estimator
+                .variables
+                .iter()
+                .all(|v| !LexoRankProvider.needs_rebalance(&Rank::from_string(v.rank.clone())))
+        );
+        std::mem::swap(&mut moved, &mut next);
+    }
+}
+
+#[tokio::test]
+async fn test_update_variable_keeps_unset_fields() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let variable = estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "total".to_string(),
+            "@surface * 10.0".to_string(),
+            "Price per square metre".to_string(),
+        )
+        .await
+        .unwrap();
+
+    let updated = estimators
+        .update_variable(
+            &acme(),
+            &editor(),
+            variable.id,
+            None,
+            Some("@surface * 12.0".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated.name, "total");
+    assert_eq!(updated.expression, "@surface * 12.0");
+    assert_eq!(updated.description, "Price per square metre");
+    assert_eq!(updated.rank, variable.rank);
+}
+
+#[tokio::test]
+async fn test_evaluate_uses_stored_variables() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    for (name, expression) in [("ht", "@surface * 100.0"), ("ttc", "@ht * 1.2")] {
+        estimators
+            .add_variable(
+                &acme(),
+                &editor(),
+                estimator.id,
+                name.to_string(),
+                expression.to_string(),
+                String::new(),
+            )
+            .await
+            .unwrap();
+    }
+
+    let results = estimators
+        .evaluate(
+            &acme(),
+            estimator.id,
+            HashMap::from([("surface".to_string(), 10.0)]),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(results["ht"], 1000.0);
+    assert!((results["ttc"] - 1200.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_removed_variables_and_estimators_are_not_found() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let variable = estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "vat".to_string(),
+            "0.2".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+
+    estimators
+        .remove_variable(&acme(), &editor(), variable.id, None)
+        .await
+        .unwrap();
+    assert_not_found(
+        estimators
+            .remove_variable(&acme(), &editor(), variable.id, None)
+            .await,
+    );
+    assert_not_found(
+        estimators
+            .update_variable(
+                &acme(),
+                &editor(),
+                variable.id,
+                Some("tva".to_string()),
+                None,
+                None,
+                None,
+            )
+            .await,
+    );
+
+    estimators
+        .delete_estimator(&acme(), &editor(), estimator.id, None)
+        .await
+        .unwrap();
+    assert_not_found(estimators.get_estimator(&acme(), estimator.id).await);
+    assert_not_found(
+        estimators
+            .delete_estimator(&acme(), &editor(), estimator.id, None)
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_touch_estimators() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let variable = estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "vat".to_string(),
+            "0.2".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+
+    assert_not_found(estimators.get_estimator(&globex(), estimator.id).await);
+    assert_not_found(
+        estimators
+            .update_estimator(&globex(), &editor(), estimator.id, None, None)
+            .await,
+    );
+    assert_not_found(
+        estimators
+            .add_variable(
+                &globex(),
+                &editor(),
+                estimator.id,
+                "x".to_string(),
+                "1.0".to_string(),
+                String::new(),
+            )
+            .await,
+    );
+    assert_not_found(
+        estimators
+            .update_variable(&globex(), &editor(), variable.id, None, None, None, None)
+            .await,
+    );
+    assert_not_found(
+        estimators
+            .remove_variable(&globex(), &editor(), variable.id, None)
+            .await,
+    );
+    assert_not_found(
+        estimators
+            .evaluate(&globex(), estimator.id, HashMap::new())
+            .await,
+    );
+
+    assert_eq!(
+        estimators
+            .get_estimator(&acme(), estimator.id)
+            .await
+            .unwrap()
+            .variables
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_deleting_a_flow_deletes_its_estimators() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+
+    flows
+        .delete_flow(&acme(), &editor(), flow.id, None)
+        .await
+        .unwrap();
+
+    assert_not_found(estimators.get_estimator(&acme(), estimator.id).await);
+}
+
+#[tokio::test]
+async fn test_writes_against_a_stale_version_conflict() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let estimator = estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    let variable = estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "vat".to_string(),
+            "0.2".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+
+    let renamed = estimators
+        .update_estimator(
+            &acme(),
+            &editor(),
+            estimator.id,
+            Some("Labour".to_string()),
+            Some(1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(renamed.version, 2);
+
+    let stale_rename = estimators
+        .update_estimator(
+            &acme(),
+            &editor(),
+            estimator.id,
+            Some("Paint".to_string()),
+            Some(1),
+        )
+        .await;
+    let stale_delete = estimators
+        .delete_estimator(&acme(), &editor(), estimator.id, Some(1))
+        .await;
+    let stale_removal = estimators
+        .remove_variable(&acme(), &editor(), variable.id, Some(2))
+        .await;
+
+    assert!(matches!(stale_rename, Err(DomainError::Conflict { .. })));
+    assert!(matches!(stale_delete, Err(DomainError::Conflict { .. })));
+    assert!(matches!(stale_removal, Err(DomainError::Conflict { .. })));
+    let current = estimators
+        .get_estimator(&acme(), estimator.id)
+        .await
+        .unwrap();
+    assert_eq!(current.name, "Labour");
+    assert_eq!(current.variables.len(), 1);
+}
+
+#[tokio::test]
+async fn test_estimators_are_paged_newest_first() {
+    let Services {
+        flows, estimators, ..
+    } = services();
+    let flow = kitchen(&flows).await;
+    let mut ids = Vec::new();
+    for name in ["Pricing", "Labour", "Materials"] {
+        let estimator = estimators
+            .create_estimator(&acme(), &editor(), flow.id, name.to_string())
+            .await
+            .unwrap();
+        ids.push(estimator.id);
+    }
+    let query = ListQuery {
+        limit: 2,
+        ..ListQuery::default()
+    };
+
+    let first = estimators
+        .list_estimators_for_flow(&acme(), flow.id, query.clone())
+        .await
+        .unwrap();
+    let second = estimators
+        .list_estimators_for_flow(
+            &acme(),
+            flow.id,
+            ListQuery {
+                after: first.next,
+                ..query
+            },
+        )
+        .await
+        .unwrap();
+
+    let listed: Vec<_> = first
+        .items
+        .iter()
+        .chain(&second.items)
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(listed, vec![ids[2], ids[1], ids[0]]);
+    assert_eq!(second.next, None);
+}
+
+#[tokio::test]
+async fn test_changes_that_cannot_be_audited_are_not_stored() {
+    let store = Arc::new(InMemoryStore::new());
+    let Services {
+        flows, estimators, ..
+    } = services_on(store.clone());
+    let (id, ids) = with_variables(&flows, &estimators, &["a", "b"]).await;
+    let unaudited = estimators_on(store, UnavailableAuditLog);
+
+    let rename = unaudited
+        .update_estimator(&acme(), &editor(), id, Some("Labour".to_string()), None)
+        .await;
+    let addition = unaudited
+        .add_variable(
+            &acme(),
+            &editor(),
+            id,
+            "c".to_string(),
+            "1.0".to_string(),
+            String::new(),
+        )
+        .await;
+    let reorder = unaudited
+        .reorder_variable(&acme(), &editor(), ids[0], Some(ids[1]), None, None)
+        .await;
+    let removal = unaudited
+        .remove_variable(&acme(), &editor(), ids[1], None)
+        .await;
+
+    for result in [
+        rename.map(drop),
+        addition.map(drop),
+        reorder.map(drop),
+        removal,
+    ] {
+        assert!(
+            matches!(result, Err(DomainError::RepositoryError { .. })),
+            "expected RepositoryError, got {result:?}"
+        );
+    }
+    let current = estimators.get_estimator(&acme(), id).await.unwrap();
+    assert_eq!(current.name, "Pricing");
+    assert_eq!(variable_names(&current), vec!["a", "b"]);
+}
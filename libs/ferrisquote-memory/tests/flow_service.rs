@@ -0,0 +1,1526 @@
+//! Flow, step and field services running against the in-memory adapters.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    Flows, Services, UnavailableAuditLog, acme, assert_conflict, assert_not_found,
+    assert_validation, editor, flows_on, globex, services, services_on,
+};
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{
+            entry::{AuditAction, AuditEntity},
+            query::AuditFilter,
+        },
+        ports::AuditService,
+    },
+    error::DomainError,
+    flows::{
+        entities::{
+            field::FieldConfig,
+            flow::Flow,
+            ids::{FieldId, StepId},
+            layout::{FlowLayout, StepLayout},
+            query::{ListQuery, ListSort, SortOrder},
+        },
+        ports::{FieldService, FlowService, StepService},
+    },
+    rank::{entities::Rank, ports::RankService, services::LexoRankProvider},
+};
+use ferrisquote_memory::InMemoryStore;
+
+/// A flow of `acme` with the steps `titles`, each holding a field per key in `keys`.
+async fn flow_with_steps(service: &Flows, titles: &[&str], keys: &[&str]) -> Flow {
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    for title in titles {
+        let step = service
+            .add_step(&acme(), &editor(), flow.id, title.to_string())
+            .await
+            .unwrap();
+        for key in keys {
+            service
+                .add_field(
+                    &acme(),
+                    &editor(),
+                    step.id,
+                    key.to_string(),
+                    key.to_string(),
+                    FieldConfig::new_text(32),
+                )
+                .await
+                .unwrap();
+        }
+    }
+    service.get_flow(&acme(), flow.id).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_flows_are_listed_newest_first_per_organization() {
+    let service = services().flows;
+    let first = service
+        .create_flow(&acme(), &editor(), "First".to_string())
+        .await
+        .unwrap();
+    let second = service
+        .create_flow(&acme(), &editor(), "Second".to_string())
+        .await
+        .unwrap();
+    service
+        .create_flow(&globex(), &editor(), "Theirs".to_string())
+        .await
+        .unwrap();
+
+    let flows = service
+        .list_flows(&acme(), ListQuery::default())
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = flows.items.iter().map(|f| f.id).collect();
+    assert_eq!(ids, vec![second.id, first.id]);
+}
+
+#[tokio::test]
+async fn test_flows_are_searched_and_paged_by_name() {
+    let service = services().flows;
+    let mut ids = Vec::new();
+    for name in ["kitchen", "Bathroom", "Kitchenette", "attic"] {
+        let flow = service
+            .create_flow(&acme(), &editor(), name.to_string())
+            .await
+            .unwrap();
+        ids.push(flow.id);
+    }
+    for title in ["Size", "Finish"] {
+        service
+            .add_step(&acme(), &editor(), ids[2], title.to_string())
+            .await
+            .unwrap();
+    }
+    let query = ListQuery {
+        search: Some("KITCHEN".to_string()),
+        sort: ListSort::Name,
+        order: SortOrder::Asc,
+        limit: 1,
+        ..ListQuery::default()
+    };
+
+    let first = service.list_flows(&acme(), query.clone()).await.unwrap();
+    let second = service
+        .list_flows(
+            &acme(),
+            ListQuery {
+                after: first.next.clone(),
+                ..query.clone()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(first.items[0].id, ids[0]);
+    assert_eq!(first.next.as_ref().map(|cursor| cursor.id), Some(ids[0]));
+    assert_eq!(second.items[0].id, ids[2]);
+    assert_eq!(second.items[0].step_count, 2);
+    assert_eq!(second.next, None);
+}
+
+#[tokio::test]
+async fn test_name_pages_survive_changes_to_the_cursor_flow() {
+    let service = services().flows;
+    let mut ids = Vec::new();
+    for name in ["Attic", "Bathroom", "Kitchen"] {
+        let flow = service
+            .create_flow(&acme(), &editor(), name.to_string())
+            .await
+            .unwrap();
+        ids.push(flow.id);
+    }
+    let query = ListQuery {
+        sort: ListSort::Name,
+        order: SortOrder::Asc,
+        limit: 1,
+        ..ListQuery::default()
+    };
+    let first = service.list_flows(&acme(), query.clone()).await.unwrap();
+    let next = ListQuery {
+        after: first.next,
+        ..query
+    };
+
+    // Renamed to sort last, the cursor flow neither shows up again nor skips
+    // the flows that followed it.
+    service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            ids[0],
+            Some("Zinc".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let second = service.list_flows(&acme(), next.clone()).await.unwrap();
+    assert_eq!(second.items[0].id, ids[1]);
+
+    service
+        .delete_flow(&acme(), &editor(), ids[0], None)
+        .await
+        .unwrap();
+    let second = service.list_flows(&acme(), next).await.unwrap();
+    assert_eq!(second.items[0].id, ids[1]);
+}
+
+#[tokio::test]
+async fn test_update_flow_metadata_keeps_unset_fields() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            None,
+            Some("Full renovation".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated = service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            Some("Bathroom".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated.name, "Bathroom");
+    assert_eq!(updated.description, "Full renovation");
+}
+
+#[tokio::test]
+async fn test_missing_flow_is_not_found() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    service
+        .delete_flow(&acme(), &editor(), flow.id, None)
+        .await
+        .unwrap();
+
+    assert_not_found(service.get_flow(&acme(), flow.id).await);
+    assert_not_found(
+        service
+            .update_flow_metadata(&acme(), &editor(), flow.id, None, None, None)
+            .await,
+    );
+    assert_not_found(service.delete_flow(&acme(), &editor(), flow.id, None).await);
+    assert_not_found(
+        service
+            .add_step(&acme(), &editor(), flow.id, "Step".to_string())
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn test_steps_are_appended_and_reordered_by_rank() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let a = service
+        .add_step(&acme(), &editor(), flow.id, "A".to_string())
+        .await
+        .unwrap();
+    let b = service
+        .add_step(&acme(), &editor(), flow.id, "B".to_string())
+        .await
+        .unwrap();
+    let c = service
+        .add_step(&acme(), &editor(), flow.id, "C".to_string())
+        .await
+        .unwrap();
+
+    let flow = service
+        .reorder_step(&acme(), &editor(), c.id, None, Some(a.id), None)
+        .await
+        .unwrap();
+
+    let order: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+    assert_eq!(order, vec![c.id, a.id, b.id]);
+
+    let flow = service
+        .reorder_step(&acme(), &editor(), c.id, Some(a.id), Some(b.id), None)
+        .await
+        .unwrap();
+
+    let order: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+    assert_eq!(order, vec![a.id, c.id, b.id]);
+}
+
+#[tokio::test]
+async fn test_update_step_metadata_keeps_unset_fields() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Rooms".to_string())
+        .await
+        .unwrap();
+    service
+        .update_step_metadata(
+            &acme(),
+            &editor(),
+            step.id,
+            None,
+            Some("One per room".to_string()),
+            Some(true),
+            Some(Some("Room".to_string())),
+            Some(1),
+            Some(Some(5)),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated = service
+        .update_step_metadata(
+            &acme(),
+            &editor(),
+            step.id,
+            Some("Areas".to_string()),
+            None,
+            None,
+            None,
+            None,
+            Some(None),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated.title, "Areas");
+    assert_eq!(updated.description, "One per room");
+    assert!(updated.is_repeatable);
+    assert_eq!(updated.repeat_label.as_deref(), Some("Room"));
+    assert_eq!(updated.min_repeats, 1);
+    assert_eq!(updated.max_repeats, None);
+    assert_eq!(updated.rank, step.rank);
+}
+
+#[tokio::test]
+async fn test_removed_step_takes_its_fields_along() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let field = service
+        .add_field(
+            &acme(),
+            &editor(),
+            step.id,
+            "Surface".to_string(),
+            "surface".to_string(),
+            FieldConfig::new_number(None, None),
+        )
+        .await
+        .unwrap();
+
+    service
+        .remove_step(&acme(), &editor(), step.id, None)
+        .await
+        .unwrap();
+
+    assert!(
+        service
+            .get_flow(&acme(), flow.id)
+            .await
+            .unwrap()
+            .steps
+            .is_empty()
+    );
+    assert_not_found(service.remove_step(&acme(), &editor(), step.id, None).await);
+    assert_not_found(
+        service
+            .remove_field(&acme(), &editor(), field.id, None)
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn test_update_field_config_keeps_unset_fields() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let field = service
+        .add_field(
+            &acme(),
+            &editor(),
+            step.id,
+            "Surface".to_string(),
+            "surface".to_string(),
+            FieldConfig::new_number(Some(1.0), Some(500.0)),
+        )
+        .await
+        .unwrap();
+
+    let updated = service
+        .update_field_config(
+            &acme(),
+            &editor(),
+            field.id,
+            Some("Floor area".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated.label, "Floor area");
+    assert_eq!(updated.key, "surface");
+    assert!(matches!(
+        updated.config,
+        FieldConfig::Number(ref n) if n.min == Some(1.0) && n.max == Some(500.0)
+    ));
+}
+
+#[tokio::test]
+async fn test_fields_can_be_moved_across_steps() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let size = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let finish = service
+        .add_step(&acme(), &editor(), flow.id, "Finish".to_string())
+        .await
+        .unwrap();
+    let surface = service
+        .add_field(
+            &acme(),
+            &editor(),
+            size.id,
+            "Surface".to_string(),
+            "surface".to_string(),
+            FieldConfig::new_number(None, None),
+        )
+        .await
+        .unwrap();
+    let color = service
+        .add_field(
+            &acme(),
+            &editor(),
+            finish.id,
+            "Color".to_string(),
+            "color".to_string(),
+            FieldConfig::new_text(32),
+        )
+        .await
+        .unwrap();
+
+    let flow = service
+        .move_field(
+            &acme(),
+            &editor(),
+            surface.id,
+            Some(finish.id),
+            None,
+            Some(color.id),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(flow.get_step(&size.id).unwrap().fields.is_empty());
+    let keys: Vec<&str> = flow
+        .get_step(&finish.id)
+        .unwrap()
+        .fields
+        .iter()
+        .map(|f| f.key.as_str())
+        .collect();
+    assert_eq!(keys, vec!["surface", "color"]);
+    assert_eq!(flow.get_step(&finish.id).unwrap().fields[0].id, surface.id);
+}
+
+#[tokio::test]
+async fn test_failed_move_leaves_the_field_in_place() {
+    let store = Arc::new(InMemoryStore::new());
+    let service = services_on(store.clone()).flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let size = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let finish = service
+        .add_step(&acme(), &editor(), flow.id, "Finish".to_string())
+        .await
+        .unwrap();
+    let surface = service
+        .add_field(
+            &acme(),
+            &editor(),
+            size.id,
+            "Surface".to_string(),
+            "surface".to_string(),
+            FieldConfig::new_number(None, None),
+        )
+        .await
+        .unwrap();
+    let color = service
+        .add_field(
+            &acme(),
+            &editor(),
+            finish.id,
+            "Color".to_string(),
+            "color".to_string(),
+            FieldConfig::new_text(32),
+        )
+        .await
+        .unwrap();
+
+    // The field is deleted and re-created before the audit entry fails to be written.
+    let failing = flows_on(store, UnavailableAuditLog);
+    let result = failing
+        .move_field(
+            &acme(),
+            &editor(),
+            surface.id,
+            Some(finish.id),
+            None,
+            None,
+            None,
+        )
+        .await;
+    assert!(
+        matches!(result, Err(DomainError::RepositoryError { .. })),
+        "{result:?}"
+    );
+    let result = failing.remove_step(&acme(), &editor(), size.id, None).await;
+    assert!(
+        matches!(result, Err(DomainError::RepositoryError { .. })),
+        "{result:?}"
+    );
+
+    let flow = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert_eq!(flow.steps.len(), 2);
+    assert_eq!(flow.get_step(&size.id).unwrap().fields[0].id, surface.id);
+    let finish = flow.get_step(&finish.id).unwrap();
+    assert_eq!(finish.fields.len(), 1);
+    assert_eq!(finish.fields[0].id, color.id);
+}
+
+#[tokio::test]
+async fn test_search_flow_fields_matches_labels_case_insensitively() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    for (label, key) in [
+        ("Wall surface", "walls"),
+        ("Floor Surface", "floor"),
+        ("Color", "color"),
+    ] {
+        service
+            .add_field(
+                &acme(),
+                &editor(),
+                step.id,
+                label.to_string(),
+                key.to_string(),
+                FieldConfig::new_number(None, None),
+            )
+            .await
+            .unwrap();
+    }
+
+    let found = service
+        .search_flow_fields(&acme(), flow.id, Some("surf".to_string()))
+        .await
+        .unwrap();
+
+    let keys: Vec<&str> = found.iter().map(|f| f.key.as_str()).collect();
+    assert_eq!(keys, vec!["walls", "floor"]);
+    assert_eq!(
+        service
+            .search_flow_fields(&acme(), flow.id, None)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_other_organization_sees_nothing() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let field = service
+        .add_field(
+            &acme(),
+            &editor(),
+            step.id,
+            "Surface".to_string(),
+            "surface".to_string(),
+            FieldConfig::new_number(None, None),
+        )
+        .await
+        .unwrap();
+
+    assert!(
+        service
+            .list_flows(&globex(), ListQuery::default())
+            .await
+            .unwrap()
+            .items
+            .is_empty()
+    );
+    assert_not_found(service.get_flow(&globex(), flow.id).await);
+    assert_not_found(
+        service
+            .delete_flow(&globex(), &editor(), flow.id, None)
+            .await,
+    );
+    assert_not_found(
+        service
+            .remove_step(&globex(), &editor(), step.id, None)
+            .await,
+    );
+    assert_not_found(
+        service
+            .update_field_config(&globex(), &editor(), field.id, None, None, None)
+            .await,
+    );
+    assert_not_found(service.search_flow_fields(&globex(), flow.id, None).await);
+
+    assert_eq!(
+        service.get_flow(&acme(), flow.id).await.unwrap().steps[0]
+            .fields
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_changes_are_listed_in_the_audit_log() {
+    let Services {
+        flows: service,
+        audit,
+        ..
+    } = services();
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            Some("Bathroom".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+
+    let filter = AuditFilter {
+        entity: Some(AuditEntity::Flow),
+        entity_id: Some(flow.id.to_string()),
+    };
+    let page = audit.list_entries(&acme(), filter, 1, 20).await.unwrap();
+
+    assert_eq!(page.total, 2);
+    assert_eq!(page.entries[0].action, AuditAction::Update);
+    assert_eq!(page.entries[1].action, AuditAction::Create);
+    assert_eq!(
+        audit
+            .list_entries(&acme(), AuditFilter::default(), 1, 20)
+            .await
+            .unwrap()
+            .total,
+        3
+    );
+    assert_eq!(
+        audit
+            .list_entries(&globex(), AuditFilter::default(), 1, 20)
+            .await
+            .unwrap()
+            .total,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_steps_are_rebalanced_when_their_ranks_grow_too_long() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let first = service
+        .add_step(&acme(), &editor(), flow.id, "First".to_string())
+        .await
+        .unwrap();
+    let mut moved = service
+        .add_step(&acme(), &editor(), flow.id, "Moved".to_string())
+        .await
+        .unwrap();
+    let mut next = service
+        .add_step(&acme(), &editor(), flow.id, "Next".to_string())
+        .await
+        .unwrap();
+
+    // Always drop a step right behind the first one, halving the same gap.
+    for _ in 0..200 {
+        let flow = service
+            .reorder_step(
+                &acme(),
+                &editor(),
+                moved.id,
+                Some(first.id),
+                Some(next.id),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let order: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+        assert_eq!(order[..2], [first.id, moved.id]);
+        assert!(
+            flow.steps
+                .iter()
+                .all(|s| !LexoRankProvider.needs_rebalance(&Rank::from_string(s.rank.clone())))
+        );
+        std::mem::swap(&mut moved, &mut next);
+    }
+}
+
+#[tokio::test]
+async fn test_rebalance_flow_keeps_the_order_of_steps_and_fields() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let size = service
+        .add_step(&acme(), &editor(), flow.id, "Size".to_string())
+        .await
+        .unwrap();
+    let finish = service
+        .add_step(&acme(), &editor(), flow.id, "Finish".to_string())
+        .await
+        .unwrap();
+    for key in ["width", "depth", "height"] {
+        service
+            .add_field(
+                &acme(),
+                &editor(),
+                size.id,
+                key.to_string(),
+                key.to_string(),
+                FieldConfig::new_number(None, None),
+            )
+            .await
+            .unwrap();
+    }
+    let before = service
+        .reorder_step(&acme(), &editor(), finish.id, None, Some(size.id), None)
+        .await
+        .unwrap();
+
+    let after = service
+        .rebalance_flow(&acme(), &editor(), flow.id, None)
+        .await
+        .unwrap();
+
+    let shape = |flow: &Flow| -> Vec<(StepId, Vec<FieldId>)> {
+        flow.steps
+            .iter()
+            .map(|s| (s.id, s.fields.iter().map(|f| f.id).collect()))
+            .collect()
+    };
+    assert_eq!(shape(&after), shape(&before));
+    let stored = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert_eq!(shape(&stored), shape(&before));
+    assert!(
+        stored
+            .steps
+            .iter()
+            .zip(&after.steps)
+            .all(|(a, b)| a.rank == b.rank)
+    );
+    assert_not_found(
+        service
+            .rebalance_flow(&globex(), &editor(), flow.id, None)
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn test_reorder_flow_applies_the_whole_layout_at_once() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    for title in ["Size", "Finish"] {
+        let step = service
+            .add_step(&acme(), &editor(), flow.id, title.to_string())
+            .await
+            .unwrap();
+        for key in ["width", "depth"] {
+            service
+                .add_field(
+                    &acme(),
+                    &editor(),
+                    step.id,
+                    key.to_string(),
+                    key.to_string(),
+                    FieldConfig::new_number(None, None),
+                )
+                .await
+                .unwrap();
+        }
+    }
+    let before = service.get_flow(&acme(), flow.id).await.unwrap();
+    let [size, finish] = [&before.steps[0], &before.steps[1]];
+
+    // Swap the steps and move the first field of Size to the end of Finish.
+    let layout = FlowLayout {
+        steps: vec![
+            StepLayout {
+                step_id: finish.id,
+                field_ids: vec![finish.fields[1].id, finish.fields[0].id, size.fields[0].id],
+            },
+            StepLayout {
+                step_id: size.id,
+                field_ids: vec![size.fields[1].id],
+            },
+        ],
+    };
+    let reordered = service
+        .reorder_flow(&acme(), &editor(), flow.id, layout.clone(), None)
+        .await
+        .unwrap();
+
+    let shape = |flow: &Flow| -> Vec<(StepId, Vec<FieldId>)> {
+        flow.steps
+            .iter()
+            .map(|s| (s.id, s.fields.iter().map(|f| f.id).collect()))
+            .collect()
+    };
+    let expected: Vec<(StepId, Vec<FieldId>)> = layout
+        .steps
+        .iter()
+        .map(|s| (s.step_id, s.field_ids.clone()))
+        .collect();
+    assert_eq!(shape(&reordered), expected);
+    assert_eq!(
+        shape(&service.get_flow(&acme(), flow.id).await.unwrap()),
+        expected
+    );
+    assert_eq!(reordered.steps[0].fields[2].key, "width");
+}
+
+#[tokio::test]
+async fn test_invalid_layout_changes_nothing() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    for title in ["Size", "Finish"] {
+        service
+            .add_step(&acme(), &editor(), flow.id, title.to_string())
+            .await
+            .unwrap();
+    }
+    let before = service.get_flow(&acme(), flow.id).await.unwrap();
+
+    let layout = FlowLayout {
+        steps: vec![StepLayout {
+            step_id: before.steps[1].id,
+            field_ids: Vec::new(),
+        }],
+    };
+    let result = service
+        .reorder_flow(&acme(), &editor(), flow.id, layout, None)
+        .await;
+
+    assert!(matches!(result, Err(DomainError::ValidationError { .. })));
+    let after = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert!(
+        after
+            .steps
+            .iter()
+            .zip(&before.steps)
+            .all(|(a, b)| a.id == b.id && a.rank == b.rank)
+    );
+    assert_not_found(
+        service
+            .reorder_flow(
+                &globex(),
+                &editor(),
+                flow.id,
+                FlowLayout { steps: Vec::new() },
+                None,
+            )
+            .await,
+    );
+}
+
+#[tokio::test]
+async fn test_flow_pages_follow_each_other() {
+    let service = services().flows;
+    let mut ids = Vec::new();
+    for name in ["Kitchen", "Bathroom", "Attic"] {
+        ids.push(
+            service
+                .create_flow(&acme(), &editor(), name.to_string())
+                .await
+                .unwrap()
+                .id,
+        );
+    }
+    let first_two = ListQuery {
+        limit: 2,
+        ..ListQuery::default()
+    };
+
+    let first = service
+        .list_flows(&acme(), first_two.clone())
+        .await
+        .unwrap();
+    let second = service
+        .list_flows(
+            &acme(),
+            ListQuery {
+                after: first.next.clone(),
+                ..first_two.clone()
+            },
+        )
+        .await
+        .unwrap();
+
+    // Newest first by default.
+    assert_eq!(
+        first.items.iter().map(|f| f.id).collect::<Vec<_>>(),
+        vec![ids[2], ids[1]]
+    );
+    assert_eq!(first.next.as_ref().map(|cursor| cursor.id), Some(ids[1]));
+    assert_eq!(
+        second.items.iter().map(|f| f.id).collect::<Vec<_>>(),
+        vec![ids[0]]
+    );
+    assert_eq!(second.next, None);
+    assert!(matches!(
+        service
+            .list_flows(
+                &acme(),
+                ListQuery {
+                    limit: 0,
+                    ..first_two
+                }
+            )
+            .await,
+        Err(DomainError::ValidationError { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_mutate_a_flow() {
+    let service = services().flows;
+    let flow = service
+        .create_flow(&acme(), &editor(), "Ours".to_string())
+        .await
+        .unwrap();
+
+    assert_not_found(
+        service
+            .update_flow_metadata(
+                &globex(),
+                &editor(),
+                flow.id,
+                Some("Hijacked".to_string()),
+                None,
+                None,
+            )
+            .await,
+    );
+    assert_not_found(
+        service
+            .add_step(&globex(), &editor(), flow.id, "Injected".to_string())
+            .await,
+    );
+    assert_not_found(
+        service
+            .rebalance_flow(&globex(), &editor(), flow.id, None)
+            .await,
+    );
+
+    let stored = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert_eq!(stored.name, "Ours");
+    assert!(stored.steps.is_empty());
+}
+
+#[tokio::test]
+async fn test_other_organization_cannot_touch_steps_or_fields() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Surface"], &["area"]).await;
+    let (step, field) = (flow.steps[0].id, flow.steps[0].fields[0].id);
+
+    assert_not_found(
+        service
+            .update_step_metadata(
+                &globex(),
+                &editor(),
+                step,
+                Some("Hijacked".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await,
+    );
+    assert_not_found(
+        service
+            .reorder_step(&globex(), &editor(), step, None, None, None)
+            .await,
+    );
+    assert_not_found(
+        service
+            .add_field(
+                &globex(),
+                &editor(),
+                step,
+                "Injected".to_string(),
+                "injected".to_string(),
+                FieldConfig::new_boolean(false),
+            )
+            .await,
+    );
+    assert_not_found(
+        service
+            .update_field_config(
+                &globex(),
+                &editor(),
+                field,
+                Some("Hijacked".to_string()),
+                None,
+                None,
+            )
+            .await,
+    );
+    assert_not_found(
+        service
+            .move_field(&globex(), &editor(), field, None, None, None, None)
+            .await,
+    );
+    assert_not_found(
+        service
+            .remove_field(&globex(), &editor(), field, None)
+            .await,
+    );
+    assert_not_found(service.remove_step(&globex(), &editor(), step, None).await);
+
+    let stored = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert_eq!(stored.steps[0].title, "Surface");
+    assert_eq!(stored.steps[0].fields.len(), 1);
+    assert_eq!(stored.steps[0].fields[0].label, "area");
+}
+
+#[tokio::test]
+async fn test_fields_cannot_be_moved_into_another_organization() {
+    let service = services().flows;
+    let ours = flow_with_steps(&service, &["Ours"], &["area"]).await;
+    let theirs = service
+        .create_flow(&globex(), &editor(), "Theirs".to_string())
+        .await
+        .unwrap();
+    let their_step = service
+        .add_step(&globex(), &editor(), theirs.id, "Theirs".to_string())
+        .await
+        .unwrap();
+
+    assert_not_found(
+        service
+            .move_field(
+                &acme(),
+                &editor(),
+                ours.steps[0].fields[0].id,
+                Some(their_step.id),
+                None,
+                None,
+                None,
+            )
+            .await,
+    );
+    let theirs = service.get_flow(&globex(), theirs.id).await.unwrap();
+    assert!(theirs.steps[0].fields.is_empty());
+}
+
+#[tokio::test]
+async fn test_every_change_is_recorded_with_its_actor_and_changes() {
+    let Services {
+        flows: service,
+        audit,
+        ..
+    } = services();
+    let flow = service
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            Some("Bathroom".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let step = service
+        .add_step(&acme(), &editor(), flow.id, "Surface".to_string())
+        .await
+        .unwrap();
+    service
+        .remove_step(&acme(), &editor(), step.id, None)
+        .await
+        .unwrap();
+    service
+        .delete_flow(&acme(), &editor(), flow.id, None)
+        .await
+        .unwrap();
+
+    let page = audit
+        .list_entries(&acme(), AuditFilter::default(), 1, 20)
+        .await
+        .unwrap();
+    let actions: Vec<(AuditEntity, AuditAction)> = page
+        .entries
+        .iter()
+        .rev()
+        .map(|e| (e.entity, e.action))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            (AuditEntity::Flow, AuditAction::Create),
+            (AuditEntity::Flow, AuditAction::Update),
+            (AuditEntity::Step, AuditAction::Create),
+            (AuditEntity::Step, AuditAction::Delete),
+            (AuditEntity::Flow, AuditAction::Delete),
+        ]
+    );
+    assert!(
+        page.entries
+            .iter()
+            .all(|e| e.actor == editor() && e.organization_id == acme())
+    );
+
+    let rename = &page.entries[3];
+    assert_eq!(rename.entity_id, flow.id.to_string());
+    assert_eq!(rename.changes.len(), 1);
+    assert_eq!(rename.changes[0].key, "name");
+    assert_eq!(rename.changes[0].before, Some("Kitchen".into()));
+    assert_eq!(rename.changes[0].after, Some("Bathroom".into()));
+}
+
+#[tokio::test]
+async fn test_rejected_changes_are_not_recorded() {
+    let Services {
+        flows: service,
+        audit,
+        ..
+    } = services();
+    let flow = service
+        .create_flow(&acme(), &editor(), "Ours".to_string())
+        .await
+        .unwrap();
+
+    assert_not_found(
+        service
+            .delete_flow(&globex(), &editor(), flow.id, None)
+            .await,
+    );
+
+    let page = audit
+        .list_entries(&acme(), AuditFilter::default(), 1, 20)
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+}
+
+#[tokio::test]
+async fn test_steps_cannot_be_placed_next_to_steps_of_another_flow() {
+    let service = services().flows;
+    let ours = flow_with_steps(&service, &["Size", "Finish"], &[]).await;
+    let other = flow_with_steps(&service, &["A", "B"], &[]).await;
+    let (size, finish) = (ours.steps[0].id, ours.steps[1].id);
+    let (a, b) = (other.steps[0].id, other.steps[1].id);
+
+    assert_validation(
+        service
+            .reorder_step(&acme(), &editor(), size, Some(a), Some(b), None)
+            .await,
+        &format!("Step {a} does not belong to flow {}", ours.id),
+    );
+    assert_validation(
+        service
+            .reorder_step(&acme(), &editor(), size, Some(finish), Some(b), None)
+            .await,
+        &format!("Step {b} does not belong to flow {}", ours.id),
+    );
+
+    let stored = service.get_flow(&acme(), ours.id).await.unwrap();
+    assert_eq!(stored.steps[0].id, size);
+    assert_eq!(stored.steps[0].rank, ours.steps[0].rank);
+    assert_eq!(
+        service
+            .get_flow(&acme(), other.id)
+            .await
+            .unwrap()
+            .steps
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_step_neighbours_must_be_ordered() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Size", "Finish", "Extras"], &[]).await;
+    let (size, finish, extras) = (flow.steps[0].id, flow.steps[1].id, flow.steps[2].id);
+
+    assert_validation(
+        service
+            .reorder_step(&acme(), &editor(), size, Some(extras), Some(finish), None)
+            .await,
+        &format!("Step {extras} must come before Step {finish}"),
+    );
+    assert_validation(
+        service
+            .reorder_step(&acme(), &editor(), size, Some(finish), Some(finish), None)
+            .await,
+        &format!("Step {finish} must come before Step {finish}"),
+    );
+    assert_validation(
+        service
+            .reorder_step(&acme(), &editor(), size, Some(size), None, None)
+            .await,
+        &format!("Step {size} cannot be placed next to itself"),
+    );
+
+    let flow = service
+        .reorder_step(&acme(), &editor(), size, Some(finish), Some(extras), None)
+        .await
+        .unwrap();
+    let order: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+    assert_eq!(order, vec![finish, size, extras]);
+}
+
+#[tokio::test]
+async fn test_step_reordered_without_neighbours_goes_to_the_end() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Size", "Finish", "Extras"], &[]).await;
+    let (size, finish, extras) = (flow.steps[0].id, flow.steps[1].id, flow.steps[2].id);
+
+    let flow = service
+        .reorder_step(&acme(), &editor(), size, None, None, None)
+        .await
+        .unwrap();
+
+    let order: Vec<StepId> = flow.steps.iter().map(|s| s.id).collect();
+    assert_eq!(order, vec![finish, extras, size]);
+    let mut ranks: Vec<&str> = flow.steps.iter().map(|s| s.rank.as_str()).collect();
+    ranks.dedup();
+    assert_eq!(ranks.len(), 3);
+}
+
+#[tokio::test]
+async fn test_fields_cannot_be_placed_next_to_fields_of_another_step() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Size", "Finish"], &["width", "depth"]).await;
+    let (size, finish) = (&flow.steps[0], &flow.steps[1]);
+    let field = size.fields[0].id;
+    let (wide, deep) = (finish.fields[0].id, finish.fields[1].id);
+
+    assert_validation(
+        service
+            .move_field(
+                &acme(),
+                &editor(),
+                field,
+                None,
+                Some(wide),
+                Some(deep),
+                None,
+            )
+            .await,
+        &format!("Field {wide} does not belong to step {}", size.id),
+    );
+    assert_validation(
+        service
+            .move_field(
+                &acme(),
+                &editor(),
+                field,
+                Some(finish.id),
+                Some(size.fields[1].id),
+                None,
+                None,
+            )
+            .await,
+        &format!(
+            "Field {} does not belong to step {}",
+            size.fields[1].id, finish.id
+        ),
+    );
+
+    let flow = service
+        .move_field(
+            &acme(),
+            &editor(),
+            field,
+            Some(finish.id),
+            Some(wide),
+            Some(deep),
+            None,
+        )
+        .await
+        .unwrap();
+    let order: Vec<FieldId> = flow.steps[1].fields.iter().map(|f| f.id).collect();
+    assert_eq!(order, vec![wide, field, deep]);
+}
+
+#[tokio::test]
+async fn test_field_neighbours_must_be_ordered() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Size"], &["width", "depth", "height"]).await;
+    let [width, depth, height] = [0, 1, 2].map(|i| flow.steps[0].fields[i].id);
+
+    assert_validation(
+        service
+            .move_field(
+                &acme(),
+                &editor(),
+                width,
+                None,
+                Some(height),
+                Some(depth),
+                None,
+            )
+            .await,
+        &format!("Field {height} must come before Field {depth}"),
+    );
+    assert_validation(
+        service
+            .move_field(&acme(), &editor(), width, None, None, Some(width), None)
+            .await,
+        &format!("Field {width} cannot be placed next to itself"),
+    );
+}
+
+#[tokio::test]
+async fn test_fields_cannot_be_moved_into_another_flow() {
+    let service = services().flows;
+    let ours = flow_with_steps(&service, &["Size"], &["width"]).await;
+    let other = flow_with_steps(&service, &["Finish"], &[]).await;
+    let target = other.steps[0].id;
+
+    assert_validation(
+        service
+            .move_field(
+                &acme(),
+                &editor(),
+                ours.steps[0].fields[0].id,
+                Some(target),
+                None,
+                None,
+                None,
+            )
+            .await,
+        &format!("Step {target} does not belong to flow {}", ours.id),
+    );
+    assert!(
+        service.get_flow(&acme(), other.id).await.unwrap().steps[0]
+            .fields
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_changes_against_a_stale_version_are_rejected() {
+    let Services {
+        flows: service,
+        audit,
+        ..
+    } = services();
+    let flow = flow_with_steps(&service, &["Size"], &["width"]).await;
+    let (step, field) = (&flow.steps[0], &flow.steps[0].fields[0]);
+
+    let renamed = service
+        .update_flow_metadata(
+            &acme(),
+            &editor(),
+            flow.id,
+            Some("Bathroom".to_string()),
+            None,
+            Some(flow.version),
+        )
+        .await
+        .unwrap();
+    assert_eq!(renamed.version, flow.version + 1);
+    let recorded = audit
+        .list_entries(&acme(), AuditFilter::default(), 1, 20)
+        .await
+        .unwrap()
+        .total;
+
+    assert_conflict(
+        service
+            .update_flow_metadata(
+                &acme(),
+                &editor(),
+                flow.id,
+                Some("Kitchen".to_string()),
+                None,
+                Some(flow.version),
+            )
+            .await,
+    );
+    assert_conflict(
+        service
+            .delete_flow(&acme(), &editor(), flow.id, Some(flow.version))
+            .await,
+    );
+    assert_conflict(
+        service
+            .remove_step(&acme(), &editor(), step.id, Some(step.version + 1))
+            .await,
+    );
+    assert_conflict(
+        service
+            .remove_field(&acme(), &editor(), field.id, Some(field.version + 1))
+            .await,
+    );
+
+    let current = service.get_flow(&acme(), flow.id).await.unwrap();
+    assert_eq!(current.name, "Bathroom");
+    assert_eq!(current.steps[0].fields.len(), 1);
+    let page = audit
+        .list_entries(&acme(), AuditFilter::default(), 1, 20)
+        .await
+        .unwrap();
+    assert_eq!(page.total, recorded);
+}
+
+#[tokio::test]
+async fn test_every_change_bumps_the_version_of_its_target() {
+    let service = services().flows;
+    let flow = flow_with_steps(&service, &["Size", "Finish"], &["width"]).await;
+    let (size, finish) = (&flow.steps[0], &flow.steps[1]);
+    let field = &size.fields[0];
+
+    let reordered = service
+        .reorder_step(
+            &acme(),
+            &editor(),
+            finish.id,
+            None,
+            Some(size.id),
+            Some(finish.version),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        reordered.get_step(&finish.id).unwrap().version,
+        finish.version + 1
+    );
+    assert_eq!(reordered.get_step(&size.id).unwrap().version, size.version);
+
+    let moved = service
+        .move_field(
+            &acme(),
+            &editor(),
+            field.id,
+            Some(finish.id),
+            None,
+            None,
+            Some(field.version),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        moved
+            .get_step(&finish.id)
+            .unwrap()
+            .get_field(&field.id)
+            .unwrap()
+            .version,
+        field.version + 1
+    );
+
+    let rebalanced = service
+        .rebalance_flow(&acme(), &editor(), flow.id, Some(flow.version))
+        .await
+        .unwrap();
+    assert_eq!(rebalanced.version, flow.version + 1);
+}
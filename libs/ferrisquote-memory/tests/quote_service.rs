@@ -0,0 +1,423 @@
+//! Quote service running against the in-memory adapters.
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use common::{Services, acme, assert_conflict, editor, quotes_on, services, services_on, surface};
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{
+            actor::Actor,
+            entry::{AuditEntity, AuditEntry},
+            query::AuditFilter,
+        },
+        ports::{AuditRepository, AuditService},
+    },
+    error::DomainError,
+    estimator::{entities::estimator::Estimator, ports::EstimatorService},
+    flows::ports::FlowService,
+    organization::entities::ids::OrganizationId,
+    quote::{
+        entities::{quote::Quote, status::QuoteStatus},
+        ports::QuoteService,
+        services::EXPIRY_ACTOR,
+    },
+    transaction::ports::Transactional,
+};
+use ferrisquote_memory::{
+    InMemoryAuditRepository, InMemoryStore, unit_of_work::InMemoryTransaction,
+};
+
+/// An audit log that rejects the entries of one entity and records the others.
+#[derive(Clone)]
+struct RejectingAuditLog {
+    inner: InMemoryAuditRepository,
+    entity_id: String,
+}
+
+impl Transactional<InMemoryTransaction> for RejectingAuditLog {
+    fn in_transaction(&self, transaction: &InMemoryTransaction) -> Self {
+        Self {
+            inner: self.inner.in_transaction(transaction),
+            entity_id: self.entity_id.clone(),
+        }
+    }
+}
+
+impl AuditRepository for RejectingAuditLog {
+    async fn append_entry(&self, entry: AuditEntry) -> Result<AuditEntry, DomainError> {
+        if entry.entity_id == self.entity_id {
+            return Err(DomainError::repository("audit log unavailable"));
+        }
+        self.inner.append_entry(entry).await
+    }
+
+    async fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: &AuditFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<AuditEntry>, u64), DomainError> {
+        self.inner
+            .list_entries(organization, filter, offset, limit)
+            .await
+    }
+}
+
+/// An estimator of `acme` pricing 100 per square metre.
+async fn pricing(services: &Services) -> Estimator {
+    let flow = services
+        .flows
+        .create_flow(&acme(), &editor(), "Kitchen".to_string())
+        .await
+        .unwrap();
+    let estimator = services
+        .estimators
+        .create_estimator(&acme(), &editor(), flow.id, "Pricing".to_string())
+        .await
+        .unwrap();
+    services
+        .estimators
+        .add_variable(
+            &acme(),
+            &editor(),
+            estimator.id,
+            "total".to_string(),
+            "@surface * 100.0".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+    estimator
+}
+
+/// A quote of `acme` for 10 square metres, sent with `validity_days` to run.
+async fn sent_quote(services: &Services, validity_days: u32) -> Quote {
+    let estimator = pricing(services).await;
+    let quote = services
+        .quotes
+        .create_quote(
+            &acme(),
+            &editor(),
+            estimator.id,
+            surface(10.0),
+            None,
+            Some(validity_days),
+        )
+        .await
+        .unwrap();
+    services
+        .quotes
+        .transition_quote(&acme(), &editor(), quote.id, QuoteStatus::Sent)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_transitions_are_stored_with_their_history() {
+    let services = services();
+    let quote = sent_quote(&services, 30).await;
+    assert!(quote.valid_until.is_some());
+
+    let accepted = services
+        .quotes
+        .transition_quote(&acme(), &editor(), quote.id, QuoteStatus::Accepted)
+        .await
+        .unwrap();
+    assert_eq!(accepted.status, QuoteStatus::Accepted);
+
+    let stored = services.quotes.get_quote(&acme(), quote.id).await.unwrap();
+    assert_eq!(stored.status, QuoteStatus::Accepted);
+
+    let history = services
+        .quotes
+        .get_quote_history(&acme(), quote.id)
+        .await
+        .unwrap();
+    let steps: Vec<_> = history.iter().map(|c| (c.from, c.to)).collect();
+    assert_eq!(
+        steps,
+        vec![
+            (QuoteStatus::Draft, QuoteStatus::Sent),
+            (QuoteStatus::Sent, QuoteStatus::Accepted),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_transition_leaves_quote_untouched() {
+    let services = services();
+    let estimator = pricing(&services).await;
+    let quote = services
+        .quotes
+        .create_quote(&acme(), &editor(), estimator.id, surface(10.0), None, None)
+        .await
+        .unwrap();
+
+    let result = services
+        .quotes
+        .transition_quote(&acme(), &editor(), quote.id, QuoteStatus::Accepted)
+        .await;
+
+    assert!(matches!(result, Err(DomainError::InvalidTransition { .. })));
+    let stored = services.quotes.get_quote(&acme(), quote.id).await.unwrap();
+    assert_eq!(stored.status, QuoteStatus::Draft);
+    assert!(
+        services
+            .quotes
+            .get_quote_history(&acme(), quote.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_overdue_quote_cannot_be_accepted_or_rejected() {
+    let services = services();
+    let quote = sent_quote(&services, 0).await;
+
+    for status in [QuoteStatus::Accepted, QuoteStatus::Rejected] {
+        assert_conflict(
+            services
+                .quotes
+                .transition_quote(&acme(), &editor(), quote.id, status)
+                .await,
+        );
+    }
+
+    let stored = services.quotes.get_quote(&acme(), quote.id).await.unwrap();
+    assert_eq!(stored.status, QuoteStatus::Sent);
+}
+
+#[tokio::test]
+async fn test_expiry_sweep_expires_only_overdue_quotes() {
+    let services = services();
+    let overdue = sent_quote(&services, 0).await;
+    let running = sent_quote(&services, 30).await;
+
+    let expired = services
+        .quotes
+        .expire_overdue_quotes(Utc::now())
+        .await
+        .unwrap();
+
+    let ids: Vec<_> = expired.iter().map(|q| q.id).collect();
+    assert_eq!(ids, vec![overdue.id]);
+    let stored = services
+        .quotes
+        .get_quote(&acme(), overdue.id)
+        .await
+        .unwrap();
+    assert_eq!(stored.status, QuoteStatus::Expired);
+    let stored = services
+        .quotes
+        .get_quote(&acme(), running.id)
+        .await
+        .unwrap();
+    assert_eq!(stored.status, QuoteStatus::Sent);
+
+    let history = services
+        .quotes
+        .get_quote_history(&acme(), overdue.id)
+        .await
+        .unwrap();
+    let last = history.last().unwrap();
+    assert_eq!(
+        (last.from, last.to),
+        (QuoteStatus::Sent, QuoteStatus::Expired)
+    );
+    assert_eq!(last.reason.as_deref(), Some("validity period elapsed"));
+
+    let filter = AuditFilter {
+        entity: Some(AuditEntity::Quote),
+        entity_id: Some(overdue.id.to_string()),
+    };
+    let page = services
+        .audit
+        .list_entries(&acme(), filter, 1, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.entries[0].actor, Actor::system(EXPIRY_ACTOR));
+
+    assert!(
+        services
+            .quotes
+            .expire_overdue_quotes(Utc::now())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_revision_is_a_draft_copy_numbered_after_the_last() {
+    let services = services();
+    let quote = sent_quote(&services, 30).await;
+
+    let revision = services
+        .quotes
+        .create_revision(&acme(), &editor(), quote.id)
+        .await
+        .unwrap();
+
+    assert_ne!(revision.id, quote.id);
+    assert_eq!(revision.root_id, quote.id);
+    assert_eq!(revision.revision, 2);
+    assert_eq!(revision.status, QuoteStatus::Draft);
+    assert_eq!(revision.total, quote.total);
+    assert!(revision.valid_until.is_none());
+
+    assert_conflict(
+        services
+            .quotes
+            .create_revision(&acme(), &editor(), quote.id)
+            .await,
+    );
+
+    let revisions = services
+        .quotes
+        .list_revisions(&acme(), revision.id)
+        .await
+        .unwrap();
+    let numbers: Vec<_> = revisions.iter().map(|q| q.revision).collect();
+    assert_eq!(numbers, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_sending_a_revision_supersedes_the_sent_one() {
+    let services = services();
+    let quote = sent_quote(&services, 30).await;
+    let revision = services
+        .quotes
+        .create_revision(&acme(), &editor(), quote.id)
+        .await
+        .unwrap();
+
+    services
+        .quotes
+        .transition_quote(&acme(), &editor(), revision.id, QuoteStatus::Sent)
+        .await
+        .unwrap();
+
+    let previous = services.quotes.get_quote(&acme(), quote.id).await.unwrap();
+    assert_eq!(previous.status, QuoteStatus::Superseded);
+    let history = services
+        .quotes
+        .get_quote_history(&acme(), quote.id)
+        .await
+        .unwrap();
+    let last = history.last().unwrap();
+    assert_eq!(
+        (last.from, last.to),
+        (QuoteStatus::Sent, QuoteStatus::Superseded)
+    );
+    assert_eq!(last.reason.as_deref(), Some("superseded by revision 2"));
+
+    let filter = AuditFilter {
+        entity: Some(AuditEntity::Quote),
+        entity_id: Some(quote.id.to_string()),
+    };
+    let page = services
+        .audit
+        .list_entries(&acme(), filter, 1, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.entries[0].actor, editor());
+    assert_eq!(
+        page.entries[0].after.as_ref().unwrap()["status"],
+        "superseded"
+    );
+}
+
+#[tokio::test]
+async fn test_diff_reports_changed_answers_line_items_and_total() {
+    let services = services();
+    let quote = sent_quote(&services, 30).await;
+    let revision = services
+        .quotes
+        .create_revision(&acme(), &editor(), quote.id)
+        .await
+        .unwrap();
+    services
+        .quotes
+        .update_quote_submission(&acme(), &editor(), revision.id, surface(12.0), None)
+        .await
+        .unwrap();
+
+    let diff = services
+        .quotes
+        .diff_revisions(&acme(), quote.id, revision.id)
+        .await
+        .unwrap();
+
+    assert_eq!((diff.from_revision, diff.to_revision), (1, 2));
+    assert_eq!(diff.changed_answers.len(), 1);
+    assert_eq!(diff.changed_answers[0].key, "surface");
+    assert_eq!(diff.changed_answers[0].before, Some(10.0));
+    assert_eq!(diff.changed_answers[0].after, Some(12.0));
+    assert_eq!(diff.changed_line_items.len(), 1);
+    assert_eq!(diff.changed_line_items[0].key, "total");
+    assert_eq!(diff.total_before, 1000.0);
+    assert_eq!(diff.total_after, 1200.0);
+    assert_eq!(diff.total_delta, 200.0);
+}
+
+#[tokio::test]
+async fn test_diff_requires_revisions_of_the_same_quote() {
+    let services = services();
+    let first = sent_quote(&services, 30).await;
+    let second = sent_quote(&services, 30).await;
+
+    let result = services
+        .quotes
+        .diff_revisions(&acme(), first.id, second.id)
+        .await;
+
+    assert!(matches!(result, Err(DomainError::ValidationError { .. })));
+}
+
+#[tokio::test]
+async fn test_send_is_undone_when_the_revision_it_supersedes_cannot_be() {
+    let store = Arc::new(InMemoryStore::new());
+    let services = services_on(store.clone());
+    let quote = sent_quote(&services, 30).await;
+    let revision = services
+        .quotes
+        .create_revision(&acme(), &editor(), quote.id)
+        .await
+        .unwrap();
+    let quotes = quotes_on(
+        store.clone(),
+        RejectingAuditLog {
+            inner: InMemoryAuditRepository::with_store(store),
+            entity_id: quote.id.to_string(),
+        },
+    );
+
+    let result = quotes
+        .transition_quote(&acme(), &editor(), revision.id, QuoteStatus::Sent)
+        .await;
+
+    assert!(
+        matches!(result, Err(DomainError::RepositoryError { .. })),
+        "expected RepositoryError, got {result:?}"
+    );
+    let previous = services.quotes.get_quote(&acme(), quote.id).await.unwrap();
+    let revision = services
+        .quotes
+        .get_quote(&acme(), revision.id)
+        .await
+        .unwrap();
+    assert_eq!(previous.status, QuoteStatus::Sent);
+    assert_eq!(revision.status, QuoteStatus::Draft);
+    let history = services
+        .quotes
+        .get_quote_history(&acme(), revision.id)
+        .await
+        .unwrap();
+    assert!(history.is_empty());
+}
@@ -0,0 +1,86 @@
+//! In-memory transactions against concurrent writers.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::acme;
+use ferrisquote_domain::domain::{
+    flows::{entities::flow::Flow, ports::FlowRepository},
+    transaction::ports::{Transaction, Transactional, UnitOfWork},
+};
+use ferrisquote_memory::{InMemoryFlowRepository, InMemoryStore, InMemoryUnitOfWork};
+
+fn flow(name: &str) -> Flow {
+    Flow::new(acme(), name.to_string(), String::new())
+}
+
+#[tokio::test]
+async fn test_interleaved_transactions_lose_no_write() {
+    let store = Arc::new(InMemoryStore::new());
+    let unit_of_work = InMemoryUnitOfWork::with_store(store.clone());
+    let flows = InMemoryFlowRepository::with_store(store);
+    let (kitchen, bathroom, garage) = (flow("Kitchen"), flow("Bathroom"), flow("Garage"));
+
+    let first = unit_of_work.begin().await.unwrap();
+    let second = tokio::spawn({
+        let (unit_of_work, flows, bathroom) =
+            (unit_of_work.clone(), flows.clone(), bathroom.clone());
+        async move {
+            let transaction = unit_of_work.begin().await.unwrap();
+            flows
+                .in_transaction(&transaction)
+                .create_flow(bathroom)
+                .await
+                .unwrap();
+            transaction.commit().await.unwrap();
+        }
+    });
+    let outside = tokio::spawn({
+        let (flows, garage) = (flows.clone(), garage.clone());
+        async move { flows.create_flow(garage).await.unwrap() }
+    });
+    // Both writers start, then wait for the first transaction to end.
+    tokio::task::yield_now().await;
+    flows
+        .in_transaction(&first)
+        .create_flow(kitchen.clone())
+        .await
+        .unwrap();
+    first.commit().await.unwrap();
+    second.await.unwrap();
+    outside.await.unwrap();
+
+    let organization = acme();
+    for expected in [kitchen, bathroom, garage] {
+        let stored = flows.get_flow(&organization, expected.id).await.unwrap();
+        assert_eq!(stored.name, expected.name);
+    }
+}
+
+#[tokio::test]
+async fn test_rolled_back_transaction_lets_the_next_one_in() {
+    let store = Arc::new(InMemoryStore::new());
+    let unit_of_work = InMemoryUnitOfWork::with_store(store.clone());
+    let flows = InMemoryFlowRepository::with_store(store);
+    let (kitchen, bathroom) = (flow("Kitchen"), flow("Bathroom"));
+
+    let first = unit_of_work.begin().await.unwrap();
+    flows
+        .in_transaction(&first)
+        .create_flow(kitchen.clone())
+        .await
+        .unwrap();
+    drop(first);
+    let second = unit_of_work.begin().await.unwrap();
+    flows
+        .in_transaction(&second)
+        .create_flow(bathroom.clone())
+        .await
+        .unwrap();
+    second.commit().await.unwrap();
+
+    let organization = acme();
+    assert!(flows.get_flow(&organization, kitchen.id).await.is_err());
+    assert!(flows.get_flow(&organization, bathroom.id).await.is_ok());
+}
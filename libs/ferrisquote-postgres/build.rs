@@ -0,0 +1,4 @@
+fn main() {
+    // Rebuild when a migration is added or edited, since they are embedded by `sqlx::migrate!`.
+    println!("cargo:rerun-if-changed=migrations");
+}
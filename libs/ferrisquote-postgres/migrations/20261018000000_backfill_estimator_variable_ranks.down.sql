@@ -0,0 +1 @@
+DROP INDEX IF EXISTS idx_estimator_variables_estimator_rank;
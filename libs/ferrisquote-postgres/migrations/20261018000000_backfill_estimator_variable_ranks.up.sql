@@ -0,0 +1,12 @@
+-- Variables were created without a rank and all share the column default 'n',
+-- which is not a valid LexoRank. Assign ranks following creation order.
+UPDATE estimator_variables v
+SET rank = '0|n' || LPAD(ordered.position::TEXT, 6, '0') || '1'
+FROM (
+  SELECT id, ROW_NUMBER() OVER (PARTITION BY estimator_id ORDER BY created_at, id) AS position
+  FROM estimator_variables
+  WHERE rank = 'n'
+) AS ordered
+WHERE v.id = ordered.id;
+
+CREATE INDEX idx_estimator_variables_estimator_rank ON estimator_variables (estimator_id, rank);
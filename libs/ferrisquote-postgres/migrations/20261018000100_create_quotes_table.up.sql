@@ -0,0 +1,18 @@
+CREATE TABLE quotes (
+  id UUID PRIMARY KEY,
+  flow_id UUID NOT NULL,
+  estimator_id UUID NOT NULL,
+  status VARCHAR(32) NOT NULL DEFAULT 'draft',
+  submission JSONB NOT NULL,
+  results JSONB NOT NULL,
+  validity_days INTEGER NOT NULL DEFAULT 30,
+  valid_until TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+  CONSTRAINT fk_quotes_flow_id FOREIGN KEY (flow_id) REFERENCES flows(id) ON DELETE CASCADE,
+  CONSTRAINT fk_quotes_estimator_id FOREIGN KEY (estimator_id) REFERENCES estimators(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_quotes_flow_id ON quotes (flow_id);
+CREATE INDEX idx_quotes_status_valid_until ON quotes (status, valid_until);
@@ -0,0 +1,12 @@
+CREATE TABLE quote_status_history (
+  id UUID PRIMARY KEY,
+  quote_id UUID NOT NULL,
+  from_status VARCHAR(32) NOT NULL,
+  to_status VARCHAR(32) NOT NULL,
+  reason TEXT,
+  changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+  CONSTRAINT fk_quote_status_history_quote_id FOREIGN KEY (quote_id) REFERENCES quotes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_quote_status_history_quote_id ON quote_status_history (quote_id, changed_at);
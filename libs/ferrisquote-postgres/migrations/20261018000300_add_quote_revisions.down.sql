@@ -0,0 +1,6 @@
+ALTER TABLE quotes
+  DROP CONSTRAINT IF EXISTS uq_quotes_root_id_revision,
+  DROP CONSTRAINT IF EXISTS fk_quotes_root_id,
+  DROP COLUMN IF EXISTS total,
+  DROP COLUMN IF EXISTS revision,
+  DROP COLUMN IF EXISTS root_id;
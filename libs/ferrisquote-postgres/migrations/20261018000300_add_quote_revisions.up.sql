@@ -0,0 +1,12 @@
+ALTER TABLE quotes
+  ADD COLUMN root_id UUID,
+  ADD COLUMN revision INTEGER NOT NULL DEFAULT 1,
+  ADD COLUMN total DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+-- Existing quotes are the first revision of themselves.
+UPDATE quotes SET root_id = id;
+
+ALTER TABLE quotes
+  ALTER COLUMN root_id SET NOT NULL,
+  ADD CONSTRAINT fk_quotes_root_id FOREIGN KEY (root_id) REFERENCES quotes(id) ON DELETE CASCADE,
+  ADD CONSTRAINT uq_quotes_root_id_revision UNIQUE (root_id, revision);
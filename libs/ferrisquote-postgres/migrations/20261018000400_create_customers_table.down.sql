@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_quotes_customer_id;
+
+ALTER TABLE quotes
+  DROP CONSTRAINT IF EXISTS fk_quotes_customer_id,
+  DROP COLUMN IF EXISTS customer_id;
+
+DROP TABLE IF EXISTS customers;
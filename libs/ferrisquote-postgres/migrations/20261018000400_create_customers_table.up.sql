@@ -0,0 +1,23 @@
+CREATE TABLE customers (
+  id UUID PRIMARY KEY,
+  company_name VARCHAR(255),
+  contact_name VARCHAR(255) NOT NULL,
+  email VARCHAR(255) NOT NULL,
+  phone VARCHAR(64),
+  billing_address JSONB,
+  vat_number VARCHAR(64),
+  discount_rate DOUBLE PRECISION NOT NULL DEFAULT 0,
+  attributes JSONB NOT NULL DEFAULT '{}'::jsonb,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+  CONSTRAINT chk_customers_discount_rate CHECK (discount_rate >= 0 AND discount_rate <= 1)
+);
+
+CREATE INDEX idx_customers_email ON customers (email);
+
+ALTER TABLE quotes
+  ADD COLUMN customer_id UUID,
+  ADD CONSTRAINT fk_quotes_customer_id FOREIGN KEY (customer_id) REFERENCES customers(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_quotes_customer_id ON quotes (customer_id);
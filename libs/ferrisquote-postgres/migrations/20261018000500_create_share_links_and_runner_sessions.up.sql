@@ -0,0 +1,26 @@
+CREATE TABLE share_links (
+  id UUID PRIMARY KEY,
+  flow_id UUID NOT NULL,
+  estimator_id UUID NOT NULL,
+  token VARCHAR(64) NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMPTZ,
+  revoked_at TIMESTAMPTZ,
+
+  CONSTRAINT uq_share_links_token UNIQUE (token),
+  CONSTRAINT fk_share_links_flow_id FOREIGN KEY (flow_id) REFERENCES flows(id) ON DELETE CASCADE,
+  CONSTRAINT fk_share_links_estimator_id FOREIGN KEY (estimator_id) REFERENCES estimators(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_share_links_flow_id ON share_links (flow_id);
+
+CREATE TABLE runner_sessions (
+  id UUID PRIMARY KEY,
+  share_link_id UUID NOT NULL,
+  submission JSONB NOT NULL,
+  completed_steps UUID[] NOT NULL DEFAULT '{}',
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+  CONSTRAINT fk_runner_sessions_share_link_id FOREIGN KEY (share_link_id) REFERENCES share_links(id) ON DELETE CASCADE
+);
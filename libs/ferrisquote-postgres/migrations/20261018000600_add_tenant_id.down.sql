@@ -0,0 +1,8 @@
+ALTER TABLE runner_sessions DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE share_links DROP COLUMN IF EXISTS tenant_id;
+DROP INDEX IF EXISTS idx_quotes_tenant_id;
+ALTER TABLE quotes DROP COLUMN IF EXISTS tenant_id;
+DROP INDEX IF EXISTS idx_estimators_tenant_id;
+ALTER TABLE estimators DROP COLUMN IF EXISTS tenant_id;
+DROP INDEX IF EXISTS idx_flows_tenant_id;
+ALTER TABLE flows DROP COLUMN IF EXISTS tenant_id;
@@ -0,0 +1,23 @@
+-- Scope flows and everything built on them to an organization (tenant).
+-- Rows created before multi-tenancy are assigned to the 'default' organization.
+ALTER TABLE flows ADD COLUMN tenant_id VARCHAR(255) NOT NULL DEFAULT 'default';
+ALTER TABLE flows ALTER COLUMN tenant_id DROP DEFAULT;
+CREATE INDEX idx_flows_tenant_id ON flows (tenant_id);
+
+ALTER TABLE estimators ADD COLUMN tenant_id VARCHAR(255);
+UPDATE estimators e SET tenant_id = f.tenant_id FROM flows f WHERE f.id = e.flow_id;
+ALTER TABLE estimators ALTER COLUMN tenant_id SET NOT NULL;
+CREATE INDEX idx_estimators_tenant_id ON estimators (tenant_id);
+
+ALTER TABLE quotes ADD COLUMN tenant_id VARCHAR(255);
+UPDATE quotes q SET tenant_id = f.tenant_id FROM flows f WHERE f.id = q.flow_id;
+ALTER TABLE quotes ALTER COLUMN tenant_id SET NOT NULL;
+CREATE INDEX idx_quotes_tenant_id ON quotes (tenant_id);
+
+ALTER TABLE share_links ADD COLUMN tenant_id VARCHAR(255);
+UPDATE share_links l SET tenant_id = f.tenant_id FROM flows f WHERE f.id = l.flow_id;
+ALTER TABLE share_links ALTER COLUMN tenant_id SET NOT NULL;
+
+ALTER TABLE runner_sessions ADD COLUMN tenant_id VARCHAR(255);
+UPDATE runner_sessions s SET tenant_id = l.tenant_id FROM share_links l WHERE l.id = s.share_link_id;
+ALTER TABLE runner_sessions ALTER COLUMN tenant_id SET NOT NULL;
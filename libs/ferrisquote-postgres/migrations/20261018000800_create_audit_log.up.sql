@@ -0,0 +1,28 @@
+CREATE TABLE audit_log (
+  id UUID PRIMARY KEY,
+  tenant_id VARCHAR(255) NOT NULL,
+  actor_kind VARCHAR(16) NOT NULL,
+  actor_id VARCHAR(255),
+  actor_name VARCHAR(255),
+  entity_type VARCHAR(32) NOT NULL,
+  entity_id VARCHAR(255) NOT NULL,
+  action VARCHAR(16) NOT NULL,
+  before JSONB,
+  after JSONB,
+  changes JSONB NOT NULL DEFAULT '[]',
+  at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_tenant_at ON audit_log (tenant_id, at DESC);
+CREATE INDEX idx_audit_log_entity ON audit_log (tenant_id, entity_type, entity_id, at DESC);
+
+-- The audit log is append-only: entries can never be changed nor removed.
+CREATE FUNCTION audit_log_reject_change() RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_audit_log_append_only
+  BEFORE UPDATE OR DELETE ON audit_log
+  FOR EACH ROW EXECUTE FUNCTION audit_log_reject_change();
@@ -0,0 +1,5 @@
+ALTER TABLE estimator_variables DROP COLUMN IF EXISTS version;
+ALTER TABLE estimators DROP COLUMN IF EXISTS version;
+ALTER TABLE fields DROP COLUMN IF EXISTS version;
+ALTER TABLE steps DROP COLUMN IF EXISTS version;
+ALTER TABLE flows DROP COLUMN IF EXISTS version;
@@ -0,0 +1,7 @@
+-- Optimistic concurrency: every write increments the version of the row it
+-- changes, and writes made against an older version are rejected.
+ALTER TABLE flows ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE steps ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE fields ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE estimators ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE estimator_variables ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_estimators_flow_id_name;
+DROP INDEX IF EXISTS idx_estimators_flow_id_id;
+DROP INDEX IF EXISTS idx_flows_tenant_id_name;
+DROP INDEX IF EXISTS idx_flows_tenant_id_id;
@@ -0,0 +1,6 @@
+-- Keyset pagination of flows and estimators: by id (creation order, ids are
+-- UUIDv7) and by case-insensitive name, ties broken by id.
+CREATE INDEX idx_flows_tenant_id_id ON flows (tenant_id, id);
+CREATE INDEX idx_flows_tenant_id_name ON flows (tenant_id, (LOWER(name) COLLATE "C"), id);
+CREATE INDEX idx_estimators_flow_id_id ON estimators (flow_id, id);
+CREATE INDEX idx_estimators_flow_id_name ON estimators (flow_id, (LOWER(name) COLLATE "C"), id);
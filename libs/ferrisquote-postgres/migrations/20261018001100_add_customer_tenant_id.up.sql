@@ -0,0 +1,12 @@
+-- Scope customers to an organization. Existing customers take the organization
+-- of a quote made for them, or the 'default' one; quotes of another
+-- organization lose the link.
+ALTER TABLE customers ADD COLUMN tenant_id VARCHAR(255);
+UPDATE customers c SET tenant_id = q.tenant_id FROM quotes q WHERE q.customer_id = c.id;
+UPDATE customers SET tenant_id = 'default' WHERE tenant_id IS NULL;
+ALTER TABLE customers ALTER COLUMN tenant_id SET NOT NULL;
+CREATE INDEX idx_customers_tenant_id ON customers (tenant_id);
+
+UPDATE quotes q SET customer_id = NULL
+FROM customers c
+WHERE c.id = q.customer_id AND c.tenant_id <> q.tenant_id;
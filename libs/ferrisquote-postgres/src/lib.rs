@@ -1,4 +1,13 @@
+pub mod migrations;
 pub mod repositories;
+pub mod unit_of_work;
 
+pub use migrations::migrate;
+pub use repositories::PostgresApiKeyRepository;
+pub use repositories::PostgresAuditRepository;
+pub use repositories::PostgresCustomerRepository;
 pub use repositories::PostgresEstimatorRepository;
 pub use repositories::PostgresFlowRepository;
+pub use repositories::PostgresQuoteRepository;
+pub use repositories::PostgresRunnerRepository;
+pub use unit_of_work::PostgresUnitOfWork;
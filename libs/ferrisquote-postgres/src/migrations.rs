@@ -0,0 +1,26 @@
+use sqlx::{
+    PgPool,
+    migrate::{MigrateError, Migrator},
+};
+
+/// The SQL files of `migrations/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Apply every pending migration.
+///
+/// The migrator holds a Postgres advisory lock (keyed on the database name) while it runs,
+/// so replicas starting together apply each migration once and the others wait for it.
+/// Fails if an applied migration no longer matches its embedded file.
+pub async fn migrate(pool: &PgPool) -> Result<(), MigrateError> {
+    tracing::info!(
+        "Applying database migrations ({} embedded)",
+        MIGRATOR
+            .iter()
+            .filter(|m| m.migration_type.is_up_migration())
+            .count()
+    );
+    MIGRATOR.run(pool).await?;
+    tracing::info!("Database schema is up to date");
+
+    Ok(())
+}
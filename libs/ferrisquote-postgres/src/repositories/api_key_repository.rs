@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    api_key::{
+        entities::{api_key::ApiKey, ids::ApiKeyId},
+        ports::ApiKeyRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+use sqlx::{PgPool, Row};
+
+const API_KEY_COLUMNS: &str = "id, tenant_id, name, prefix, key_hash, scopes, created_at, expires_at, last_used_at, revoked_at";
+
+/// Stores API keys by the hash of their secret.
+#[derive(Clone)]
+pub struct PostgresApiKeyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool: Arc::new(pool),
+        }
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+fn build_api_key(row: &sqlx::postgres::PgRow) -> ApiKey {
+    ApiKey {
+        id: ApiKeyId::from_uuid(row.get("id")),
+        organization_id: OrganizationId::new(row.get::<String, _>("tenant_id")),
+        name: row.get("name"),
+        prefix: row.get("prefix"),
+        key_hash: row.get("key_hash"),
+        scopes: row.get("scopes"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        last_used_at: row.get("last_used_at"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    async fn create_api_key(&self, api_key: ApiKey) -> Result<ApiKey, DomainError> {
+        sqlx::query(
+            "INSERT INTO api_keys (id, tenant_id, name, prefix, key_hash, scopes, created_at, expires_at, last_used_at, revoked_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(api_key.id.into_uuid())
+        .bind(api_key.organization_id.as_str())
+        .bind(&api_key.name)
+        .bind(&api_key.prefix)
+        .bind(&api_key.key_hash)
+        .bind(&api_key.scopes)
+        .bind(api_key.created_at)
+        .bind(api_key.expires_at)
+        .bind(api_key.last_used_at)
+        .bind(api_key.revoked_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(api_key)
+    }
+
+    async fn get_api_key_by_hash(&self, key_hash: &str) -> Result<ApiKey, DomainError> {
+        let row = sqlx::query(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM api_keys WHERE key_hash = $1"
+        ))
+        .bind(key_hash)
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("ApiKey", "<hash>"))?;
+
+        Ok(build_api_key(&row))
+    }
+
+    async fn list_api_keys(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<ApiKey>, DomainError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM api_keys \
+             WHERE tenant_id = $1 \
+             ORDER BY created_at DESC"
+        ))
+        .bind(organization.as_str())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(rows.iter().map(build_api_key).collect())
+    }
+
+    async fn revoke_api_key(
+        &self,
+        organization: &OrganizationId,
+        id: ApiKeyId,
+        at: DateTime<Utc>,
+    ) -> Result<ApiKey, DomainError> {
+        // Revoking twice keeps the original revocation time.
+        let row = sqlx::query(&format!(
+            "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, $2) \
+             WHERE id = $1 AND tenant_id = $3 \
+             RETURNING {API_KEY_COLUMNS}"
+        ))
+        .bind(id.into_uuid())
+        .bind(at)
+        .bind(organization.as_str())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("ApiKey", id.to_string()))?;
+
+        Ok(build_api_key(&row))
+    }
+
+    async fn touch_api_key(&self, id: ApiKeyId, at: DateTime<Utc>) -> Result<(), DomainError> {
+        sqlx::query("UPDATE api_keys SET last_used_at = $2 WHERE id = $1")
+            .bind(id.into_uuid())
+            .bind(at)
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    audit::{
+        entities::{actor::Actor, entry::AuditEntry, ids::AuditEntryId, query::AuditFilter},
+        ports::AuditRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+    quote::entities::diff::ValueChange,
+    transaction::ports::Transactional,
+};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+
+use crate::unit_of_work::{ConnectionSource, PostgresTransaction};
+
+const AUDIT_COLUMNS: &str = "id, tenant_id, actor_kind, actor_id, actor_name, entity_type, entity_id, action, before, after, changes, at";
+
+/// Append-only audit log. The table itself rejects updates and deletes.
+#[derive(Clone)]
+pub struct PostgresAuditRepository {
+    source: ConnectionSource,
+}
+
+impl PostgresAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self {
+            source: ConnectionSource::Pool(pool),
+        }
+    }
+}
+
+impl Transactional<PostgresTransaction> for PostgresAuditRepository {
+    fn in_transaction(&self, transaction: &PostgresTransaction) -> Self {
+        Self {
+            source: transaction.source(),
+        }
+    }
+}
+
+/// Build an `AuditEntry` from a row selected with `AUDIT_COLUMNS`.
+fn build_entry(row: &sqlx::postgres::PgRow) -> Result<AuditEntry, DomainError> {
+    let changes: sqlx::types::Json<Vec<ValueChange<Value>>> = row
+        .try_get("changes")
+        .map_err(|e| DomainError::internal(format!("Failed to decode audit changes: {e}")))?;
+
+    Ok(AuditEntry {
+        id: AuditEntryId::from_uuid(row.get("id")),
+        organization_id: OrganizationId::new(row.get::<String, _>("tenant_id")),
+        actor: Actor::new(
+            row.get::<String, _>("actor_kind").parse()?,
+            row.get("actor_id"),
+            row.get("actor_name"),
+        ),
+        entity: row.get::<String, _>("entity_type").parse()?,
+        entity_id: row.get("entity_id"),
+        action: row.get::<String, _>("action").parse()?,
+        before: row.get("before"),
+        after: row.get("after"),
+        changes: changes.0,
+        at: row.get("at"),
+    })
+}
+
+impl AuditRepository for PostgresAuditRepository {
+    async fn append_entry(&self, entry: AuditEntry) -> Result<AuditEntry, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query(
+            "INSERT INTO audit_log (id, tenant_id, actor_kind, actor_id, actor_name, entity_type, entity_id, action, before, after, changes, at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(entry.id.into_uuid())
+        .bind(entry.organization_id.as_str())
+        .bind(entry.actor.kind.as_str())
+        .bind(&entry.actor.id)
+        .bind(&entry.actor.name)
+        .bind(entry.entity.as_str())
+        .bind(&entry.entity_id)
+        .bind(entry.action.as_str())
+        .bind(&entry.before)
+        .bind(&entry.after)
+        .bind(sqlx::types::Json(&entry.changes))
+        .bind(entry.at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(entry)
+    }
+
+    async fn list_entries(
+        &self,
+        organization: &OrganizationId,
+        filter: &AuditFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<AuditEntry>, u64), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        // Unset criteria are bound as NULL and match every row.
+        let criteria = "tenant_id = $1 \
+             AND ($2::VARCHAR IS NULL OR entity_type = $2) \
+             AND ($3::VARCHAR IS NULL OR entity_id = $3)";
+        let entity = filter.entity.map(|e| e.as_str());
+
+        let total: i64 =
+            sqlx::query_scalar(&format!("SELECT COUNT(*) FROM audit_log WHERE {criteria}"))
+                .bind(organization.as_str())
+                .bind(entity)
+                .bind(&filter.entity_id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT {AUDIT_COLUMNS} FROM audit_log WHERE {criteria} \
+             ORDER BY at DESC, id DESC \
+             LIMIT $4 OFFSET $5"
+        ))
+        .bind(organization.as_str())
+        .bind(entity)
+        .bind(&filter.entity_id)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        let entries = rows.iter().map(build_entry).collect::<Result<_, _>>()?;
+        Ok((entries, total as u64))
+    }
+}
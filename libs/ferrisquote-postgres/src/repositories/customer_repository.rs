@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ferrisquote_domain::domain::{
+    customer::{
+        entities::{
+            customer::{Address, Customer},
+            ids::CustomerId,
+        },
+        ports::CustomerRepository,
+    },
+    error::DomainError,
+    organization::entities::ids::OrganizationId,
+};
+use sqlx::{PgPool, Row};
+
+const CUSTOMER_COLUMNS: &str = "id, tenant_id, company_name, contact_name, email, phone, billing_address, \
+                                vat_number, discount_rate, attributes, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct PostgresCustomerRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresCustomerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool: Arc::new(pool),
+        }
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+/// Build a `Customer` from a row selected with `CUSTOMER_COLUMNS`.
+fn build_customer(row: &sqlx::postgres::PgRow) -> Result<Customer, DomainError> {
+    let billing_address: Option<sqlx::types::Json<Address>> = row
+        .try_get("billing_address")
+        .map_err(|e| DomainError::internal(format!("Failed to decode billing address: {e}")))?;
+    let attributes: sqlx::types::Json<HashMap<String, f64>> = row
+        .try_get("attributes")
+        .map_err(|e| DomainError::internal(format!("Failed to decode customer attributes: {e}")))?;
+
+    Ok(Customer {
+        id: CustomerId::from_uuid(row.get("id")),
+        organization_id: OrganizationId::new(row.get::<String, _>("tenant_id")),
+        company_name: row.get("company_name"),
+        contact_name: row.get("contact_name"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        billing_address: billing_address.map(|a| a.0),
+        vat_number: row.get("vat_number"),
+        discount_rate: row.get("discount_rate"),
+        attributes: attributes.0,
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+impl CustomerRepository for PostgresCustomerRepository {
+    async fn create_customer(&self, customer: Customer) -> Result<Customer, DomainError> {
+        sqlx::query(
+            "INSERT INTO customers (id, tenant_id, company_name, contact_name, email, phone, billing_address, vat_number, discount_rate, attributes, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(customer.id.into_uuid())
+        .bind(customer.organization_id.as_str())
+        .bind(&customer.company_name)
+        .bind(&customer.contact_name)
+        .bind(&customer.email)
+        .bind(&customer.phone)
+        .bind(customer.billing_address.as_ref().map(sqlx::types::Json))
+        .bind(&customer.vat_number)
+        .bind(customer.discount_rate)
+        .bind(sqlx::types::Json(&customer.attributes))
+        .bind(customer.created_at)
+        .bind(customer.updated_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(customer)
+    }
+
+    async fn get_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<Customer, DomainError> {
+        let row = sqlx::query(&format!(
+            "SELECT {CUSTOMER_COLUMNS} FROM customers WHERE id = $1 AND tenant_id = $2"
+        ))
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Customer", id.to_string()))?;
+
+        build_customer(&row)
+    }
+
+    async fn list_customers(
+        &self,
+        organization: &OrganizationId,
+    ) -> Result<Vec<Customer>, DomainError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CUSTOMER_COLUMNS} FROM customers WHERE tenant_id = $1 ORDER BY contact_name, id"
+        ))
+        .bind(organization.as_str())
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter().map(build_customer).collect()
+    }
+
+    async fn update_customer(
+        &self,
+        organization: &OrganizationId,
+        customer: Customer,
+    ) -> Result<Customer, DomainError> {
+        let result = sqlx::query(
+            "UPDATE customers \
+             SET company_name = $2, \
+                 contact_name = $3, \
+                 email = $4, \
+                 phone = $5, \
+                 billing_address = $6, \
+                 vat_number = $7, \
+                 discount_rate = $8, \
+                 attributes = $9, \
+                 updated_at = $10 \
+             WHERE id = $1 AND tenant_id = $11",
+        )
+        .bind(customer.id.into_uuid())
+        .bind(&customer.company_name)
+        .bind(&customer.contact_name)
+        .bind(&customer.email)
+        .bind(&customer.phone)
+        .bind(customer.billing_address.as_ref().map(sqlx::types::Json))
+        .bind(&customer.vat_number)
+        .bind(customer.discount_rate)
+        .bind(sqlx::types::Json(&customer.attributes))
+        .bind(customer.updated_at)
+        .bind(organization.as_str())
+        .execute(&*self.pool)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Customer", customer.id.to_string()));
+        }
+
+        Ok(customer)
+    }
+
+    async fn delete_customer(
+        &self,
+        organization: &OrganizationId,
+        id: CustomerId,
+    ) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM customers WHERE id = $1 AND tenant_id = $2")
+            .bind(id.into_uuid())
+            .bind(organization.as_str())
+            .execute(&*self.pool)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found("Customer", id.to_string()));
+        }
+
+        Ok(())
+    }
+}
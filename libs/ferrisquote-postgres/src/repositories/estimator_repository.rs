@@ -11,30 +11,46 @@ use ferrisquote_domain::domain::{
         },
         ports::EstimatorRepository,
     },
-    flows::entities::ids::FlowId,
+    flows::entities::{
+        ids::FlowId,
+        query::{ListQuery, ListSort},
+    },
+    organization::entities::ids::OrganizationId,
+    transaction::ports::Transactional,
 };
-use sqlx::{PgPool, Row};
+use sqlx::{PgConnection, PgPool, Row};
 use uuid::Uuid;
 
+use super::{bump_failed, keyset};
+use crate::unit_of_work::{ConnectionSource, PostgresTransaction};
+
 #[derive(Clone)]
 pub struct PostgresEstimatorRepository {
-    pool: Arc<PgPool>,
+    source: ConnectionSource,
 }
 
 impl PostgresEstimatorRepository {
     pub fn new(pool: PgPool) -> Self {
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
         Self {
-            pool: Arc::new(pool),
+            source: ConnectionSource::Pool(pool),
         }
     }
+}
 
-    pub fn with_pool(pool: Arc<PgPool>) -> Self {
-        Self { pool }
+impl Transactional<PostgresTransaction> for PostgresEstimatorRepository {
+    fn in_transaction(&self, transaction: &PostgresTransaction) -> Self {
+        Self {
+            source: transaction.source(),
+        }
     }
 }
 
 async fn load_variables_for_estimators(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     estimator_ids: &[Uuid],
 ) -> Result<HashMap<Uuid, Vec<EstimatorVariable>>, DomainError> {
     if estimator_ids.is_empty() {
@@ -42,142 +58,261 @@ async fn load_variables_for_estimators(
     }
 
     let rows = sqlx::query(
-        "SELECT id, estimator_id, name, expression, description \
+        "SELECT id, estimator_id, name, expression, description, rank, version \
          FROM estimator_variables \
          WHERE estimator_id = ANY($1) \
          ORDER BY estimator_id, rank",
     )
     .bind(estimator_ids)
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| DomainError::repository(e.to_string()))?;
 
     let mut map: HashMap<Uuid, Vec<EstimatorVariable>> = HashMap::new();
     for row in rows {
-        let var = EstimatorVariable::with_id(
-            EstimatorVariableId::from_uuid(row.get("id")),
-            row.get("name"),
-            row.get("expression"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-        );
-        map.entry(row.get("estimator_id")).or_default().push(var);
+        map.entry(row.get("estimator_id"))
+            .or_default()
+            .push(build_variable(&row));
     }
 
     Ok(map)
 }
 
+/// Build an `Estimator` from a row + its pre-loaded variables.
+fn build_estimator(row: &sqlx::postgres::PgRow, variables: Vec<EstimatorVariable>) -> Estimator {
+    let mut estimator = Estimator::with_variables(
+        EstimatorId::from_uuid(row.get("id")),
+        OrganizationId::new(row.get::<String, _>("tenant_id")),
+        FlowId::from_uuid(row.get("flow_id")),
+        row.get("name"),
+        variables,
+    );
+    estimator.version = row.get::<i32, _>("version") as u32;
+    estimator
+}
+
+/// Build an `EstimatorVariable` from a row.
+fn build_variable(row: &sqlx::postgres::PgRow) -> EstimatorVariable {
+    let mut variable = EstimatorVariable::with_id(
+        EstimatorVariableId::from_uuid(row.get("id")),
+        row.get("name"),
+        row.get("expression"),
+        row.get::<Option<String>, _>("description")
+            .unwrap_or_default(),
+        row.get("rank"),
+    );
+    variable.version = row.get::<i32, _>("version") as u32;
+    variable
+}
+
+/// The version of an estimator of `organization`, if it exists.
+async fn estimator_version(
+    conn: &mut PgConnection,
+    organization: &OrganizationId,
+    id: EstimatorId,
+) -> Result<Option<i32>, DomainError> {
+    sqlx::query_scalar("SELECT version FROM estimators WHERE id = $1 AND tenant_id = $2")
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))
+}
+
+/// The version of a variable whose estimator belongs to `organization`, if it exists.
+async fn variable_version(
+    conn: &mut PgConnection,
+    organization: &OrganizationId,
+    id: EstimatorVariableId,
+) -> Result<Option<i32>, DomainError> {
+    sqlx::query_scalar(
+        "SELECT v.version FROM estimator_variables v \
+         JOIN estimators e ON e.id = v.estimator_id \
+         WHERE v.id = $1 AND e.tenant_id = $2",
+    )
+    .bind(id.into_uuid())
+    .bind(organization.as_str())
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| DomainError::repository(e.to_string()))
+}
+
 impl EstimatorRepository for PostgresEstimatorRepository {
     async fn create_estimator(&self, estimator: Estimator) -> Result<Estimator, DomainError> {
-        sqlx::query(
-            "INSERT INTO estimators (id, flow_id, name, created_at, updated_at) \
-             VALUES ($1, $2, $3, NOW(), NOW())",
+        let mut conn = self.source.acquire().await?;
+        // The flow must belong to the same organization as the estimator.
+        let result = sqlx::query(
+            "INSERT INTO estimators (id, tenant_id, flow_id, name, created_at, updated_at) \
+             SELECT $1, $2, $3, $4, NOW(), NOW() \
+             WHERE EXISTS (SELECT 1 FROM flows WHERE id = $3 AND tenant_id = $2)",
         )
         .bind(estimator.id.into_uuid())
+        .bind(estimator.organization_id.as_str())
         .bind(estimator.flow_id.into_uuid())
         .bind(&estimator.name)
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(
+                "Flow",
+                estimator.flow_id.to_string(),
+            ));
+        }
+
         Ok(estimator)
     }
 
-    async fn get_estimator(&self, id: EstimatorId) -> Result<Estimator, DomainError> {
+    async fn get_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+    ) -> Result<Estimator, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let row = sqlx::query(
-            "SELECT id, flow_id, name FROM estimators WHERE id = $1",
+            "SELECT id, tenant_id, flow_id, name, version FROM estimators WHERE id = $1 AND tenant_id = $2",
         )
         .bind(id.into_uuid())
-        .fetch_optional(&*self.pool)
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?
         .ok_or_else(|| DomainError::not_found("Estimator", id.to_string()))?;
 
         let est_uuid: Uuid = row.get("id");
-        let mut vars_map = load_variables_for_estimators(&self.pool, &[est_uuid]).await?;
+        let mut vars_map = load_variables_for_estimators(&mut conn, &[est_uuid]).await?;
         let variables = vars_map.remove(&est_uuid).unwrap_or_default();
 
-        Ok(Estimator::with_variables(
-            EstimatorId::from_uuid(est_uuid),
-            FlowId::from_uuid(row.get("flow_id")),
-            row.get("name"),
-            variables,
-        ))
+        Ok(build_estimator(&row, variables))
     }
 
     async fn list_estimators_for_flow(
         &self,
+        organization: &OrganizationId,
         flow_id: FlowId,
+        query: &ListQuery<EstimatorId>,
     ) -> Result<Vec<Estimator>, DomainError> {
-        let rows = sqlx::query(
-            "SELECT id, flow_id, name FROM estimators \
-             WHERE flow_id = $1 \
-             ORDER BY created_at",
-        )
-        .bind(flow_id.into_uuid())
-        .fetch_all(&*self.pool)
-        .await
-        .map_err(|e| DomainError::repository(e.to_string()))?;
+        let mut conn = self.source.acquire().await?;
+        let (after, order) = keyset(query, 4, 6);
+        let sql = format!(
+            "SELECT id, tenant_id, flow_id, name, version FROM estimators \
+             WHERE flow_id = $1 AND tenant_id = $2 \
+             AND ($3::TEXT IS NULL OR POSITION(LOWER($3) IN LOWER(name)) > 0) \
+             AND ($4::UUID IS NULL OR {after}) \
+             ORDER BY {order} \
+             LIMIT $5"
+        );
+        let mut statement = sqlx::query(&sql)
+            .bind(flow_id.into_uuid())
+            .bind(organization.as_str())
+            .bind(&query.search)
+            .bind(query.after.as_ref().map(|after| after.id.into_uuid()))
+            .bind(i64::from(query.limit));
+        if query.sort == ListSort::Name {
+            statement = statement.bind(query.after.as_ref().and_then(|after| after.name.clone()));
+        }
+        let rows = statement
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
 
         let est_ids: Vec<Uuid> = rows.iter().map(|r| r.get("id")).collect();
-        let mut vars_map = load_variables_for_estimators(&self.pool, &est_ids).await?;
+        let mut vars_map = load_variables_for_estimators(&mut conn, &est_ids).await?;
 
         let estimators = rows
             .iter()
             .map(|row| {
                 let eid: Uuid = row.get("id");
                 let variables = vars_map.remove(&eid).unwrap_or_default();
-                Estimator::with_variables(
-                    EstimatorId::from_uuid(eid),
-                    FlowId::from_uuid(row.get("flow_id")),
-                    row.get("name"),
-                    variables,
-                )
+                build_estimator(row, variables)
             })
             .collect();
 
         Ok(estimators)
     }
 
+    async fn get_estimator_for_variable(
+        &self,
+        organization: &OrganizationId,
+        variable_id: EstimatorVariableId,
+    ) -> Result<Estimator, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(
+            "SELECT v.estimator_id FROM estimator_variables v \
+             JOIN estimators e ON e.id = v.estimator_id \
+             WHERE v.id = $1 AND e.tenant_id = $2",
+        )
+        .bind(variable_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("EstimatorVariable", variable_id.to_string()))?;
+
+        self.get_estimator(
+            organization,
+            EstimatorId::from_uuid(row.get("estimator_id")),
+        )
+        .await
+    }
+
     async fn update_estimator(
         &self,
+        organization: &OrganizationId,
         id: EstimatorId,
         name: Option<String>,
+        version: Option<u32>,
     ) -> Result<Estimator, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let row = sqlx::query(
             "UPDATE estimators \
              SET name = COALESCE($2, name), \
+                 version = version + 1, \
                  updated_at = NOW() \
-             WHERE id = $1 \
-             RETURNING id, flow_id, name",
+             WHERE id = $1 AND tenant_id = $3 AND ($4::INTEGER IS NULL OR version = $4) \
+             RETURNING id, tenant_id, flow_id, name, version",
         )
         .bind(id.into_uuid())
         .bind(name)
-        .fetch_optional(&*self.pool)
+        .bind(organization.as_str())
+        .bind(version.map(|v| v as i32))
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e| DomainError::repository(e.to_string()))?
-        .ok_or_else(|| DomainError::not_found("Estimator", id.to_string()))?;
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+        let Some(row) = row else {
+            let current = estimator_version(&mut conn, organization, id).await?;
+            return Err(bump_failed("Estimator", id, version, current));
+        };
 
         let est_uuid: Uuid = row.get("id");
-        let mut vars_map = load_variables_for_estimators(&self.pool, &[est_uuid]).await?;
+        let mut vars_map = load_variables_for_estimators(&mut conn, &[est_uuid]).await?;
         let variables = vars_map.remove(&est_uuid).unwrap_or_default();
 
-        Ok(Estimator::with_variables(
-            EstimatorId::from_uuid(est_uuid),
-            FlowId::from_uuid(row.get("flow_id")),
-            row.get("name"),
-            variables,
-        ))
+        Ok(build_estimator(&row, variables))
     }
 
-    async fn delete_estimator(&self, id: EstimatorId) -> Result<(), DomainError> {
-        let result = sqlx::query("DELETE FROM estimators WHERE id = $1")
-            .bind(id.into_uuid())
-            .execute(&*self.pool)
-            .await
-            .map_err(|e| DomainError::repository(e.to_string()))?;
+    async fn delete_estimator(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let result = sqlx::query(
+            "DELETE FROM estimators \
+             WHERE id = $1 AND tenant_id = $2 AND ($3::INTEGER IS NULL OR version = $3)",
+        )
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .bind(version.map(|v| v as i32))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
 
         if result.rows_affected() == 0 {
-            return Err(DomainError::not_found("Estimator", id.to_string()));
+            let current = estimator_version(&mut conn, organization, id).await?;
+            return Err(bump_failed("Estimator", id, version, current));
         }
 
         Ok(())
@@ -185,67 +320,139 @@ impl EstimatorRepository for PostgresEstimatorRepository {
 
     async fn add_variable(
         &self,
+        organization: &OrganizationId,
         estimator_id: EstimatorId,
         variable: EstimatorVariable,
     ) -> Result<EstimatorVariable, DomainError> {
-        sqlx::query(
-            "INSERT INTO estimator_variables (id, estimator_id, name, expression, description, created_at, updated_at) \
-             VALUES ($1, $2, $3, $4, $5, NOW(), NOW())",
+        let mut conn = self.source.acquire().await?;
+        let result = sqlx::query(
+            "INSERT INTO estimator_variables (id, estimator_id, name, expression, description, rank, created_at, updated_at) \
+             SELECT $1, $2, $3, $4, $5, $6, NOW(), NOW() \
+             WHERE EXISTS (SELECT 1 FROM estimators WHERE id = $2 AND tenant_id = $7)",
         )
         .bind(variable.id.into_uuid())
         .bind(estimator_id.into_uuid())
         .bind(&variable.name)
         .bind(&variable.expression)
         .bind(&variable.description)
-        .execute(&*self.pool)
+        .bind(&variable.rank)
+        .bind(organization.as_str())
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(
+                "Estimator",
+                estimator_id.to_string(),
+            ));
+        }
+
         Ok(variable)
     }
 
     async fn update_variable(
         &self,
+        organization: &OrganizationId,
         id: EstimatorVariableId,
         name: Option<String>,
         expression: Option<String>,
         description: Option<String>,
+        rank: Option<String>,
+        version: Option<u32>,
     ) -> Result<EstimatorVariable, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let row = sqlx::query(
-            "UPDATE estimator_variables \
-             SET name = COALESCE($2, name), \
-                 expression = COALESCE($3, expression), \
-                 description = COALESCE($4, description), \
+            "UPDATE estimator_variables v \
+             SET name = COALESCE($2, v.name), \
+                 expression = COALESCE($3, v.expression), \
+                 description = COALESCE($4, v.description), \
+                 rank = COALESCE($5, v.rank), \
+                 version = v.version + 1, \
                  updated_at = NOW() \
-             WHERE id = $1 \
-             RETURNING id, name, expression, description",
+             FROM estimators e \
+             WHERE v.id = $1 AND e.id = v.estimator_id AND e.tenant_id = $6 \
+               AND ($7::INTEGER IS NULL OR v.version = $7) \
+             RETURNING v.id, v.name, v.expression, v.description, v.rank, v.version",
         )
         .bind(id.into_uuid())
         .bind(name)
         .bind(expression)
         .bind(description)
-        .fetch_optional(&*self.pool)
+        .bind(rank)
+        .bind(organization.as_str())
+        .bind(version.map(|v| v as i32))
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e| DomainError::repository(e.to_string()))?
-        .ok_or_else(|| DomainError::not_found("EstimatorVariable", id.to_string()))?;
-
-        Ok(EstimatorVariable::with_id(
-            EstimatorVariableId::from_uuid(row.get("id")),
-            row.get("name"),
-            row.get("expression"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-        ))
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        match row {
+            Some(row) => Ok(build_variable(&row)),
+            None => {
+                let current = variable_version(&mut conn, organization, id).await?;
+                Err(bump_failed("EstimatorVariable", id, version, current))
+            }
+        }
     }
 
-    async fn remove_variable(&self, id: EstimatorVariableId) -> Result<(), DomainError> {
-        let result = sqlx::query("DELETE FROM estimator_variables WHERE id = $1")
-            .bind(id.into_uuid())
-            .execute(&*self.pool)
-            .await
-            .map_err(|e| DomainError::repository(e.to_string()))?;
+    async fn update_variable_ranks(
+        &self,
+        organization: &OrganizationId,
+        ranks: &[(EstimatorVariableId, String)],
+    ) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let (ids, values): (Vec<Uuid>, Vec<&str>) = ranks
+            .iter()
+            .map(|(id, rank)| (id.into_uuid(), rank.as_str()))
+            .unzip();
+        let updated: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE estimator_variables v \
+             SET rank = r.rank, updated_at = NOW() \
+             FROM UNNEST($1::UUID[], $2::TEXT[]) AS r(id, rank), estimators e \
+             WHERE v.id = r.id AND e.id = v.estimator_id AND e.tenant_id = $3 \
+             RETURNING v.id",
+        )
+        .bind(&ids)
+        .bind(&values)
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        // Callers run this in a transaction, which rolls back the other variables.
+        if let Some(missing) = ids.iter().find(|id| !updated.contains(id)) {
+            return Err(DomainError::not_found(
+                "EstimatorVariable",
+                missing.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_variable(
+        &self,
+        organization: &OrganizationId,
+        id: EstimatorVariableId,
+        version: Option<u32>,
+    ) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let result = sqlx::query(
+            "DELETE FROM estimator_variables v \
+             USING estimators e \
+             WHERE v.id = $1 AND e.id = v.estimator_id AND e.tenant_id = $2 \
+               AND ($3::INTEGER IS NULL OR v.version = $3)",
+        )
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .bind(version.map(|v| v as i32))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
 
         if result.rows_affected() == 0 {
-            return Err(DomainError::not_found("EstimatorVariable", id.to_string()));
+            let current = variable_version(&mut conn, organization, id).await?;
+            return Err(bump_failed("EstimatorVariable", id, version, current));
         }
 
         Ok(())
@@ -6,31 +6,45 @@ use ferrisquote_domain::domain::{
     flows::{
         entities::{
             field::{Field, FieldConfig},
-            flow::Flow,
+            flow::{Flow, FlowSummary},
             ids::{FieldId, FlowId, StepId},
+            query::{ListQuery, ListSort},
             step::Step,
         },
         ports::{FieldRepository, FlowRepository, StepRepository},
     },
+    organization::entities::ids::OrganizationId,
+    transaction::ports::Transactional,
 };
-use sqlx::{PgPool, Row};
+use sqlx::{PgConnection, PgPool, Row};
 use uuid::Uuid;
 
+use super::{bump_failed, keyset};
+use crate::unit_of_work::{ConnectionSource, PostgresTransaction};
+
 /// PostgreSQL implementation of FlowRepository, StepRepository and FieldRepository.
 #[derive(Clone)]
 pub struct PostgresFlowRepository {
-    pool: Arc<PgPool>,
+    source: ConnectionSource,
 }
 
 impl PostgresFlowRepository {
     pub fn new(pool: PgPool) -> Self {
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
         Self {
-            pool: Arc::new(pool),
+            source: ConnectionSource::Pool(pool),
         }
     }
+}
 
-    pub fn with_pool(pool: Arc<PgPool>) -> Self {
-        Self { pool }
+impl Transactional<PostgresTransaction> for PostgresFlowRepository {
+    fn in_transaction(&self, transaction: &PostgresTransaction) -> Self {
+        Self {
+            source: transaction.source(),
+        }
     }
 }
 
@@ -41,7 +55,7 @@ impl PostgresFlowRepository {
 /// Load steps (with their fields) for a list of flow IDs.
 /// Returns a map flow_id → Vec<Step> sorted by rank.
 async fn load_steps_for_flows(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     flow_ids: &[Uuid],
 ) -> Result<HashMap<Uuid, Vec<Step>>, DomainError> {
     if flow_ids.is_empty() {
@@ -50,13 +64,13 @@ async fn load_steps_for_flows(
 
     // Fetch steps for all given flows in one query
     let step_rows = sqlx::query(
-        "SELECT id, flow_id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats \
+        "SELECT id, flow_id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats, version \
          FROM steps \
          WHERE flow_id = ANY($1) \
          ORDER BY flow_id, rank",
     )
     .bind(flow_ids)
-    .fetch_all(pool)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -66,32 +80,21 @@ async fn load_steps_for_flows(
     let mut fields_by_step: HashMap<Uuid, Vec<Field>> = HashMap::new();
     if !step_ids.is_empty() {
         let field_rows = sqlx::query(
-            "SELECT id, steps_id, key, label, description, rank, config \
+            "SELECT id, steps_id, key, label, description, rank, config, version \
              FROM fields \
              WHERE steps_id = ANY($1) \
              ORDER BY steps_id, rank",
         )
         .bind(step_ids.as_slice())
-        .fetch_all(pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
         for row in field_rows {
-            let config_json: sqlx::types::Json<FieldConfig> = row
-                .try_get("config")
-                .map_err(|e| DomainError::internal(format!("Failed to decode field config: {e}")))?;
-            let field = Field::with_id(
-                FieldId::from_uuid(row.get("id")),
-                row.get("key"),
-                row.get("label"),
-                row.get::<Option<String>, _>("description").unwrap_or_default(),
-                row.get("rank"),
-                config_json.0,
-            );
             fields_by_step
                 .entry(row.get("steps_id"))
                 .or_default()
-                .push(field);
+                .push(build_field(&row)?);
         }
     }
 
@@ -100,34 +103,100 @@ async fn load_steps_for_flows(
     for row in step_rows {
         let step_id: Uuid = row.get("id");
         let fields = fields_by_step.remove(&step_id).unwrap_or_default();
-        let step = Step::with_fields(
-            StepId::from_uuid(step_id),
-            row.get("title"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-            row.get("rank"),
-            row.get("is_repeatable"),
-            row.get("repeat_label"),
-            row.get::<i32, _>("min_repeats") as u32,
-            row.get::<Option<i32>, _>("max_repeats").map(|v| v as u32),
-            fields,
-        );
         steps_by_flow
             .entry(row.get("flow_id"))
             .or_default()
-            .push(step);
+            .push(build_step(&row, fields));
     }
 
     Ok(steps_by_flow)
 }
 
+/// Columns of a step row, selected from `steps s`.
+const STEP_COLUMNS: &str = "s.id, s.title, s.description, s.rank, s.is_repeatable, s.repeat_label, s.min_repeats, s.max_repeats, s.version";
+
+/// Build a full `Step` from a row selected with `STEP_COLUMNS`, loading its fields.
+async fn load_step(
+    conn: &mut PgConnection,
+    row: &sqlx::postgres::PgRow,
+) -> Result<Step, DomainError> {
+    let step_uuid: Uuid = row.get("id");
+    let field_rows = sqlx::query(
+        "SELECT id, key, label, description, rank, config, version \
+         FROM fields WHERE steps_id = $1 ORDER BY rank",
+    )
+    .bind(step_uuid)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| DomainError::repository(e.to_string()))?;
+
+    let fields = field_rows
+        .iter()
+        .map(build_field)
+        .collect::<Result<_, _>>()?;
+    Ok(build_step(row, fields))
+}
+
+/// Build a `Step` from a row + its pre-loaded fields.
+fn build_step(row: &sqlx::postgres::PgRow, fields: Vec<Field>) -> Step {
+    let mut step = Step::with_fields(
+        StepId::from_uuid(row.get("id")),
+        row.get("title"),
+        row.get::<Option<String>, _>("description")
+            .unwrap_or_default(),
+        row.get("rank"),
+        row.get("is_repeatable"),
+        row.get("repeat_label"),
+        row.get::<i32, _>("min_repeats") as u32,
+        row.get::<Option<i32>, _>("max_repeats").map(|v| v as u32),
+        fields,
+    );
+    step.version = row.get::<i32, _>("version") as u32;
+    step
+}
+
+/// Build a `Field` from a row, decoding its JSON config.
+fn build_field(row: &sqlx::postgres::PgRow) -> Result<Field, DomainError> {
+    let config_json: sqlx::types::Json<FieldConfig> = row
+        .try_get("config")
+        .map_err(|e| DomainError::internal(format!("Failed to decode field config: {e}")))?;
+    let mut field = Field::with_id(
+        FieldId::from_uuid(row.get("id")),
+        row.get("key"),
+        row.get("label"),
+        row.get::<Option<String>, _>("description")
+            .unwrap_or_default(),
+        row.get("rank"),
+        config_json.0,
+    );
+    field.version = row.get::<i32, _>("version") as u32;
+    Ok(field)
+}
+
+/// Build a `FlowSummary` from a row of `list_flows`.
+fn build_summary(row: &sqlx::postgres::PgRow) -> FlowSummary {
+    FlowSummary {
+        id: FlowId::from_uuid(row.get("id")),
+        name: row.get("name"),
+        description: row
+            .get::<Option<String>, _>("description")
+            .unwrap_or_default(),
+        step_count: row.get::<i64, _>("step_count") as usize,
+        version: row.get::<i32, _>("version") as u32,
+    }
+}
+
 /// Build a full `Flow` from a row + pre-loaded steps map.
 fn build_flow(row: &sqlx::postgres::PgRow, steps: Vec<Step>) -> Flow {
-    Flow::with_steps(
+    let mut flow = Flow::with_steps(
         FlowId::from_uuid(row.get("id")),
+        OrganizationId::new(row.get::<String, _>("tenant_id")),
         row.get("name"),
         row.get::<Option<String>, _>("description").unwrap_or_default(),
         steps,
-    )
+    );
+    flow.version = row.get::<i32, _>("version") as u32;
+    flow
 }
 
 // ============================================================================
@@ -136,91 +205,121 @@ fn build_flow(row: &sqlx::postgres::PgRow, steps: Vec<Step>) -> Flow {
 
 impl FlowRepository for PostgresFlowRepository {
     async fn create_flow(&self, flow: Flow) -> Result<Flow, DomainError> {
+        let mut conn = self.source.acquire().await?;
         sqlx::query(
-            "INSERT INTO flows (id, name, description, created_at, updated_at) \
-             VALUES ($1, $2, $3, NOW(), NOW())",
+            "INSERT INTO flows (id, tenant_id, name, description, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, NOW(), NOW())",
         )
         .bind(flow.id.into_uuid())
+        .bind(flow.organization_id.as_str())
         .bind(&flow.name)
         .bind(&flow.description)
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
         Ok(flow)
     }
 
-    async fn get_flow(&self, id: FlowId) -> Result<Flow, DomainError> {
-        let row = sqlx::query("SELECT id, name, description FROM flows WHERE id = $1")
+    async fn get_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> Result<Flow, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(
+            "SELECT id, tenant_id, name, description, version FROM flows WHERE id = $1 AND tenant_id = $2",
+        )
             .bind(id.into_uuid())
-            .fetch_optional(&*self.pool)
+            .bind(organization.as_str())
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| DomainError::repository(e.to_string()))?
             .ok_or_else(|| DomainError::not_found("Flow", id.to_string()))?;
 
         let flow_uuid: Uuid = row.get("id");
-        let mut steps_map = load_steps_for_flows(&self.pool, &[flow_uuid]).await?;
+        let mut steps_map = load_steps_for_flows(&mut conn, &[flow_uuid]).await?;
         let steps = steps_map.remove(&flow_uuid).unwrap_or_default();
 
         Ok(build_flow(&row, steps))
     }
 
-    async fn list_flows(&self) -> Result<Vec<Flow>, DomainError> {
-        let rows = sqlx::query(
-            "SELECT id, name, description FROM flows ORDER BY created_at DESC",
-        )
-        .fetch_all(&*self.pool)
-        .await
-        .map_err(|e| DomainError::repository(e.to_string()))?;
-
-        let flow_ids: Vec<Uuid> = rows.iter().map(|r| r.get("id")).collect();
-        let mut steps_map = load_steps_for_flows(&self.pool, &flow_ids).await?;
+    async fn list_flows(
+        &self,
+        organization: &OrganizationId,
+        query: &ListQuery<FlowId>,
+    ) -> Result<Vec<FlowSummary>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        // Steps are counted, not loaded, and fields are not read at all.
+        let (after, order) = keyset(query, 3, 5);
+        let sql = format!(
+            "SELECT id, name, description, version, \
+             (SELECT COUNT(*) FROM steps WHERE steps.flow_id = flows.id) AS step_count \
+             FROM flows \
+             WHERE tenant_id = $1 \
+             AND ($2::TEXT IS NULL OR POSITION(LOWER($2) IN LOWER(name)) > 0) \
+             AND ($3::UUID IS NULL OR {after}) \
+             ORDER BY {order} \
+             LIMIT $4"
+        );
+        let mut statement = sqlx::query(&sql)
+            .bind(organization.as_str())
+            .bind(&query.search)
+            .bind(query.after.as_ref().map(|after| after.id.into_uuid()))
+            .bind(i64::from(query.limit));
+        if query.sort == ListSort::Name {
+            statement = statement.bind(query.after.as_ref().and_then(|after| after.name.clone()));
+        }
+        let rows = statement
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
 
-        let flows = rows
-            .iter()
-            .map(|row| {
-                let fid: Uuid = row.get("id");
-                let steps = steps_map.remove(&fid).unwrap_or_default();
-                build_flow(row, steps)
-            })
-            .collect();
-
-        Ok(flows)
+        Ok(rows.iter().map(build_summary).collect())
     }
 
     async fn update_flow(
         &self,
+        organization: &OrganizationId,
         id: FlowId,
         name: Option<String>,
         description: Option<String>,
     ) -> Result<Flow, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let row = sqlx::query(
             "UPDATE flows \
              SET name = COALESCE($2, name), \
                  description = COALESCE($3, description), \
                  updated_at = NOW() \
-             WHERE id = $1 \
-             RETURNING id, name, description",
+             WHERE id = $1 AND tenant_id = $4 \
+             RETURNING id, tenant_id, name, description, version",
         )
         .bind(id.into_uuid())
         .bind(name)
         .bind(description)
-        .fetch_optional(&*self.pool)
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?
         .ok_or_else(|| DomainError::not_found("Flow", id.to_string()))?;
 
         let flow_uuid: Uuid = row.get("id");
-        let mut steps_map = load_steps_for_flows(&self.pool, &[flow_uuid]).await?;
+        let mut steps_map = load_steps_for_flows(&mut conn, &[flow_uuid]).await?;
         let steps = steps_map.remove(&flow_uuid).unwrap_or_default();
 
         Ok(build_flow(&row, steps))
     }
 
-    async fn delete_flow(&self, id: FlowId) -> Result<(), DomainError> {
-        let result = sqlx::query("DELETE FROM flows WHERE id = $1")
+    async fn delete_flow(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+    ) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let result = sqlx::query("DELETE FROM flows WHERE id = $1 AND tenant_id = $2")
             .bind(id.into_uuid())
-            .execute(&*self.pool)
+            .bind(organization.as_str())
+            .execute(&mut *conn)
             .await
             .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -230,6 +329,39 @@ impl FlowRepository for PostgresFlowRepository {
 
         Ok(())
     }
+
+    async fn bump_flow_version(
+        &self,
+        organization: &OrganizationId,
+        id: FlowId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let bumped: Option<i32> = sqlx::query_scalar(
+            "UPDATE flows \
+             SET version = version + 1, updated_at = NOW() \
+             WHERE id = $1 AND tenant_id = $2 AND ($3::INTEGER IS NULL OR version = $3) \
+             RETURNING version",
+        )
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .bind(expected.map(|v| v as i32))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+        if let Some(version) = bumped {
+            return Ok(version as u32);
+        }
+
+        let current: Option<i32> =
+            sqlx::query_scalar("SELECT version FROM flows WHERE id = $1 AND tenant_id = $2")
+                .bind(id.into_uuid())
+                .bind(organization.as_str())
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| DomainError::repository(e.to_string()))?;
+        Err(bump_failed("Flow", id, expected, current))
+    }
 }
 
 // ============================================================================
@@ -238,6 +370,7 @@ impl FlowRepository for PostgresFlowRepository {
 
 impl StepRepository for PostgresFlowRepository {
     async fn create_step(&self, flow_id: FlowId, step: Step) -> Result<Step, DomainError> {
+        let mut conn = self.source.acquire().await?;
         sqlx::query(
             "INSERT INTO steps (id, flow_id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats, created_at, updated_at) \
              VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW(), NOW())",
@@ -251,7 +384,7 @@ impl StepRepository for PostgresFlowRepository {
         .bind(&step.repeat_label)
         .bind(step.min_repeats as i32)
         .bind(step.max_repeats.map(|v| v as i32))
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -259,51 +392,62 @@ impl StepRepository for PostgresFlowRepository {
     }
 
     async fn get_step(&self, id: StepId) -> Result<Step, DomainError> {
-        let row = sqlx::query(
-            "SELECT id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats FROM steps WHERE id = $1",
-        )
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(&format!(
+            "SELECT {STEP_COLUMNS} FROM steps s WHERE s.id = $1"
+        ))
         .bind(id.into_uuid())
-        .fetch_optional(&*self.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?
         .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
 
-        let step_uuid: Uuid = row.get("id");
-        let field_rows = sqlx::query(
-            "SELECT id, key, label, description, rank, config \
-             FROM fields WHERE steps_id = $1 ORDER BY rank",
-        )
-        .bind(step_uuid)
-        .fetch_all(&*self.pool)
+        load_step(&mut conn, &row).await
+    }
+
+    async fn get_step_with_flow(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> Result<(FlowId, Step), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(&format!(
+            "SELECT {STEP_COLUMNS}, s.flow_id \
+             FROM steps s \
+             JOIN flows f ON f.id = s.flow_id \
+             WHERE s.id = $1 AND f.tenant_id = $2"
+        ))
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
         .await
-        .map_err(|e| DomainError::repository(e.to_string()))?;
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
 
-        let mut fields = Vec::new();
-        for fr in field_rows {
-            let config_json: sqlx::types::Json<FieldConfig> = fr
-                .try_get("config")
-                .map_err(|e| DomainError::internal(format!("Failed to decode field config: {e}")))?;
-            fields.push(Field::with_id(
-                FieldId::from_uuid(fr.get("id")),
-                fr.get("key"),
-                fr.get("label"),
-                fr.get::<Option<String>, _>("description").unwrap_or_default(),
-                fr.get("rank"),
-                config_json.0,
-            ));
-        }
+        let step = load_step(&mut conn, &row).await?;
+        Ok((FlowId::from_uuid(row.get("flow_id")), step))
+    }
 
-        Ok(Step::with_fields(
-            StepId::from_uuid(step_uuid),
-            row.get("title"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-            row.get("rank"),
-            row.get("is_repeatable"),
-            row.get("repeat_label"),
-            row.get::<i32, _>("min_repeats") as u32,
-            row.get::<Option<i32>, _>("max_repeats").map(|v| v as u32),
-            fields,
-        ))
+    async fn find_flow_id_for_step(
+        &self,
+        organization: &OrganizationId,
+        id: StepId,
+    ) -> Result<FlowId, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let flow_id: Uuid = sqlx::query_scalar(
+            "SELECT s.flow_id \
+             FROM steps s \
+             JOIN flows f ON f.id = s.flow_id \
+             WHERE s.id = $1 AND f.tenant_id = $2",
+        )
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
+
+        Ok(FlowId::from_uuid(flow_id))
     }
 
     async fn update_step(
@@ -317,6 +461,7 @@ impl StepRepository for PostgresFlowRepository {
         min_repeats: Option<u32>,
         max_repeats: Option<Option<u32>>,
     ) -> Result<Step, DomainError> {
+        let mut conn = self.source.acquire().await?;
         sqlx::query(
             "UPDATE steps \
              SET title = COALESCE($2, title), \
@@ -339,37 +484,55 @@ impl StepRepository for PostgresFlowRepository {
         .bind(min_repeats.map(|v| v as i32))
         .bind(max_repeats.is_some())
         .bind(max_repeats.flatten().map(|v| v as i32))
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
         // Reload the step with its fields
         let row = sqlx::query(
-            "SELECT id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats FROM steps WHERE id = $1",
+            "SELECT id, title, description, rank, is_repeatable, repeat_label, min_repeats, max_repeats, version FROM steps WHERE id = $1",
         )
         .bind(id.into_uuid())
-        .fetch_optional(&*self.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?
         .ok_or_else(|| DomainError::not_found("Step", id.to_string()))?;
 
-        Ok(Step::with_fields(
-            StepId::from_uuid(row.get("id")),
-            row.get("title"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-            row.get("rank"),
-            row.get("is_repeatable"),
-            row.get("repeat_label"),
-            row.get::<i32, _>("min_repeats") as u32,
-            row.get::<Option<i32>, _>("max_repeats").map(|v| v as u32),
-            vec![],
-        ))
+        Ok(build_step(&row, vec![]))
+    }
+
+    async fn update_step_ranks(&self, ranks: &[(StepId, String)]) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let (ids, values): (Vec<Uuid>, Vec<&str>) = ranks
+            .iter()
+            .map(|(id, rank)| (id.into_uuid(), rank.as_str()))
+            .unzip();
+        let updated: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE steps \
+             SET rank = r.rank, updated_at = NOW() \
+             FROM UNNEST($1::UUID[], $2::TEXT[]) AS r(id, rank) \
+             WHERE steps.id = r.id \
+             RETURNING steps.id",
+        )
+        .bind(&ids)
+        .bind(&values)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        // Callers run this in a transaction, which rolls back the other steps.
+        if let Some(missing) = ids.iter().find(|id| !updated.contains(id)) {
+            return Err(DomainError::not_found("Step", missing.to_string()));
+        }
+
+        Ok(())
     }
 
     async fn delete_step(&self, id: StepId) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
         let result = sqlx::query("DELETE FROM steps WHERE id = $1")
             .bind(id.into_uuid())
-            .execute(&*self.pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -379,6 +542,35 @@ impl StepRepository for PostgresFlowRepository {
 
         Ok(())
     }
+
+    async fn bump_step_version(
+        &self,
+        id: StepId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let bumped: Option<i32> = sqlx::query_scalar(
+            "UPDATE steps \
+             SET version = version + 1, updated_at = NOW() \
+             WHERE id = $1 AND ($2::INTEGER IS NULL OR version = $2) \
+             RETURNING version",
+        )
+        .bind(id.into_uuid())
+        .bind(expected.map(|v| v as i32))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+        if let Some(version) = bumped {
+            return Ok(version as u32);
+        }
+
+        let current: Option<i32> = sqlx::query_scalar("SELECT version FROM steps WHERE id = $1")
+            .bind(id.into_uuid())
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+        Err(bump_failed("Step", id, expected, current))
+    }
 }
 
 // ============================================================================
@@ -387,6 +579,7 @@ impl StepRepository for PostgresFlowRepository {
 
 impl FieldRepository for PostgresFlowRepository {
     async fn create_field(&self, step_id: StepId, field: Field) -> Result<Field, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let config_json = sqlx::types::Json(&field.config);
         sqlx::query(
             "INSERT INTO fields (id, steps_id, key, label, description, rank, config, created_at, updated_at) \
@@ -399,7 +592,7 @@ impl FieldRepository for PostgresFlowRepository {
         .bind(&field.description)
         .bind(&field.rank)
         .bind(config_json)
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -414,6 +607,7 @@ impl FieldRepository for PostgresFlowRepository {
         description: Option<String>,
         config: Option<FieldConfig>,
     ) -> Result<Field, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let config_json = config.as_ref().map(sqlx::types::Json);
         let row = sqlx::query(
             "UPDATE fields \
@@ -423,36 +617,26 @@ impl FieldRepository for PostgresFlowRepository {
                  config = COALESCE($5, config), \
                  updated_at = NOW() \
              WHERE id = $1 \
-             RETURNING id, key, label, description, rank, config",
+             RETURNING id, key, label, description, rank, config, version",
         )
         .bind(field_id.into_uuid())
         .bind(key)
         .bind(label)
         .bind(description)
         .bind(config_json)
-        .fetch_optional(&*self.pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?
         .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
 
-        let config_json: sqlx::types::Json<FieldConfig> = row
-            .try_get("config")
-            .map_err(|e| DomainError::internal(format!("Failed to decode field config: {e}")))?;
-
-        Ok(Field::with_id(
-            FieldId::from_uuid(row.get("id")),
-            row.get("key"),
-            row.get("label"),
-            row.get::<Option<String>, _>("description").unwrap_or_default(),
-            row.get("rank"),
-            config_json.0,
-        ))
+        build_field(&row)
     }
 
     async fn delete_field(&self, id: FieldId) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
         let result = sqlx::query("DELETE FROM fields WHERE id = $1")
             .bind(id.into_uuid())
-            .execute(&*self.pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| DomainError::repository(e.to_string()))?;
 
@@ -463,14 +647,120 @@ impl FieldRepository for PostgresFlowRepository {
         Ok(())
     }
 
+    async fn bump_field_version(
+        &self,
+        id: FieldId,
+        expected: Option<u32>,
+    ) -> Result<u32, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let bumped: Option<i32> = sqlx::query_scalar(
+            "UPDATE fields \
+             SET version = version + 1, updated_at = NOW() \
+             WHERE id = $1 AND ($2::INTEGER IS NULL OR version = $2) \
+             RETURNING version",
+        )
+        .bind(id.into_uuid())
+        .bind(expected.map(|v| v as i32))
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+        if let Some(version) = bumped {
+            return Ok(version as u32);
+        }
+
+        let current: Option<i32> = sqlx::query_scalar("SELECT version FROM fields WHERE id = $1")
+            .bind(id.into_uuid())
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+        Err(bump_failed("Field", id, expected, current))
+    }
+
+    async fn move_field(
+        &self,
+        field_id: FieldId,
+        step_id: StepId,
+        rank: String,
+    ) -> Result<Field, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(
+            "UPDATE fields \
+             SET steps_id = $2, \
+                 rank = $3, \
+                 updated_at = NOW() \
+             WHERE id = $1 \
+             RETURNING id, key, label, description, rank, config, version",
+        )
+        .bind(field_id.into_uuid())
+        .bind(step_id.into_uuid())
+        .bind(rank)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
+
+        build_field(&row)
+    }
+
+    async fn update_field_ranks(&self, ranks: &[(FieldId, String)]) -> Result<(), DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let (ids, values): (Vec<Uuid>, Vec<&str>) = ranks
+            .iter()
+            .map(|(id, rank)| (id.into_uuid(), rank.as_str()))
+            .unzip();
+        let updated: Vec<Uuid> = sqlx::query_scalar(
+            "UPDATE fields \
+             SET rank = r.rank, updated_at = NOW() \
+             FROM UNNEST($1::UUID[], $2::TEXT[]) AS r(id, rank) \
+             WHERE fields.id = r.id \
+             RETURNING fields.id",
+        )
+        .bind(&ids)
+        .bind(&values)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        // Callers run this in a transaction, which rolls back the other fields.
+        if let Some(missing) = ids.iter().find(|id| !updated.contains(id)) {
+            return Err(DomainError::not_found("Field", missing.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn find_flow_id_for_field(
+        &self,
+        organization: &OrganizationId,
+        field_id: FieldId,
+    ) -> Result<FlowId, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let flow_id: Uuid = sqlx::query_scalar(
+            "SELECT s.flow_id \
+             FROM fields f \
+             JOIN steps s ON s.id = f.steps_id \
+             JOIN flows fl ON fl.id = s.flow_id \
+             WHERE f.id = $1 AND fl.tenant_id = $2",
+        )
+        .bind(field_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Field", field_id.to_string()))?;
+
+        Ok(FlowId::from_uuid(flow_id))
+    }
+
     async fn get_flow_fields(
         &self,
         flow_id: FlowId,
         like: Option<String>,
     ) -> Result<Vec<Field>, DomainError> {
+        let mut conn = self.source.acquire().await?;
         let pattern = like.map(|q| format!("%{q}%"));
         let rows = sqlx::query(
-            "SELECT f.id, f.key, f.label, f.description, f.rank, f.config \
+            "SELECT f.id, f.key, f.label, f.description, f.rank, f.config, f.version \
              FROM fields f \
              JOIN steps s ON s.id = f.steps_id \
              WHERE s.flow_id = $1 \
@@ -479,25 +769,10 @@ impl FieldRepository for PostgresFlowRepository {
         )
         .bind(flow_id.into_uuid())
         .bind(pattern)
-        .fetch_all(&*self.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(|e| DomainError::repository(e.to_string()))?;
 
-        let mut fields = Vec::new();
-        for row in rows {
-            let config_json: sqlx::types::Json<FieldConfig> = row
-                .try_get("config")
-                .map_err(|e| DomainError::internal(format!("Failed to decode field config: {e}")))?;
-            fields.push(Field::with_id(
-                FieldId::from_uuid(row.get("id")),
-                row.get("key"),
-                row.get("label"),
-                row.get::<Option<String>, _>("description").unwrap_or_default(),
-                row.get("rank"),
-                config_json.0,
-            ));
-        }
-
-        Ok(fields)
+        rows.iter().map(build_field).collect()
     }
 }
@@ -1,5 +1,58 @@
+pub mod api_key_repository;
+pub mod audit_repository;
+pub mod customer_repository;
 pub mod estimator_repository;
 pub mod flow_repository;
+pub mod quote_repository;
+pub mod runner_repository;
 
+pub use api_key_repository::PostgresApiKeyRepository;
+pub use audit_repository::PostgresAuditRepository;
+pub use customer_repository::PostgresCustomerRepository;
 pub use estimator_repository::PostgresEstimatorRepository;
 pub use flow_repository::PostgresFlowRepository;
+pub use quote_repository::PostgresQuoteRepository;
+pub use runner_repository::PostgresRunnerRepository;
+
+use std::fmt::Display;
+
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    flows::entities::query::{ListQuery, ListSort, SortOrder},
+};
+
+/// The error for a version bump that matched no row, given the version the row
+/// is at when it still exists.
+pub(crate) fn bump_failed(
+    entity: &str,
+    id: impl Display,
+    expected: Option<u32>,
+    current: Option<i32>,
+) -> DomainError {
+    match (expected, current) {
+        (Some(expected), Some(current)) => {
+            DomainError::stale_version(entity, id, expected, current as u32)
+        }
+        _ => DomainError::not_found(entity, id.to_string()),
+    }
+}
+
+/// The cursor condition and the `ORDER BY` of a listing over a table with
+/// `id` and `name` columns.
+///
+/// The condition compares rows to the cursor id, bound as `$after`, and when
+/// sorting by name to the cursor name, bound as `$name`. Names are lowercased
+/// and compared byte by byte, like the in-memory repositories do.
+pub(crate) fn keyset<C>(query: &ListQuery<C>, after: usize, name: usize) -> (String, String) {
+    let (cmp, direction) = match query.order {
+        SortOrder::Asc => (">", "ASC"),
+        SortOrder::Desc => ("<", "DESC"),
+    };
+    match query.sort {
+        ListSort::Created => (format!("id {cmp} ${after}"), format!("id {direction}")),
+        ListSort::Name => (
+            format!("(LOWER(name) COLLATE \"C\", id) {cmp} (LOWER(${name}::TEXT), ${after})"),
+            format!("LOWER(name) COLLATE \"C\" {direction}, id {direction}"),
+        ),
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    customer::entities::ids::CustomerId,
+    error::DomainError,
+    estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+    flows::entities::ids::FlowId,
+    organization::entities::ids::OrganizationId,
+    quote::{
+        entities::{
+            ids::QuoteId,
+            quote::Quote,
+            status::{QuoteStatus, QuoteStatusChange},
+        },
+        ports::QuoteRepository,
+    },
+    transaction::ports::Transactional,
+};
+use sqlx::{Connection as _, PgPool, Row};
+use uuid::Uuid;
+
+use crate::unit_of_work::{ConnectionSource, PostgresTransaction};
+
+const QUOTE_COLUMNS: &str = "id, root_id, revision, tenant_id, flow_id, estimator_id, customer_id, \
+                             status, submission, results, total, validity_days, valid_until, \
+                             created_at, updated_at";
+
+#[derive(Clone)]
+pub struct PostgresQuoteRepository {
+    source: ConnectionSource,
+}
+
+impl PostgresQuoteRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self {
+            source: ConnectionSource::Pool(pool),
+        }
+    }
+}
+
+impl Transactional<PostgresTransaction> for PostgresQuoteRepository {
+    fn in_transaction(&self, transaction: &PostgresTransaction) -> Self {
+        Self {
+            source: transaction.source(),
+        }
+    }
+}
+
+/// Build a `Quote` from a row selected with `QUOTE_COLUMNS`.
+fn build_quote(row: &sqlx::postgres::PgRow) -> Result<Quote, DomainError> {
+    let submission: sqlx::types::Json<SubmissionData> = row
+        .try_get("submission")
+        .map_err(|e| DomainError::internal(format!("Failed to decode quote submission: {e}")))?;
+    let results: sqlx::types::Json<HashMap<String, f64>> = row
+        .try_get("results")
+        .map_err(|e| DomainError::internal(format!("Failed to decode quote results: {e}")))?;
+
+    Ok(Quote {
+        id: QuoteId::from_uuid(row.get("id")),
+        root_id: QuoteId::from_uuid(row.get("root_id")),
+        revision: row.get::<i32, _>("revision") as u32,
+        organization_id: OrganizationId::new(row.get::<String, _>("tenant_id")),
+        flow_id: FlowId::from_uuid(row.get("flow_id")),
+        estimator_id: EstimatorId::from_uuid(row.get("estimator_id")),
+        customer_id: row
+            .get::<Option<Uuid>, _>("customer_id")
+            .map(CustomerId::from_uuid),
+        status: row.get::<String, _>("status").parse()?,
+        submission: submission.0,
+        results: results.0,
+        total: row.get("total"),
+        validity_days: row.get::<i32, _>("validity_days") as u32,
+        valid_until: row.get("valid_until"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+impl QuoteRepository for PostgresQuoteRepository {
+    async fn create_quote(&self, quote: Quote) -> Result<Quote, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query(
+            "INSERT INTO quotes (id, root_id, revision, tenant_id, flow_id, estimator_id, customer_id, status, submission, results, total, validity_days, valid_until, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+        )
+        .bind(quote.id.into_uuid())
+        .bind(quote.root_id.into_uuid())
+        .bind(quote.revision as i32)
+        .bind(quote.organization_id.as_str())
+        .bind(quote.flow_id.into_uuid())
+        .bind(quote.estimator_id.into_uuid())
+        .bind(quote.customer_id.map(CustomerId::into_uuid))
+        .bind(quote.status.as_str())
+        .bind(sqlx::types::Json(&quote.submission))
+        .bind(sqlx::types::Json(&quote.results))
+        .bind(quote.total)
+        .bind(quote.validity_days as i32)
+        .bind(quote.valid_until)
+        .bind(quote.created_at)
+        .bind(quote.updated_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(quote)
+    }
+
+    async fn get_quote(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Quote, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(&format!(
+            "SELECT {QUOTE_COLUMNS} FROM quotes WHERE id = $1 AND tenant_id = $2"
+        ))
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("Quote", id.to_string()))?;
+
+        build_quote(&row)
+    }
+
+    async fn list_quotes_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {QUOTE_COLUMNS} FROM quotes \
+             WHERE flow_id = $1 AND tenant_id = $2 \
+             ORDER BY created_at DESC"
+        ))
+        .bind(flow_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter().map(build_quote).collect()
+    }
+
+    async fn list_quotes_for_customer(
+        &self,
+        organization: &OrganizationId,
+        customer_id: CustomerId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {QUOTE_COLUMNS} FROM quotes \
+             WHERE customer_id = $1 AND tenant_id = $2 \
+             ORDER BY created_at DESC"
+        ))
+        .bind(customer_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter().map(build_quote).collect()
+    }
+
+    async fn update_quote(&self, quote: Quote) -> Result<Quote, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let result = sqlx::query(
+            "UPDATE quotes \
+             SET submission = $2, \
+                 results = $3, \
+                 total = $4, \
+                 validity_days = $5, \
+                 updated_at = $6 \
+             WHERE id = $1 AND status = $7 AND tenant_id = $8",
+        )
+        .bind(quote.id.into_uuid())
+        .bind(sqlx::types::Json(&quote.submission))
+        .bind(sqlx::types::Json(&quote.results))
+        .bind(quote.total)
+        .bind(quote.validity_days as i32)
+        .bind(quote.updated_at)
+        .bind(QuoteStatus::Draft.as_str())
+        .bind(quote.organization_id.as_str())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::conflict(format!(
+                "Quote {} is no longer a draft",
+                quote.id
+            )));
+        }
+
+        Ok(quote)
+    }
+
+    async fn list_revisions(
+        &self,
+        organization: &OrganizationId,
+        root_id: QuoteId,
+    ) -> Result<Vec<Quote>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {QUOTE_COLUMNS} FROM quotes \
+             WHERE root_id = $1 AND tenant_id = $2 \
+             ORDER BY revision"
+        ))
+        .bind(root_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter().map(build_quote).collect()
+    }
+
+    async fn apply_status_change(
+        &self,
+        quote: Quote,
+        change: QuoteStatusChange,
+    ) -> Result<Quote, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        // A savepoint when the repository is bound to a transaction.
+        let mut tx = conn
+            .begin()
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        // Guard on the previous status so concurrent transitions cannot both apply.
+        let result = sqlx::query(
+            "UPDATE quotes \
+             SET status = $2, \
+                 valid_until = $3, \
+                 updated_at = $4 \
+             WHERE id = $1 AND status = $5 AND tenant_id = $6",
+        )
+        .bind(quote.id.into_uuid())
+        .bind(quote.status.as_str())
+        .bind(quote.valid_until)
+        .bind(quote.updated_at)
+        .bind(change.from.as_str())
+        .bind(quote.organization_id.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::conflict(format!(
+                "Quote {} is no longer {}",
+                quote.id, change.from
+            )));
+        }
+
+        sqlx::query(
+            "INSERT INTO quote_status_history (id, quote_id, from_status, to_status, reason, changed_at) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(Uuid::now_v7())
+        .bind(change.quote_id.into_uuid())
+        .bind(change.from.as_str())
+        .bind(change.to.as_str())
+        .bind(&change.reason)
+        .bind(change.changed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(quote)
+    }
+
+    async fn list_status_history(
+        &self,
+        organization: &OrganizationId,
+        id: QuoteId,
+    ) -> Result<Vec<QuoteStatusChange>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(
+            "SELECT h.quote_id, h.from_status, h.to_status, h.reason, h.changed_at \
+             FROM quote_status_history h \
+             JOIN quotes q ON q.id = h.quote_id \
+             WHERE h.quote_id = $1 AND q.tenant_id = $2 \
+             ORDER BY h.changed_at, h.id",
+        )
+        .bind(id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(QuoteStatusChange {
+                    quote_id: QuoteId::from_uuid(row.get("quote_id")),
+                    from: row.get::<String, _>("from_status").parse()?,
+                    to: row.get::<String, _>("to_status").parse()?,
+                    reason: row.get("reason"),
+                    changed_at: row.get("changed_at"),
+                })
+            })
+            .collect()
+    }
+
+    async fn list_overdue_quotes(&self, now: DateTime<Utc>) -> Result<Vec<Quote>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {QUOTE_COLUMNS} FROM quotes \
+             WHERE status = $1 AND valid_until <= $2 \
+             ORDER BY valid_until"
+        ))
+        .bind(QuoteStatus::Sent.as_str())
+        .bind(now)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        rows.iter().map(build_quote).collect()
+    }
+}
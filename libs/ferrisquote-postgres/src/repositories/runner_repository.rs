@@ -0,0 +1,232 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    estimator::entities::{ids::EstimatorId, submission::SubmissionData},
+    flows::entities::ids::{FlowId, StepId},
+    organization::entities::ids::OrganizationId,
+    runner::{
+        entities::{
+            ids::{RunnerSessionId, ShareLinkId},
+            session::RunnerSession,
+            share_link::ShareLink,
+        },
+        ports::{RunnerSessionRepository, ShareLinkRepository},
+    },
+    transaction::ports::Transactional,
+};
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::unit_of_work::{ConnectionSource, PostgresTransaction};
+
+const SHARE_LINK_COLUMNS: &str =
+    "id, tenant_id, flow_id, estimator_id, token, created_at, expires_at, revoked_at";
+
+/// Stores share links and the anonymous sessions opened through them.
+#[derive(Clone)]
+pub struct PostgresRunnerRepository {
+    source: ConnectionSource,
+}
+
+impl PostgresRunnerRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self {
+            source: ConnectionSource::Pool(pool),
+        }
+    }
+}
+
+impl Transactional<PostgresTransaction> for PostgresRunnerRepository {
+    fn in_transaction(&self, transaction: &PostgresTransaction) -> Self {
+        Self {
+            source: transaction.source(),
+        }
+    }
+}
+
+fn build_share_link(row: &sqlx::postgres::PgRow) -> ShareLink {
+    ShareLink {
+        id: ShareLinkId::from_uuid(row.get("id")),
+        organization_id: OrganizationId::new(row.get::<String, _>("tenant_id")),
+        flow_id: FlowId::from_uuid(row.get("flow_id")),
+        estimator_id: EstimatorId::from_uuid(row.get("estimator_id")),
+        token: row.get("token"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+impl ShareLinkRepository for PostgresRunnerRepository {
+    async fn create_share_link(&self, link: ShareLink) -> Result<ShareLink, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query(
+            "INSERT INTO share_links (id, tenant_id, flow_id, estimator_id, token, created_at, expires_at, revoked_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(link.id.into_uuid())
+        .bind(link.organization_id.as_str())
+        .bind(link.flow_id.into_uuid())
+        .bind(link.estimator_id.into_uuid())
+        .bind(&link.token)
+        .bind(link.created_at)
+        .bind(link.expires_at)
+        .bind(link.revoked_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(link)
+    }
+
+    async fn get_share_link_by_token(&self, token: &str) -> Result<ShareLink, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(&format!(
+            "SELECT {SHARE_LINK_COLUMNS} FROM share_links WHERE token = $1"
+        ))
+        .bind(token)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("ShareLink", token))?;
+
+        Ok(build_share_link(&row))
+    }
+
+    async fn list_share_links_for_flow(
+        &self,
+        organization: &OrganizationId,
+        flow_id: FlowId,
+    ) -> Result<Vec<ShareLink>, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {SHARE_LINK_COLUMNS} FROM share_links \
+             WHERE flow_id = $1 AND tenant_id = $2 \
+             ORDER BY created_at DESC"
+        ))
+        .bind(flow_id.into_uuid())
+        .bind(organization.as_str())
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(rows.iter().map(build_share_link).collect())
+    }
+
+    async fn revoke_share_link(
+        &self,
+        organization: &OrganizationId,
+        id: ShareLinkId,
+        at: DateTime<Utc>,
+    ) -> Result<ShareLink, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        // Revoking twice keeps the original revocation time.
+        let row = sqlx::query(&format!(
+            "UPDATE share_links SET revoked_at = COALESCE(revoked_at, $2) \
+             WHERE id = $1 AND tenant_id = $3 \
+             RETURNING {SHARE_LINK_COLUMNS}"
+        ))
+        .bind(id.into_uuid())
+        .bind(at)
+        .bind(organization.as_str())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("ShareLink", id.to_string()))?;
+
+        Ok(build_share_link(&row))
+    }
+}
+
+impl RunnerSessionRepository for PostgresRunnerRepository {
+    async fn create_session(&self, session: RunnerSession) -> Result<RunnerSession, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let completed: Vec<Uuid> = session
+            .completed_steps
+            .iter()
+            .map(|s| s.into_uuid())
+            .collect();
+
+        sqlx::query(
+            "INSERT INTO runner_sessions (id, tenant_id, share_link_id, submission, completed_steps, created_at, updated_at) \
+             SELECT $1, tenant_id, $2, $3, $4, $5, $6 FROM share_links WHERE id = $2",
+        )
+        .bind(session.id.into_uuid())
+        .bind(session.share_link_id.into_uuid())
+        .bind(sqlx::types::Json(&session.submission))
+        .bind(&completed)
+        .bind(session.created_at)
+        .bind(session.updated_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(session)
+    }
+
+    async fn get_session(&self, id: RunnerSessionId) -> Result<RunnerSession, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query(
+            "SELECT id, share_link_id, submission, completed_steps, created_at, updated_at \
+             FROM runner_sessions WHERE id = $1",
+        )
+        .bind(id.into_uuid())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?
+        .ok_or_else(|| DomainError::not_found("RunnerSession", id.to_string()))?;
+
+        let submission: sqlx::types::Json<SubmissionData> = row
+            .try_get("submission")
+            .map_err(|e| DomainError::internal(format!("Failed to decode submission: {e}")))?;
+        let completed: Vec<Uuid> = row.get("completed_steps");
+
+        Ok(RunnerSession {
+            id: RunnerSessionId::from_uuid(row.get("id")),
+            share_link_id: ShareLinkId::from_uuid(row.get("share_link_id")),
+            submission: submission.0,
+            completed_steps: completed.into_iter().map(StepId::from_uuid).collect(),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    async fn update_session(&self, session: RunnerSession) -> Result<RunnerSession, DomainError> {
+        let mut conn = self.source.acquire().await?;
+        let completed: Vec<Uuid> = session
+            .completed_steps
+            .iter()
+            .map(|s| s.into_uuid())
+            .collect();
+
+        let result = sqlx::query(
+            "UPDATE runner_sessions \
+             SET submission = $2, \
+                 completed_steps = $3, \
+                 updated_at = $4 \
+             WHERE id = $1",
+        )
+        .bind(session.id.into_uuid())
+        .bind(sqlx::types::Json(&session.submission))
+        .bind(&completed)
+        .bind(session.updated_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::not_found(
+                "RunnerSession",
+                session.id.to_string(),
+            ));
+        }
+
+        Ok(session)
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use ferrisquote_domain::domain::{
+    error::DomainError,
+    transaction::ports::{Transaction, UnitOfWork},
+};
+use sqlx::{PgConnection, PgPool, Postgres, pool::PoolConnection};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+type PgTransaction = sqlx::Transaction<'static, Postgres>;
+
+/// Opens Postgres transactions on a pool.
+#[derive(Clone)]
+pub struct PostgresUnitOfWork {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresUnitOfWork {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool: Arc::new(pool),
+        }
+    }
+
+    pub fn with_pool(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+impl UnitOfWork for PostgresUnitOfWork {
+    type Transaction = PostgresTransaction;
+
+    async fn begin(&self) -> Result<PostgresTransaction, DomainError> {
+        let transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))?;
+
+        Ok(PostgresTransaction {
+            connection: Arc::new(Mutex::new(Some(transaction))),
+        })
+    }
+}
+
+/// A `sqlx::Transaction` shared by the repositories bound to it.
+///
+/// Repositories run their queries one at a time on its connection. Once committed or
+/// rolled back, the repositories still bound to it fail with a repository error.
+pub struct PostgresTransaction {
+    connection: Arc<Mutex<Option<PgTransaction>>>,
+}
+
+impl PostgresTransaction {
+    /// Where repositories bound to this transaction run their queries.
+    pub(crate) fn source(&self) -> ConnectionSource {
+        ConnectionSource::Transaction(self.connection.clone())
+    }
+
+    async fn finish(&self) -> Result<PgTransaction, DomainError> {
+        self.connection
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| DomainError::repository("Transaction is already finished"))
+    }
+}
+
+impl Transaction for PostgresTransaction {
+    async fn commit(self) -> Result<(), DomainError> {
+        self.finish()
+            .await?
+            .commit()
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))
+    }
+
+    async fn rollback(self) -> Result<(), DomainError> {
+        self.finish()
+            .await?
+            .rollback()
+            .await
+            .map_err(|e| DomainError::repository(e.to_string()))
+    }
+}
+
+/// Where a repository runs its queries: the pool, or the connection of a transaction.
+#[derive(Clone)]
+pub(crate) enum ConnectionSource {
+    Pool(Arc<PgPool>),
+    Transaction(Arc<Mutex<Option<PgTransaction>>>),
+}
+
+impl ConnectionSource {
+    /// A connection for the queries of one repository call.
+    pub(crate) async fn acquire(&self) -> Result<Connection<'_>, DomainError> {
+        match self {
+            Self::Pool(pool) => pool
+                .acquire()
+                .await
+                .map(Connection::Pooled)
+                .map_err(|e| DomainError::repository(e.to_string())),
+            Self::Transaction(transaction) => {
+                MutexGuard::try_map(transaction.lock().await, Option::as_mut)
+                    .map(Connection::Transaction)
+                    .map_err(|_| DomainError::repository("Transaction is already finished"))
+            }
+        }
+    }
+}
+
+pub(crate) enum Connection<'a> {
+    Pooled(PoolConnection<Postgres>),
+    Transaction(MappedMutexGuard<'a, PgTransaction>),
+}
+
+impl Deref for Connection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Self::Pooled(connection) => connection,
+            Self::Transaction(transaction) => transaction,
+        }
+    }
+}
+
+impl DerefMut for Connection<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Self::Pooled(connection) => connection,
+            Self::Transaction(transaction) => transaction,
+        }
+    }
+}